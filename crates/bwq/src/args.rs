@@ -16,7 +16,7 @@ pub enum Commands {
     /// lint files, directories, or query strings
     #[command(name = "check")]
     Check {
-        /// Files or directories to check (ignored if --query is used) [default: .]
+        /// Files or directories to check, or - for stdin (ignored if --query is used) [default: .]
         files: Vec<PathBuf>,
 
         /// Lint a query string directly (instead of files)
@@ -27,7 +27,7 @@ pub enum Commands {
         #[arg(long)]
         no_warnings: bool,
 
-        /// Output format (text or json)
+        /// Output format (text, json or sarif)
         #[arg(long, default_value = "text")]
         output_format: String,
 
@@ -35,11 +35,111 @@ pub enum Commands {
         #[arg(long)]
         exit_zero: bool,
 
-        /// File extensions to check (can be used multiple times)
-        #[arg(long = "extension", short = 'e', default_values = ["bwq"])]
+        /// File extensions to check (can be used multiple times) [default: `extensions` from
+        /// bwq.toml, or bwq]
+        #[arg(long = "extension", short = 'e')]
+        extensions: Vec<String>,
+
+        /// Apply safe fixes to the checked files
+        #[arg(long, conflicts_with = "query")]
+        fix: bool,
+
+        /// Apply unsafe fixes as well as safe ones (implies --fix)
+        #[arg(long, conflicts_with = "query")]
+        unsafe_fixes: bool,
+
+        /// How queries are stored in each input: query (one per file), csv, jsonl or bw-json
+        /// (a Brandwatch API dump of queries)
+        #[arg(long, default_value = "query")]
+        input_format: String,
+
+        /// Column (csv) or JSON pointer (jsonl, bw-json) holding each record's query [default:
+        /// query, /query or /booleanQuery]
+        #[arg(long)]
+        query_field: Option<String>,
+
+        /// Column or JSON pointer identifying each record in diagnostics [default: id or /id,
+        /// falling back to the row, line or position]
+        #[arg(long)]
+        id_field: Option<String>,
+
+        /// Re-check files whenever they change, reporting the files whose diagnostics changed
+        #[arg(long, short = 'w', conflicts_with_all = ["query", "query_field", "id_field"])]
+        watch: bool,
+    },
+
+    /// format files into the canonical query layout
+    #[command(name = "format")]
+    Format {
+        /// Files or directories to format [default: .]
+        files: Vec<PathBuf>,
+
+        /// Report files that would be reformatted instead of writing them
+        #[arg(long)]
+        check: bool,
+
+        /// Print a diff of the changes instead of writing them
+        #[arg(long)]
+        diff: bool,
+
+        /// Maximum line width before expressions are broken over several lines
+        #[arg(long, default_value_t = 80)]
+        line_width: usize,
+
+        /// File extensions to format (can be used multiple times) [default: `extensions` from
+        /// bwq.toml, or bwq]
+        #[arg(long = "extension", short = 'e')]
         extensions: Vec<String>,
     },
 
+    /// run queries against the documents in their `.test.toml` fixtures
+    #[command(name = "test")]
+    Test {
+        /// Query files or directories to test [default: .]
+        files: Vec<PathBuf>,
+
+        /// File extensions of query files (can be used multiple times) [default: `extensions`
+        /// from bwq.toml, or bwq]
+        #[arg(long = "extension", short = 'e')]
+        extensions: Vec<String>,
+    },
+
+    /// report the size and complexity of queries, against any configured limits
+    #[command(name = "stats")]
+    Stats {
+        /// Files or directories to measure (ignored if --query is used) [default: .]
+        files: Vec<PathBuf>,
+
+        /// Measure a query string directly (instead of files)
+        #[arg(long, short = 'q')]
+        query: Option<String>,
+
+        /// Output format (text or json)
+        #[arg(long, default_value = "text")]
+        output_format: String,
+
+        /// File extensions to measure (can be used multiple times) [default: `extensions` from
+        /// bwq.toml, or bwq]
+        #[arg(long = "extension", short = 'e')]
+        extensions: Vec<String>,
+    },
+
+    /// explain a diagnostic code or rule name
+    #[command(name = "rule")]
+    Rule {
+        /// Code (E015), name (mixed-and-or) or check (short-wildcard) of the rule to explain
+        #[arg(required_unless_present = "all")]
+        rule: Option<String>,
+
+        /// Explain every rule
+        #[arg(long, conflicts_with = "rule")]
+        all: bool,
+
+        /// Output format (text or json)
+        #[arg(long, default_value = "text")]
+        output_format: String,
+    },
+
     /// Show example queries
     Examples,
 
@@ -6,22 +6,58 @@ use rayon::prelude::*;
 
 use crate::{
     ExitStatus,
-    output::{FileResults, OutputFormat, Printer},
+    input::{InputFormat, InputOptions, Record},
+    output::{FileResults, OutputFormat, Printer, Source},
 };
-use bwq_linter::analyze_query;
+use bwq_linter::{
+    AnalysisResult, BrandwatchLinter,
+    config::{ConfigResolver, RuleSettings},
+    fix::fix_source,
+};
+
+const DEFAULT_EXTENSIONS: [&str; 1] = ["bwq"];
+
+/// the path that reads from standard input
+const STDIN_PATH: &str = "-";
+
+/// which fixes `bwq check` writes back to the checked files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixMode {
+    Off,
+    Safe,
+    Unsafe,
+}
+
+impl FixMode {
+    pub fn from_flags(fix: bool, unsafe_fixes: bool) -> Self {
+        if unsafe_fixes {
+            FixMode::Unsafe
+        } else if fix {
+            FixMode::Safe
+        } else {
+            FixMode::Off
+        }
+    }
+}
 
 pub fn run_check(
     files: Vec<PathBuf>,
     query: Option<String>,
     no_warnings: bool,
     output_format: String,
-    extensions: Vec<String>,
     exit_zero: bool,
+    fix_mode: FixMode,
+    input: InputOptions,
 ) -> Result<ExitStatus, anyhow::Error> {
     let show_warnings = !no_warnings;
 
+    let mut resolver = ConfigResolver::new();
+
     if let Some(query_str) = query {
+        let settings = resolver.settings_for(&std::env::current_dir()?)?;
+        let mut linter = BrandwatchLinter::with_settings(settings);
         Ok(check_single_query_string(
+            &mut linter,
             &query_str,
             show_warnings,
             &output_format,
@@ -33,8 +69,27 @@ pub fn run_check(
         } else {
             files
         };
+        let (stdin, paths): (Vec<_>, Vec<_>) = target_files
+            .into_iter()
+            .partition(|path| path.as_os_str() == STDIN_PATH);
 
-        let results = check_files(&target_files, &extensions)?;
+        if fix_mode != FixMode::Off && (!stdin.is_empty() || input.format != InputFormat::Query) {
+            anyhow::bail!("Fixes can only be applied to query files, not to stdin or records");
+        }
+
+        let mut results = if paths.is_empty() {
+            FileResults::new()
+        } else {
+            check_files(&paths, &input, &mut resolver, fix_mode)?
+        };
+        if !stdin.is_empty() {
+            let content = std::io::read_to_string(std::io::stdin())?;
+            let settings = resolver.settings_for(&std::env::current_dir()?)?;
+            collect_results(
+                &mut results,
+                check_content(&content, None, &settings, &input),
+            );
+        }
 
         let printer = Printer::new(OutputFormat::from(output_format.as_str()), show_warnings);
         printer.print_file_results(&results);
@@ -47,7 +102,16 @@ pub fn run_check(
     }
 }
 
-fn check_files(paths: &[PathBuf], extensions: &[String]) -> Result<FileResults, anyhow::Error> {
+/// the outcome of checking one query, with the number of fixes written back to its file. a
+/// query that couldn't be read has already been reported
+type Checked = Result<(Source, AnalysisResult, String, usize), ()>;
+
+fn check_files(
+    paths: &[PathBuf],
+    input: &InputOptions,
+    resolver: &mut ConfigResolver,
+    fix_mode: FixMode,
+) -> Result<FileResults, anyhow::Error> {
     // Validate that all paths exist
     for file_path in paths {
         if !file_path.exists() {
@@ -55,47 +119,153 @@ fn check_files(paths: &[PathBuf], extensions: &[String]) -> Result<FileResults,
         }
     }
 
-    let files = discover_files(paths, extensions);
-
+    let files = discover_configured_files(paths, &input.extensions(), resolver)?;
     if files.is_empty() {
-        eprintln!(
-            "Warning: No files found that have the extension(s): {}",
-            extensions.join(", ")
-        );
         return Ok(FileResults::new());
     }
 
-    let results: Vec<_> = files
+    let files = files
+        .into_iter()
+        .map(|file_path| {
+            let settings = resolver.settings_for(&file_path)?;
+            Ok((file_path, settings))
+        })
+        .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+    let checked: Vec<Checked> = files
         .par_iter()
-        .map(|file_path| match fs::read_to_string(file_path) {
-            Ok(content) => {
-                let query = content.trim();
-                let analysis = analyze_query(query);
-                Ok((file_path.clone(), analysis, query.to_string()))
+        .flat_map_iter(|(file_path, settings)| {
+            if input.format != InputFormat::Query {
+                return match fs::read_to_string(file_path) {
+                    Ok(content) => check_content(&content, Some(file_path), settings, input),
+                    Err(e) => {
+                        eprintln!("Error reading file {}: {}", file_path.display(), e);
+                        vec![Err(())]
+                    }
+                };
+            }
+
+            vec![match lint_file(file_path, settings.clone(), fix_mode) {
+                Ok((analysis, query, fixed)) => {
+                    Ok((Source::File(file_path.clone()), analysis, query, fixed))
+                }
+                Err(e) => {
+                    eprintln!("Error reading file {}: {}", file_path.display(), e);
+                    Err(())
+                }
+            }]
+        })
+        .collect();
+
+    let mut results = FileResults::new();
+    collect_results(&mut results, checked);
+    Ok(results)
+}
+
+/// lint the queries in the contents of `file`, or of stdin when it is `None`
+fn check_content(
+    content: &str,
+    file: Option<&Path>,
+    settings: &RuleSettings,
+    input: &InputOptions,
+) -> Vec<Checked> {
+    let mut linter = BrandwatchLinter::with_settings(settings.clone());
+    let name = file.map_or("<stdin>".to_string(), |file| file.display().to_string());
+
+    if input.format == InputFormat::Query {
+        let analysis = linter.analyze(content);
+        return vec![Ok((Source::Stdin, analysis, content.to_string(), 0))];
+    }
+
+    let records = match input.read_records(content) {
+        Ok(records) => records,
+        Err(e) => {
+            eprintln!("Error reading {name}: {e:#}");
+            return vec![Err(())];
+        }
+    };
+
+    records
+        .into_iter()
+        .map(|record| match record {
+            Ok(Record { id, query }) => {
+                let analysis = linter.analyze(&query);
+                let source = Source::Record {
+                    file: file.map(Path::to_path_buf),
+                    id,
+                };
+                Ok((source, analysis, query, 0))
             }
             Err(e) => {
-                eprintln!("Error reading file {}: {}", file_path.display(), e);
-                Err(file_path.clone())
+                eprintln!("Error reading {name}: {e:#}");
+                Err(())
             }
         })
-        .collect();
+        .collect()
+}
+
+fn collect_results(results: &mut FileResults, checked: Vec<Checked>) {
+    for outcome in checked {
+        match outcome {
+            Ok((source, analysis, query, fixed)) => {
+                results.fixed += fixed;
+                results.successful.push((source, analysis, query));
+            }
+            Err(()) => results.read_errors += 1,
+        }
+    }
+}
 
-    let read_errors = results.iter().filter(|r| r.is_err()).count();
-    let successful = results.into_iter().filter_map(|r| r.ok()).collect();
+/// lint a file, applying fixes first unless `fix_mode` is off. returns the analysis, the query
+/// that was analysed and the number of fixes written back
+pub(crate) fn lint_file(
+    file_path: &Path,
+    settings: RuleSettings,
+    fix_mode: FixMode,
+) -> std::io::Result<(AnalysisResult, String, usize)> {
+    let content = fs::read_to_string(file_path)?;
+    let mut linter = BrandwatchLinter::with_settings(settings);
+    let (content, fixed) = if fix_mode == FixMode::Off {
+        (content, 0)
+    } else {
+        fix_file(&mut linter, file_path, content, fix_mode)
+    };
 
-    Ok(FileResults {
-        successful,
-        read_errors,
-    })
+    // the untrimmed contents, so that spans are positions in the file
+    let analysis = linter.analyze(&content);
+    Ok((analysis, content, fixed))
+}
+
+/// apply fixes to a file's contents and write them back, returning the new contents and the
+/// number of fixes applied. the file is left alone if it can't be written
+fn fix_file(
+    linter: &mut BrandwatchLinter,
+    file_path: &Path,
+    content: String,
+    fix_mode: FixMode,
+) -> (String, usize) {
+    let result = fix_source(linter, &content, fix_mode == FixMode::Unsafe);
+    if result.applied == 0 {
+        return (content, 0);
+    }
+
+    match fs::write(file_path, &result.output) {
+        Ok(()) => (result.output, result.applied),
+        Err(e) => {
+            eprintln!("Error writing file {}: {}", file_path.display(), e);
+            (content, 0)
+        }
+    }
 }
 
 fn check_single_query_string(
+    linter: &mut BrandwatchLinter,
     query: &str,
     show_warnings: bool,
     output_format: &str,
     exit_zero: bool,
 ) -> ExitStatus {
-    let analysis = analyze_query(query);
+    let analysis = linter.analyze(query);
     let printer = Printer::new(OutputFormat::from(output_format), show_warnings);
     printer.print_analysis(&analysis);
 
@@ -114,6 +284,57 @@ fn matches_extensions(file_path: &Path, extensions: &[String]) -> bool {
     }
 }
 
+/// discover the files under `paths` with the extensions given on the command line or, when
+/// there are none, the ones configured for each path; warns when nothing is found
+pub(crate) fn discover_configured_files(
+    paths: &[PathBuf],
+    extensions: &[String],
+    resolver: &mut ConfigResolver,
+) -> Result<Vec<PathBuf>, anyhow::Error> {
+    let (files, searched_extensions) = find_configured_files(paths, extensions, resolver)?;
+
+    if files.is_empty() {
+        eprintln!(
+            "Warning: No files found that have the extension(s): {}",
+            searched_extensions.join(", ")
+        );
+    }
+
+    Ok(files)
+}
+
+/// [`discover_configured_files`] without the warning, also returning the extensions searched for
+pub(crate) fn find_configured_files(
+    paths: &[PathBuf],
+    extensions: &[String],
+    resolver: &mut ConfigResolver,
+) -> Result<(Vec<PathBuf>, Vec<String>), anyhow::Error> {
+    let mut files = Vec::new();
+    let mut searched_extensions: Vec<String> = Vec::new();
+
+    for path in paths {
+        let path_extensions = if extensions.is_empty() {
+            resolver.extensions_for(path)?.unwrap_or_else(|| {
+                DEFAULT_EXTENSIONS
+                    .iter()
+                    .map(|ext| ext.to_string())
+                    .collect()
+            })
+        } else {
+            extensions.to_vec()
+        };
+
+        files.extend(discover_files(std::slice::from_ref(path), &path_extensions));
+        for ext in path_extensions {
+            if !searched_extensions.contains(&ext) {
+                searched_extensions.push(ext);
+            }
+        }
+    }
+
+    Ok((files, searched_extensions))
+}
+
 fn discover_files(paths: &[PathBuf], extensions: &[String]) -> Vec<PathBuf> {
     let mut discovered_files = Vec::new();
 
@@ -137,3 +358,20 @@ fn discover_files(paths: &[PathBuf], extensions: &[String]) -> Vec<PathBuf> {
 
     discovered_files
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spans_are_positions_in_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("query.bwq");
+        fs::write(&file, "\n\n   apple and banana\n").unwrap();
+
+        let (analysis, _, _) = lint_file(&file, RuleSettings::default(), FixMode::Off).unwrap();
+        let start = &analysis.errors[0].span().start;
+        assert_eq!(analysis.errors[0].code(), "E004");
+        assert_eq!((start.line, start.column), (3, 10));
+    }
+}
@@ -0,0 +1,135 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+use similar::TextDiff;
+
+use crate::{
+    ExitStatus,
+    commands::{check::discover_configured_files, pluralize_files},
+};
+use bwq_linter::{
+    config::ConfigResolver,
+    formatter::{FormatOptions, format_source},
+};
+
+enum FormatResult {
+    Unchanged,
+    Changed { original: String, formatted: String },
+    Failed(String),
+}
+
+pub fn run_format(
+    files: Vec<PathBuf>,
+    check: bool,
+    diff: bool,
+    line_width: usize,
+    extensions: Vec<String>,
+) -> Result<ExitStatus, anyhow::Error> {
+    let target_files = if files.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        files
+    };
+
+    for file_path in &target_files {
+        if !file_path.exists() {
+            anyhow::bail!("Path does not exist: {}", file_path.display());
+        }
+    }
+
+    let mut files =
+        discover_configured_files(&target_files, &extensions, &mut ConfigResolver::new())?;
+    files.sort();
+
+    if files.is_empty() {
+        return Ok(ExitStatus::Success);
+    }
+
+    let options = FormatOptions {
+        line_width,
+        ..FormatOptions::default()
+    };
+
+    let results: Vec<_> = files
+        .par_iter()
+        .map(|file_path| (file_path, format_file(file_path, &options)))
+        .collect();
+
+    let mut changed = 0;
+    let mut unchanged = 0;
+    let mut failed = 0;
+
+    for (file_path, result) in results {
+        match result {
+            FormatResult::Unchanged => unchanged += 1,
+            FormatResult::Changed {
+                original,
+                formatted,
+            } => {
+                changed += 1;
+                let path = file_path.display().to_string();
+
+                if diff {
+                    print!(
+                        "{}",
+                        TextDiff::from_lines(&original, &formatted)
+                            .unified_diff()
+                            .header(&path, &path)
+                    );
+                } else if check {
+                    println!("Would reformat: {path}");
+                } else if let Err(e) = fs::write(file_path, formatted) {
+                    eprintln!("Error writing file {path}: {e}");
+                    changed -= 1;
+                    failed += 1;
+                }
+            }
+            FormatResult::Failed(message) => {
+                eprintln!("Failed to format {}: {}", file_path.display(), message);
+                failed += 1;
+            }
+        }
+    }
+
+    let verb = if check || diff {
+        "would be reformatted"
+    } else {
+        "reformatted"
+    };
+    let mut summary = vec![format!("{} {verb}", pluralize_files(changed))];
+    if unchanged > 0 {
+        summary.push(format!("{} already formatted", pluralize_files(unchanged)));
+    }
+    if failed > 0 {
+        summary.push(format!(
+            "{} could not be formatted",
+            pluralize_files(failed)
+        ));
+    }
+    eprintln!("{}", summary.join(", "));
+
+    Ok(if failed > 0 {
+        ExitStatus::Error
+    } else if changed > 0 && (check || diff) {
+        ExitStatus::LintFailure
+    } else {
+        ExitStatus::Success
+    })
+}
+
+fn format_file(file_path: &Path, options: &FormatOptions) -> FormatResult {
+    let original = match fs::read_to_string(file_path) {
+        Ok(content) => content,
+        Err(e) => return FormatResult::Failed(e.to_string()),
+    };
+
+    match format_source(&original, options) {
+        Ok(formatted) if formatted == original => FormatResult::Unchanged,
+        Ok(formatted) => FormatResult::Changed {
+            original,
+            formatted,
+        },
+        Err(e) => FormatResult::Failed(e.to_string()),
+    }
+}
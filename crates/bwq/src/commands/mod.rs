@@ -1,3 +1,17 @@
 pub(crate) mod check;
 pub(crate) mod examples;
+pub(crate) mod format;
+pub(crate) mod rule;
 pub(crate) mod server;
+pub(crate) mod stats;
+pub(crate) mod test;
+pub(crate) mod watch;
+
+/// e.g. `1 file` or `3 files`, for summaries
+pub(crate) fn pluralize_files(count: usize) -> String {
+    if count == 1 {
+        "1 file".to_string()
+    } else {
+        format!("{count} files")
+    }
+}
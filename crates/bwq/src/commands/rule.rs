@@ -0,0 +1,58 @@
+use crate::{ExitStatus, output::OutputFormat};
+use bwq_linter::registry::{RULES, RuleInfo, rules};
+
+pub fn run_rule(
+    code: Option<String>,
+    all: bool,
+    output_format: String,
+) -> Result<ExitStatus, anyhow::Error> {
+    let rules: Vec<&RuleInfo> = if all {
+        RULES.iter().collect()
+    } else {
+        let code = code.unwrap_or_default();
+        let rules = rules(&code);
+        if rules.is_empty() {
+            anyhow::bail!("Unknown rule: {code}");
+        }
+        rules
+    };
+
+    match OutputFormat::from(output_format.as_str()) {
+        OutputFormat::Json => {
+            // a check raising several codes is explained like `--all`, as a list
+            let output = if rules.len() == 1 && !all {
+                rules[0].to_json()
+            } else {
+                serde_json::Value::Array(rules.iter().map(|rule| rule.to_json()).collect())
+            };
+            println!("{}", serde_json::to_string_pretty(&output).unwrap());
+        }
+        _ => {
+            let text: Vec<String> = rules.iter().map(|rule| format_rule(rule)).collect();
+            print!("{}", text.join("\n"));
+        }
+    }
+
+    Ok(ExitStatus::Success)
+}
+
+fn format_rule(rule: &RuleInfo) -> String {
+    let mut text = format!(
+        "{} ({}): {}\n\nCategory: {}\nDefault severity: {}\n\n{}\n",
+        rule.code,
+        rule.name,
+        rule.summary,
+        rule.category.as_str(),
+        rule.severity.as_str(),
+        rule.explanation
+    );
+
+    if !rule.bad.is_empty() {
+        text.push_str(&format!(
+            "\nBad:\n  {}\n\nGood:\n  {}\n",
+            rule.bad, rule.good
+        ));
+    }
+
+    text
+}
@@ -0,0 +1,149 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::{ExitStatus, commands::check::discover_configured_files, output::OutputFormat};
+use bwq_linter::{
+    config::ConfigResolver,
+    lexer::Lexer,
+    metrics::{Limits, QueryMetrics},
+    parser::Parser,
+};
+
+/// the metrics of one query, with the limits that apply to it
+struct Stats {
+    file: Option<PathBuf>,
+    metrics: QueryMetrics,
+    limits: Limits,
+}
+
+pub fn run_stats(
+    files: Vec<PathBuf>,
+    query: Option<String>,
+    output_format: String,
+    extensions: Vec<String>,
+) -> Result<ExitStatus, anyhow::Error> {
+    let mut resolver = ConfigResolver::new();
+    let output_format = OutputFormat::from(output_format.as_str());
+
+    if let Some(query) = query {
+        let stats = Stats {
+            file: None,
+            metrics: metrics(&query),
+            limits: resolver.settings_for(&std::env::current_dir()?)?.limits,
+        };
+        match output_format {
+            OutputFormat::Json => {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&stats.to_json()).unwrap()
+                )
+            }
+            _ => print!("{}", stats.format()),
+        }
+        return Ok(ExitStatus::Success);
+    }
+
+    let target_files = if files.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        files
+    };
+    for file_path in &target_files {
+        if !file_path.exists() {
+            anyhow::bail!("Path does not exist: {}", file_path.display());
+        }
+    }
+
+    let mut files = discover_configured_files(&target_files, &extensions, &mut resolver)?;
+    files.sort();
+
+    let mut all_stats = Vec::new();
+    let mut read_errors = 0;
+    for file_path in files {
+        match fs::read_to_string(&file_path) {
+            Ok(content) => all_stats.push(Stats {
+                metrics: metrics(&content),
+                limits: resolver.settings_for(&file_path)?.limits,
+                file: Some(file_path),
+            }),
+            Err(e) => {
+                eprintln!("Error reading file {}: {}", file_path.display(), e);
+                read_errors += 1;
+            }
+        }
+    }
+
+    match output_format {
+        OutputFormat::Json => {
+            let output: Vec<_> = all_stats.iter().map(Stats::to_json).collect();
+            println!("{}", serde_json::to_string_pretty(&output).unwrap());
+        }
+        _ => {
+            let text: Vec<String> = all_stats.iter().map(Stats::format).collect();
+            print!("{}", text.join("\n"));
+        }
+    }
+
+    Ok(if read_errors > 0 {
+        ExitStatus::Error
+    } else {
+        ExitStatus::Success
+    })
+}
+
+fn metrics(content: &str) -> QueryMetrics {
+    let query = content.trim();
+    let (tokens, _) = Lexer::new(query).tokenize_with_errors();
+    QueryMetrics::new(&Parser::new(tokens).parse().query, query)
+}
+
+impl Stats {
+    /// one metric per line, with its limit when one is configured
+    fn format(&self) -> String {
+        let mut text = String::new();
+        let indent = if let Some(file) = &self.file {
+            text.push_str(&format!("{}\n", file.display()));
+            "  "
+        } else {
+            ""
+        };
+
+        for ((name, value), (_, limit)) in self
+            .metrics
+            .entries()
+            .into_iter()
+            .zip(self.limits.entries())
+        {
+            let label = name.replace('_', " ");
+            text.push_str(&format!("{indent}{label:<16}{value:>6}"));
+            match limit {
+                Some(limit) if value > limit => {
+                    text.push_str(&format!("  (exceeds the limit of {limit})"))
+                }
+                Some(limit) => text.push_str(&format!("  (limit {limit})")),
+                None => {}
+            }
+            text.push('\n');
+        }
+
+        text
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let limits: serde_json::Map<_, _> = self
+            .limits
+            .entries()
+            .into_iter()
+            .filter_map(|(name, limit)| Some((name.to_string(), limit?.into())))
+            .collect();
+
+        let mut json = serde_json::json!({
+            "metrics": self.metrics.to_json(),
+            "limits": limits,
+        });
+        if let Some(file) = &self.file {
+            json["filename"] = file.display().to_string().into();
+        }
+        json
+    }
+}
@@ -0,0 +1,161 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    ExitStatus,
+    commands::{check::discover_configured_files, pluralize_files},
+};
+use bwq_linter::{
+    config::ConfigResolver,
+    fixture::{CaseResult, Fixture, fixture_path},
+    lexer::Lexer,
+    parser::Parser,
+};
+
+#[derive(Default)]
+struct Summary {
+    passed: usize,
+    failed: usize,
+    errors: usize,
+}
+
+pub fn run_test(files: Vec<PathBuf>, extensions: Vec<String>) -> Result<ExitStatus, anyhow::Error> {
+    let target_files = if files.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        files
+    };
+
+    for file_path in &target_files {
+        if !file_path.exists() {
+            anyhow::bail!("Path does not exist: {}", file_path.display());
+        }
+    }
+
+    let mut files =
+        discover_configured_files(&target_files, &extensions, &mut ConfigResolver::new())?;
+    files.retain(|file_path| fixture_path(file_path).is_file());
+    files.sort();
+
+    if files.is_empty() {
+        eprintln!("No query tests found");
+        return Ok(ExitStatus::Success);
+    }
+
+    let mut summary = Summary::default();
+    for file_path in &files {
+        for failure in test_file(file_path, &mut summary) {
+            println!("{failure}");
+        }
+    }
+
+    let mut parts = vec![format!("{} passed", summary.passed)];
+    if summary.failed > 0 {
+        parts.push(format!("{} failed", summary.failed));
+    }
+    if summary.errors > 0 {
+        parts.push(format!(
+            "{} could not be run",
+            pluralize_files(summary.errors)
+        ));
+    }
+    eprintln!("{}", parts.join(", "));
+
+    Ok(if summary.errors > 0 {
+        ExitStatus::Error
+    } else if summary.failed > 0 {
+        ExitStatus::LintFailure
+    } else {
+        ExitStatus::Success
+    })
+}
+
+/// run the fixture of the query in `file_path`, returning a report of each case that failed
+fn test_file(file_path: &Path, summary: &mut Summary) -> Vec<String> {
+    // the untrimmed contents, so that spans are positions in the file
+    let query = match fs::read_to_string(file_path) {
+        Ok(query) => query,
+        Err(e) => {
+            eprintln!("Error reading file {}: {}", file_path.display(), e);
+            summary.errors += 1;
+            return Vec::new();
+        }
+    };
+
+    let fixture = match Fixture::load(&fixture_path(file_path)) {
+        Ok(fixture) => fixture,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            summary.errors += 1;
+            return Vec::new();
+        }
+    };
+
+    let (tokens, lexer_errors) = Lexer::new(&query).tokenize_with_errors();
+    let parsed = Parser::new(tokens).parse();
+    if !lexer_errors.is_empty() || !parsed.errors.is_empty() {
+        eprintln!(
+            "Error: {} has syntax errors; run `bwq check` for details",
+            file_path.display()
+        );
+        summary.errors += 1;
+        return Vec::new();
+    }
+
+    let mut failures = Vec::new();
+    for result in fixture.run(&parsed.query) {
+        if result.passed() {
+            summary.passed += 1;
+        } else {
+            summary.failed += 1;
+            failures.push(failure_report(file_path, &query, &result));
+        }
+    }
+    failures
+}
+
+fn failure_report(file_path: &Path, query: &str, result: &CaseResult) -> String {
+    let outcome = if result.should_match {
+        "did not match"
+    } else {
+        "matched"
+    };
+    let cause = result.explanation.cause();
+
+    format!(
+        "{}: {} {outcome}\n  caused by `{}` at {}:{}",
+        file_path.display(),
+        result.case.label,
+        cause.text(query),
+        cause.span.start.line,
+        cause.span.start.column
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_positions_are_in_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("brand.bwq");
+        fs::write(&file, "\n\n\napple AND juice\n").unwrap();
+        fs::write(
+            fixture_path(&file),
+            "[[should_match]]\ntext = \"apple pie\"\n",
+        )
+        .unwrap();
+
+        let mut summary = Summary::default();
+        let failures = test_file(&file, &mut summary);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(
+            failures,
+            [format!(
+                "{}: should_match[0] did not match\n  caused by `juice` at 4:11",
+                file.display()
+            )]
+        );
+    }
+}
@@ -0,0 +1,341 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use notify::{Event, EventKind, RecursiveMode, Watcher, event::ModifyKind};
+
+use crate::{
+    ExitStatus,
+    commands::check::{FixMode, discover_configured_files, find_configured_files, lint_file},
+    output::{OutputFormat, Printer},
+};
+use bwq_linter::config::{CONFIG_FILE_NAMES, ConfigResolver};
+
+/// editors often save in several steps, so changes are linted once they stop for this long
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// the diagnostics last reported for a file
+#[derive(Debug, Default, PartialEq)]
+struct FileReport {
+    lines: Vec<String>,
+    errors: usize,
+    warnings: usize,
+}
+
+/// what a check changed
+#[derive(Debug, Default, PartialEq)]
+struct Update {
+    /// the files whose diagnostics changed or that were removed
+    files: Vec<PathBuf>,
+    fixed: usize,
+}
+
+/// `bwq check --watch`: lint everything once, then re-lint the files that change and redraw a
+/// report of the ones whose diagnostics changed, leaving the last report on screen when none
+/// did. runs until interrupted
+pub fn run_watch(
+    files: Vec<PathBuf>,
+    no_warnings: bool,
+    output_format: String,
+    extensions: Vec<String>,
+    fix_mode: FixMode,
+) -> Result<ExitStatus, anyhow::Error> {
+    if OutputFormat::from(output_format.as_str()) != OutputFormat::Text {
+        anyhow::bail!("--watch only supports the text output format");
+    }
+
+    let paths = if files.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        files
+    };
+    for path in &paths {
+        if !path.exists() {
+            anyhow::bail!("Path does not exist: {}", path.display());
+        }
+    }
+
+    let (sender, events) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    for path in &paths {
+        // a file is watched through its directory, since editors often save by replacing it
+        let path = fs::canonicalize(path)?;
+        let directory = if path.is_file() {
+            path.parent().map(Path::to_path_buf).unwrap_or(path)
+        } else {
+            path
+        };
+        watcher.watch(&directory, RecursiveMode::Recursive)?;
+    }
+
+    let mut session = Session {
+        printer: Printer::new(OutputFormat::Text, !no_warnings),
+        resolver: ConfigResolver::new(),
+        reports: BTreeMap::new(),
+        paths,
+        extensions,
+        fix_mode,
+    };
+    discover_configured_files(&session.paths, &session.extensions, &mut session.resolver)?;
+    let update = session.check(None)?;
+    session.redraw(&update);
+
+    while let Ok(event) = events.recv() {
+        let mut changed = HashSet::new();
+        changed_paths(event, &mut changed);
+        while let Ok(event) = events.recv_timeout(DEBOUNCE) {
+            changed_paths(event, &mut changed);
+        }
+
+        if changed.is_empty() {
+            continue;
+        }
+        // saving a file with nothing new to report, including the save made by --fix, keeps
+        // the last report on screen
+        let update = session.check(Some(&changed))?;
+        if !update.files.is_empty() || update.fixed > 0 {
+            session.redraw(&update);
+        }
+    }
+
+    Ok(ExitStatus::Success)
+}
+
+/// add the paths whose contents changed; opening or reading a file, which linting does, is not
+/// a change
+fn changed_paths(event: notify::Result<Event>, changed: &mut HashSet<PathBuf>) {
+    let event = match event {
+        Ok(event) => event,
+        Err(e) => {
+            eprintln!("Error watching files: {e}");
+            return;
+        }
+    };
+
+    let is_change = match event.kind {
+        EventKind::Create(_) | EventKind::Remove(_) => true,
+        EventKind::Modify(kind) => !matches!(kind, ModifyKind::Metadata(_)),
+        EventKind::Any | EventKind::Access(_) | EventKind::Other => false,
+    };
+    if is_change {
+        changed.extend(event.paths);
+    }
+}
+
+struct Session {
+    printer: Printer,
+    resolver: ConfigResolver,
+    /// keyed by the path as discovered, so it reads as the user wrote it
+    reports: BTreeMap<PathBuf, FileReport>,
+    paths: Vec<PathBuf>,
+    extensions: Vec<String>,
+    fix_mode: FixMode,
+}
+
+impl Session {
+    /// lint the discovered files among `changed`, or all of them when it is `None`, and forget
+    /// the ones that are gone
+    fn check(&mut self, changed: Option<&HashSet<PathBuf>>) -> Result<Update, anyhow::Error> {
+        // settings are cached per directory, so an edited config means starting over
+        let config_changed = changed.is_some_and(|changed| {
+            changed.iter().any(|path| {
+                path.file_name()
+                    .is_some_and(|name| CONFIG_FILE_NAMES.iter().any(|config| name == *config))
+            })
+        });
+        if config_changed {
+            self.resolver = ConfigResolver::new();
+        }
+        let changed = changed.filter(|_| !config_changed);
+
+        let (files, _) = find_configured_files(&self.paths, &self.extensions, &mut self.resolver)?;
+        let to_lint: Vec<&PathBuf> = files
+            .iter()
+            .filter(|file| {
+                changed.is_none_or(|changed| {
+                    fs::canonicalize(file).is_ok_and(|file| changed.contains(&file))
+                })
+            })
+            .collect();
+        let removed: Vec<PathBuf> = self
+            .reports
+            .keys()
+            .filter(|file| !files.contains(file))
+            .cloned()
+            .collect();
+
+        let mut update = Update::default();
+        for file in to_lint {
+            let report = match lint_file(file, self.resolver.settings_for(file)?, self.fix_mode) {
+                Ok((analysis, _, file_fixed)) => {
+                    update.fixed += file_fixed;
+                    FileReport {
+                        lines: self.printer.compact_lines(file, &analysis),
+                        errors: analysis.errors.len(),
+                        warnings: analysis.warnings.len(),
+                    }
+                }
+                Err(e) => FileReport {
+                    lines: vec![format!("{}: could not be read: {e}", file.display())],
+                    errors: 1,
+                    warnings: 0,
+                },
+            };
+
+            if self.reports.get(file).unwrap_or(&FileReport::default()) != &report {
+                update.files.push(file.clone());
+            }
+            self.reports.insert(file.clone(), report);
+        }
+        for file in removed {
+            if self
+                .reports
+                .remove(&file)
+                .is_some_and(|report| !report.lines.is_empty())
+            {
+                update.files.push(file);
+            }
+        }
+
+        Ok(update)
+    }
+
+    fn redraw(&self, update: &Update) {
+        let mut stdout = std::io::stdout().lock();
+        if stdout.is_terminal() {
+            // clear the screen and move to its top left corner
+            let _ = write!(stdout, "\x1b[2J\x1b[H");
+        }
+
+        for file in &update.files {
+            match self.reports.get(file) {
+                Some(report) if !report.lines.is_empty() => {
+                    for line in &report.lines {
+                        let _ = writeln!(stdout, "{line}");
+                    }
+                }
+                Some(_) => {
+                    let _ = writeln!(stdout, "{}: no problems", file.display());
+                }
+                None => {
+                    let _ = writeln!(stdout, "{}: removed", file.display());
+                }
+            }
+        }
+
+        let errors: usize = self.reports.values().map(|report| report.errors).sum();
+        let warnings: usize = self.reports.values().map(|report| report.warnings).sum();
+        let valid = self
+            .reports
+            .values()
+            .filter(|report| report.errors == 0)
+            .count();
+
+        let _ = writeln!(stdout);
+        if update.fixed > 0 {
+            let _ = writeln!(stdout, "Fixed {} issue(s)", update.fixed);
+        }
+        let mut summary = format!(
+            "Summary: {valid}/{} files valid, {errors} error(s)",
+            self.reports.len()
+        );
+        if self.printer.show_warnings {
+            summary.push_str(&format!(", {warnings} warning(s)"));
+        }
+        let _ = writeln!(stdout, "{summary}");
+        let _ = writeln!(stdout, "Watching for changes...");
+        let _ = stdout.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, DataChange, MetadataKind};
+
+    fn session(path: &Path, fix_mode: FixMode) -> Session {
+        Session {
+            printer: Printer::new(OutputFormat::Text, true),
+            resolver: ConfigResolver::new(),
+            reports: BTreeMap::new(),
+            paths: vec![path.to_path_buf()],
+            extensions: Vec::new(),
+            fix_mode,
+        }
+    }
+
+    fn changed(file: &Path) -> HashSet<PathBuf> {
+        HashSet::from([fs::canonicalize(file).unwrap()])
+    }
+
+    #[test]
+    fn test_changed_paths() {
+        let path = PathBuf::from("/queries/query.bwq");
+        let event = |kind| Ok(Event::new(kind).add_path(path.clone()));
+
+        let mut changed = HashSet::new();
+        changed_paths(event(EventKind::Access(AccessKind::Any)), &mut changed);
+        changed_paths(
+            event(EventKind::Modify(ModifyKind::Metadata(MetadataKind::Any))),
+            &mut changed,
+        );
+        changed_paths(Err(notify::Error::generic("watch failed")), &mut changed);
+        assert!(changed.is_empty());
+
+        changed_paths(
+            event(EventKind::Modify(ModifyKind::Data(DataChange::Content))),
+            &mut changed,
+        );
+        changed_paths(event(EventKind::Create(CreateKind::File)), &mut changed);
+        assert_eq!(changed, HashSet::from([path]));
+    }
+
+    #[test]
+    fn test_check_reports_files_whose_diagnostics_changed() {
+        let dir = tempfile::tempdir().unwrap();
+        let clean = dir.path().join("clean.bwq");
+        let broken = dir.path().join("broken.bwq");
+        fs::write(&clean, "apple AND juice").unwrap();
+        fs::write(&broken, "apple AND").unwrap();
+
+        // files without problems aren't reported the first time
+        let mut session = session(dir.path(), FixMode::Off);
+        let update = session.check(None).unwrap();
+        assert_eq!(update.files, vec![broken.clone()]);
+
+        // saving without changing the diagnostics reports nothing
+        fs::write(&broken, "apple AND").unwrap();
+        let update = session.check(Some(&changed(&broken))).unwrap();
+        assert_eq!(update, Update::default());
+
+        // a fixed file is reported, so that its old diagnostics are replaced
+        fs::write(&broken, "apple AND juice").unwrap();
+        let update = session.check(Some(&changed(&broken))).unwrap();
+        assert_eq!(update.files, vec![broken.clone()]);
+
+        fs::write(&clean, "apple AND").unwrap();
+        let events = changed(&clean);
+        fs::remove_file(&broken).unwrap();
+        let update = session.check(Some(&events)).unwrap();
+        assert_eq!(update.files, vec![clean.clone()]);
+        assert_eq!(session.reports.len(), 1);
+    }
+
+    #[test]
+    fn test_check_after_fixing_reports_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("query.bwq");
+        fs::write(&file, "language:EN").unwrap();
+
+        let mut session = session(dir.path(), FixMode::Safe);
+        let update = session.check(None).unwrap();
+        assert_eq!(update.fixed, 1);
+
+        // the save made by the fix is linted again, with nothing new to report
+        let update = session.check(Some(&changed(&file))).unwrap();
+        assert_eq!(update, Update::default());
+    }
+}
@@ -0,0 +1,300 @@
+use anyhow::{Context, anyhow, bail};
+use serde_json::Value;
+
+/// how the queries to check are stored in each input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// the whole input is one query
+    Query,
+    /// one query per row, in a column
+    Csv,
+    /// one JSON object per line
+    Jsonl,
+    /// a Brandwatch API dump: an array of queries, or an object with one under `results`
+    BwJson,
+}
+
+/// how `bwq check` finds and reads the queries in its inputs
+#[derive(Debug, Clone)]
+pub struct InputOptions {
+    pub format: InputFormat,
+    /// where each record keeps its query, see [`read_records`]
+    pub query_field: Option<String>,
+    /// where each record keeps its id
+    pub id_field: Option<String>,
+    /// extensions given on the command line
+    pub extensions: Vec<String>,
+}
+
+impl InputOptions {
+    pub fn new(
+        format: &str,
+        query_field: Option<String>,
+        id_field: Option<String>,
+        extensions: Vec<String>,
+    ) -> Result<Self, anyhow::Error> {
+        let format = InputFormat::from_name(format).ok_or_else(|| {
+            anyhow!("Unknown input format '{format}': expected query, csv, jsonl or bw-json")
+        })?;
+        if format == InputFormat::Query && (query_field.is_some() || id_field.is_some()) {
+            bail!("--query-field and --id-field need a container --input-format");
+        }
+
+        Ok(Self {
+            format,
+            query_field,
+            id_field,
+            extensions,
+        })
+    }
+
+    /// the extensions to search directories for: those given, or the container's own
+    pub fn extensions(&self) -> Vec<String> {
+        match self.format.extension() {
+            Some(extension) if self.extensions.is_empty() => vec![extension.to_string()],
+            _ => self.extensions.clone(),
+        }
+    }
+
+    pub fn read_records(
+        &self,
+        content: &str,
+    ) -> Result<Vec<Result<Record, anyhow::Error>>, anyhow::Error> {
+        read_records(
+            content,
+            self.format,
+            self.query_field.as_deref(),
+            self.id_field.as_deref(),
+        )
+    }
+}
+
+/// a query read from a container file, with what identifies it in diagnostics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub id: String,
+    pub query: String,
+}
+
+impl InputFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "query" => Some(Self::Query),
+            "csv" => Some(Self::Csv),
+            "jsonl" => Some(Self::Jsonl),
+            "bw-json" => Some(Self::BwJson),
+            _ => None,
+        }
+    }
+
+    /// the extension searched for in directories when none is given
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            Self::Query => None,
+            Self::Csv => Some("csv"),
+            Self::Jsonl => Some("jsonl"),
+            Self::BwJson => Some("json"),
+        }
+    }
+
+    fn default_query_field(&self) -> &'static str {
+        match self {
+            Self::Query | Self::Csv => "query",
+            Self::Jsonl => "/query",
+            Self::BwJson => "/booleanQuery",
+        }
+    }
+
+    fn default_id_field(&self) -> &'static str {
+        match self {
+            Self::Query | Self::Csv => "id",
+            Self::Jsonl | Self::BwJson => "/id",
+        }
+    }
+}
+
+/// split the contents of a container into its queries
+///
+/// `query_field` and `id_field` are column names for CSV and JSON pointers otherwise. a record
+/// without an id is identified by its row, line or position instead. records whose query can't
+/// be found are returned as errors so the rest can still be checked
+pub fn read_records(
+    content: &str,
+    format: InputFormat,
+    query_field: Option<&str>,
+    id_field: Option<&str>,
+) -> Result<Vec<Result<Record, anyhow::Error>>, anyhow::Error> {
+    let query_field = query_field.unwrap_or(format.default_query_field());
+    let id_field = id_field.unwrap_or(format.default_id_field());
+
+    match format {
+        InputFormat::Query => Ok(vec![Ok(Record {
+            id: "query 1".to_string(),
+            query: content.trim().to_string(),
+        })]),
+        InputFormat::Csv => read_csv(content, query_field, id_field),
+        InputFormat::Jsonl => Ok(content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                let fallback = format!("line {}", index + 1);
+                let value: Value = serde_json::from_str(line)
+                    .with_context(|| format!("{fallback} is not valid JSON"))?;
+                json_record(&value, query_field, id_field, fallback)
+            })
+            .collect()),
+        InputFormat::BwJson => {
+            let value: Value = serde_json::from_str(content).context("Invalid JSON")?;
+            let queries = match &value {
+                Value::Array(queries) => queries.as_slice(),
+                Value::Object(object) => match object.get("results") {
+                    Some(Value::Array(queries)) => queries.as_slice(),
+                    _ => std::slice::from_ref(&value),
+                },
+                _ => bail!("Expected an array of queries or an object with `results`"),
+            };
+
+            Ok(queries
+                .iter()
+                .enumerate()
+                .map(|(index, query)| {
+                    json_record(query, query_field, id_field, format!("query {}", index + 1))
+                })
+                .collect())
+        }
+    }
+}
+
+fn read_csv(
+    content: &str,
+    query_field: &str,
+    id_field: &str,
+) -> Result<Vec<Result<Record, anyhow::Error>>, anyhow::Error> {
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let headers = reader.headers().context("Invalid CSV header")?.clone();
+    let column = |name: &str| headers.iter().position(|header| header.trim() == name);
+
+    let query_column = column(query_field).ok_or_else(|| {
+        anyhow!(
+            "No `{query_field}` column (columns are: {})",
+            headers.iter().collect::<Vec<_>>().join(", ")
+        )
+    })?;
+    let id_column = column(id_field);
+
+    Ok(reader
+        .records()
+        .enumerate()
+        .map(|(index, row)| {
+            // the header is row 1
+            let fallback = format!("row {}", index + 2);
+            let row = row.with_context(|| format!("{fallback} is not valid CSV"))?;
+            let id = id_column
+                .and_then(|column| row.get(column))
+                .filter(|id| !id.trim().is_empty())
+                .map_or(fallback, |id| id.trim().to_string());
+            let query = row
+                .get(query_column)
+                .ok_or_else(|| anyhow!("{id} has no `{query_field}` column"))?;
+
+            Ok(Record {
+                id,
+                query: query.trim().to_string(),
+            })
+        })
+        .collect())
+}
+
+fn json_record(
+    value: &Value,
+    query_field: &str,
+    id_field: &str,
+    fallback: String,
+) -> Result<Record, anyhow::Error> {
+    let id = match value.pointer(&json_pointer(id_field)) {
+        Some(Value::String(id)) if !id.trim().is_empty() => id.trim().to_string(),
+        Some(Value::Number(id)) => id.to_string(),
+        _ => fallback,
+    };
+    let Some(Value::String(query)) = value.pointer(&json_pointer(query_field)) else {
+        bail!("{id} has no query at `{}`", json_pointer(query_field));
+    };
+
+    Ok(Record {
+        id,
+        query: query.trim().to_string(),
+    })
+}
+
+/// a JSON pointer, allowing the leading `/` to be left off a top-level key
+fn json_pointer(selector: &str) -> String {
+    if selector.is_empty() || selector.starts_with('/') {
+        selector.to_string()
+    } else {
+        format!("/{selector}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records(content: &str, format: InputFormat, query_field: Option<&str>) -> Vec<Record> {
+        read_records(content, format, query_field, None)
+            .unwrap()
+            .into_iter()
+            .filter_map(Result::ok)
+            .collect()
+    }
+
+    fn record(id: &str, query: &str) -> Record {
+        Record {
+            id: id.to_string(),
+            query: query.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_csv() {
+        let content = "id,query\n7,\"apple OR \"\"cherry pie\"\"\"\n,juice\n";
+
+        assert_eq!(
+            records(content, InputFormat::Csv, None),
+            vec![
+                record("7", "apple OR \"cherry pie\""),
+                record("row 3", "juice")
+            ]
+        );
+        assert!(read_records(content, InputFormat::Csv, Some("text"), None).is_err());
+    }
+
+    #[test]
+    fn test_jsonl() {
+        let content =
+            "{\"id\": 1, \"q\": {\"text\": \"apple\"}}\n\n{\"q\": {\"text\": \"juice\"}}\n{}\n";
+        let results = read_records(content, InputFormat::Jsonl, Some("/q/text"), None).unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap(), &record("1", "apple"));
+        assert_eq!(results[1].as_ref().unwrap(), &record("line 3", "juice"));
+        assert!(results[2].is_err());
+    }
+
+    #[test]
+    fn test_bw_json() {
+        let dump =
+            r#"{"results": [{"id": 5, "booleanQuery": "apple"}, {"booleanQuery": "juice"}]}"#;
+        assert_eq!(
+            records(dump, InputFormat::BwJson, None),
+            vec![record("5", "apple"), record("query 2", "juice")]
+        );
+
+        let array = r#"[{"id": "a", "query": "apple"}]"#;
+        assert_eq!(
+            records(array, InputFormat::BwJson, Some("query")),
+            vec![record("a", "apple")]
+        );
+        assert!(read_records("\"apple\"", InputFormat::BwJson, None, None).is_err());
+    }
+}
@@ -1,8 +1,11 @@
 pub mod args;
 mod commands;
+mod input;
 mod output;
 
 use args::Cli;
+use commands::check::FixMode;
+use input::{InputFormat, InputOptions};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ExitStatus {
@@ -19,6 +22,28 @@ impl From<ExitStatus> for i32 {
 
 pub fn run(args: Cli) -> Result<ExitStatus, anyhow::Error> {
     match args.command {
+        Some(args::Commands::Check {
+            files,
+            no_warnings,
+            output_format,
+            extensions,
+            fix,
+            unsafe_fixes,
+            input_format,
+            watch: true,
+            ..
+        }) => {
+            if InputFormat::from_name(&input_format) != Some(InputFormat::Query) {
+                anyhow::bail!("--watch can only check query files");
+            }
+            commands::watch::run_watch(
+                files,
+                no_warnings,
+                output_format,
+                extensions,
+                FixMode::from_flags(fix, unsafe_fixes),
+            )
+        }
         Some(args::Commands::Check {
             files,
             query,
@@ -26,14 +51,42 @@ pub fn run(args: Cli) -> Result<ExitStatus, anyhow::Error> {
             output_format,
             extensions,
             exit_zero,
+            fix,
+            unsafe_fixes,
+            input_format,
+            query_field,
+            id_field,
+            watch: false,
         }) => commands::check::run_check(
             files,
             query,
             no_warnings,
             output_format,
-            extensions,
             exit_zero,
+            FixMode::from_flags(fix, unsafe_fixes),
+            InputOptions::new(&input_format, query_field, id_field, extensions)?,
         ),
+        Some(args::Commands::Format {
+            files,
+            check,
+            diff,
+            line_width,
+            extensions,
+        }) => commands::format::run_format(files, check, diff, line_width, extensions),
+        Some(args::Commands::Test { files, extensions }) => {
+            commands::test::run_test(files, extensions)
+        }
+        Some(args::Commands::Stats {
+            files,
+            query,
+            output_format,
+            extensions,
+        }) => commands::stats::run_stats(files, query, output_format, extensions),
+        Some(args::Commands::Rule {
+            rule,
+            all,
+            output_format,
+        }) => commands::rule::run_rule(rule, all, output_format),
         Some(args::Commands::Examples) => commands::examples::run_examples(),
         Some(args::Commands::Server) => commands::server::run_server(),
         None => {
@@ -41,6 +94,10 @@ pub fn run(args: Cli) -> Result<ExitStatus, anyhow::Error> {
             eprintln!("\nUsage: bwq <COMMAND>");
             eprintln!("\nCommands:");
             eprintln!("  check        Lint files, directories, or queries");
+            eprintln!("  format       Format query files");
+            eprintln!("  test         Run queries against test fixtures");
+            eprintln!("  stats        Report query size and complexity");
+            eprintln!("  rule         Explain a diagnostic code");
             eprintln!("  examples     Show example queries");
             eprintln!("  server       Start language server");
             eprintln!("\nFor more information, try 'bwq --help'");
@@ -1,6 +1,8 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use bwq_linter::AnalysisResult;
+use bwq_linter::error::{Applicability, Fix, Span};
+use bwq_linter::registry::RULES;
 
 pub struct Printer {
     pub format: OutputFormat,
@@ -11,12 +13,70 @@ pub struct Printer {
 pub enum OutputFormat {
     Text,
     Json,
+    Sarif,
+}
+
+/// where a checked query came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    File(PathBuf),
+    /// a query read from standard input
+    Stdin,
+    /// one of the queries in a container file, or in standard input when `file` is `None`
+    Record {
+        file: Option<PathBuf>,
+        id: String,
+    },
+}
+
+impl Source {
+    /// the file holding the query, if it came from one
+    pub fn file(&self) -> Option<&Path> {
+        match self {
+            Source::File(file)
+            | Source::Record {
+                file: Some(file), ..
+            } => Some(file),
+            Source::Stdin | Source::Record { file: None, .. } => None,
+        }
+    }
+
+    fn json(&self, json: &mut serde_json::Value) {
+        let Some(object) = json.as_object_mut() else {
+            return;
+        };
+        if let Some(file) = self.file() {
+            object.insert(
+                "filename".to_string(),
+                serde_json::Value::String(file.display().to_string()),
+            );
+        }
+        if let Source::Record { id, .. } = self {
+            object.insert("record".to_string(), serde_json::Value::String(id.clone()));
+        }
+    }
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::File(file) => write!(f, "{}", file.display()),
+            Source::Stdin => write!(f, "<stdin>"),
+            Source::Record {
+                file: Some(file),
+                id,
+            } => write!(f, "{}[{id}]", file.display()),
+            Source::Record { file: None, id } => write!(f, "<stdin>[{id}]"),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct FileResults {
-    pub successful: Vec<(PathBuf, AnalysisResult, String)>,
+    pub successful: Vec<(Source, AnalysisResult, String)>,
     pub read_errors: usize,
+    /// number of fixes written back to the files
+    pub fixed: usize,
 }
 
 impl Default for FileResults {
@@ -30,6 +90,7 @@ impl FileResults {
         Self {
             successful: Vec::new(),
             read_errors: 0,
+            fixed: 0,
         }
     }
 
@@ -44,6 +105,19 @@ impl FileResults {
             .count()
     }
 
+    /// what the summary counts: files, or queries once any came from a container or stdin
+    fn noun(&self) -> &'static str {
+        if self
+            .successful
+            .iter()
+            .all(|(source, _, _)| matches!(source, Source::File(_)))
+        {
+            "files"
+        } else {
+            "queries"
+        }
+    }
+
     pub fn has_errors(&self) -> bool {
         self.read_errors > 0
             || self
@@ -57,6 +131,7 @@ impl From<&str> for OutputFormat {
     fn from(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "json" => OutputFormat::Json,
+            "sarif" => OutputFormat::Sarif,
             _ => OutputFormat::Text,
         }
     }
@@ -73,6 +148,7 @@ impl Printer {
     pub fn print_analysis(&self, analysis: &AnalysisResult) {
         match self.format {
             OutputFormat::Json => self.print_json(analysis),
+            OutputFormat::Sarif => self.print_sarif(&[(None, analysis)]),
             OutputFormat::Text => self.print_text(analysis),
         }
     }
@@ -80,6 +156,14 @@ impl Printer {
     pub fn print_file_results(&self, results: &FileResults) {
         match self.format {
             OutputFormat::Json => self.print_file_results_json(results),
+            OutputFormat::Sarif => {
+                let analyses: Vec<_> = results
+                    .successful
+                    .iter()
+                    .map(|(source, analysis, _)| (Some(source), analysis))
+                    .collect();
+                self.print_sarif(&analyses);
+            }
             OutputFormat::Text => self.print_file_results_text(results),
         }
     }
@@ -90,14 +174,16 @@ impl Printer {
         if !analysis.errors.is_empty() {
             println!("\nErrors:");
             for (i, error) in analysis.errors.iter().enumerate() {
-                println!("  {}. {}: {}", i + 1, error.code(), error);
+                let marker = fixable_marker(error.fix());
+                println!("  {}. {}: {}{marker}", i + 1, error.code(), error);
             }
         }
 
         if self.show_warnings && !analysis.warnings.is_empty() {
             println!("\nWarnings:");
             for (i, warning) in analysis.warnings.iter().enumerate() {
-                println!("  {}. {}: {}", i + 1, warning.code(), warning);
+                let marker = fixable_marker(warning.fix());
+                println!("  {}. {}: {}{marker}", i + 1, warning.code(), warning);
             }
         }
     }
@@ -119,10 +205,47 @@ impl Printer {
         println!("{}", serde_json::to_string_pretty(&json_output).unwrap());
     }
 
+    /// one `path:line:column: code message` line per diagnostic, as printed by `--watch`
+    pub fn compact_lines(&self, file_path: &Path, analysis: &AnalysisResult) -> Vec<String> {
+        let errors = analysis
+            .errors
+            .iter()
+            .map(|error| (error.span(), error.code(), error.to_string(), error.fix()));
+        let warnings = analysis
+            .warnings
+            .iter()
+            .filter(|_| self.show_warnings)
+            .map(|warning| {
+                (
+                    warning.span(),
+                    warning.code(),
+                    warning.to_string(),
+                    warning.fix(),
+                )
+            });
+
+        errors
+            .chain(warnings)
+            .map(|(span, code, message, fix)| {
+                format!(
+                    "{}:{}:{}: {code} {message}{}",
+                    file_path.display(),
+                    span.start.line,
+                    span.start.column,
+                    fixable_marker(fix)
+                )
+            })
+            .collect()
+    }
+
     fn print_file_results_text(&self, results: &FileResults) {
-        for (file_path, analysis, _) in &results.successful {
+        for (source, analysis, _) in &results.successful {
             if !analysis.is_valid || (self.show_warnings && !analysis.warnings.is_empty()) {
-                println!("File: {}", file_path.display());
+                match source {
+                    Source::File(_) => println!("File: {source}"),
+                    Source::Stdin => println!("Input: {source}"),
+                    Source::Record { .. } => println!("Record: {source}"),
+                }
                 self.print_analysis(analysis);
                 println!();
             }
@@ -131,13 +254,18 @@ impl Printer {
         let valid_files = results.valid_files();
         let total_files = results.total_files_processed();
 
+        if results.fixed > 0 {
+            println!("Fixed {} issue(s)", results.fixed);
+        }
+
+        let noun = results.noun();
         if results.read_errors > 0 {
             println!(
-                "Summary: {valid_files}/{total_files} files valid ({} files could not be read)",
+                "Summary: {valid_files}/{total_files} {noun} valid ({} {noun} could not be read)",
                 results.read_errors
             );
         } else {
-            println!("Summary: {valid_files}/{total_files} files valid");
+            println!("Summary: {valid_files}/{total_files} {noun} valid");
         }
     }
 
@@ -146,27 +274,17 @@ impl Printer {
         let mut warnings = Vec::new();
 
         // Add lint errors and warnings from successful files
-        for (file_path, analysis, _) in &results.successful {
+        for (source, analysis, _) in &results.successful {
             for error in &analysis.errors {
                 let mut error_json = error.to_json();
-                if let Some(obj) = error_json.as_object_mut() {
-                    obj.insert(
-                        "filename".to_string(),
-                        serde_json::Value::String(file_path.display().to_string()),
-                    );
-                }
+                source.json(&mut error_json);
                 errors.push(error_json);
             }
 
             if self.show_warnings {
                 for warning in &analysis.warnings {
                     let mut warning_json = warning.to_json();
-                    if let Some(obj) = warning_json.as_object_mut() {
-                        obj.insert(
-                            "filename".to_string(),
-                            serde_json::Value::String(file_path.display().to_string()),
-                        );
-                    }
+                    source.json(&mut warning_json);
                     warnings.push(warning_json);
                 }
             }
@@ -179,7 +297,8 @@ impl Printer {
             "summary": {
                 "total_files": total_files,
                 "valid_files": valid_files,
-                "invalid_files": total_files - valid_files
+                "invalid_files": total_files - valid_files,
+                "fixed": results.fixed
             },
             "errors": errors,
             "warnings": warnings
@@ -188,3 +307,167 @@ impl Printer {
         println!("{}", serde_json::to_string_pretty(&output).unwrap());
     }
 }
+
+impl Printer {
+    /// a SARIF 2.1.0 log with one rule per diagnostic code. `source` is `None` for a query
+    /// given on the command line
+    fn print_sarif(&self, analyses: &[(Option<&Source>, &AnalysisResult)]) {
+        let rules: Vec<_> = RULES
+            .iter()
+            .map(|rule| {
+                serde_json::json!({
+                    "id": rule.code,
+                    "name": rule.name,
+                    "shortDescription": {"text": rule.summary},
+                    "fullDescription": {"text": rule.explanation},
+                    "defaultConfiguration": {"level": rule.severity.as_str()},
+                    "properties": {"tags": [rule.category.as_str()]},
+                })
+            })
+            .collect();
+
+        let mut results = Vec::new();
+        for (source, analysis) in analyses {
+            let errors = analysis
+                .errors
+                .iter()
+                .map(|error| (error.code(), error.to_string(), error.span(), "error"));
+            let warnings = analysis
+                .warnings
+                .iter()
+                .filter(|_| self.show_warnings)
+                .map(|warning| {
+                    (
+                        warning.code(),
+                        warning.to_string(),
+                        warning.span(),
+                        "warning",
+                    )
+                });
+
+            for (code, message, span, level) in errors.chain(warnings) {
+                let locations = sarif_locations(*source, span);
+                let mut result = serde_json::json!({
+                    "ruleId": code,
+                    "level": level,
+                    "message": {"text": message},
+                    "locations": locations,
+                });
+                if let Some(index) = RULES.iter().position(|rule| rule.code == code) {
+                    result["ruleIndex"] = index.into();
+                }
+                results.push(result);
+            }
+        }
+
+        let log = serde_json::json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "bwq",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": "https://github.com/mmstroik/bwq",
+                        "rules": rules,
+                    }
+                },
+                "results": results,
+            }]
+        });
+
+        println!("{}", serde_json::to_string_pretty(&log).unwrap());
+    }
+}
+
+/// where a diagnostic is. a record's span is within its query rather than the file, so the
+/// record is named by a logical location and the file is given without a region
+fn sarif_locations(source: Option<&Source>, span: &Span) -> serde_json::Value {
+    let mut location = serde_json::json!({});
+    match source {
+        Some(Source::Record { file, id }) => {
+            if let Some(file_path) = file {
+                location["physicalLocation"] =
+                    serde_json::json!({"artifactLocation": {"uri": artifact_uri(file_path)}});
+            }
+            location["logicalLocations"] = serde_json::json!([{"name": id}]);
+        }
+        _ => {
+            location["physicalLocation"] = serde_json::json!({"region": sarif_region(span)});
+            if let Some(file_path) = source.and_then(Source::file) {
+                location["physicalLocation"]["artifactLocation"] =
+                    serde_json::json!({"uri": artifact_uri(file_path)});
+            }
+        }
+    }
+    serde_json::json!([location])
+}
+
+/// SARIF regions are 1-based, like `Span`, with an exclusive end column
+fn sarif_region(span: &Span) -> serde_json::Value {
+    serde_json::json!({
+        "startLine": span.start.line,
+        "startColumn": span.start.column,
+        "endLine": span.end.line,
+        "endColumn": span.end.column,
+    })
+}
+
+/// a relative URI reference for a file path
+fn artifact_uri(file_path: &std::path::Path) -> String {
+    let path = file_path.to_string_lossy().replace('\\', "/");
+    let path = path.strip_prefix("./").unwrap_or(&path);
+
+    let mut uri = String::new();
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    uri
+}
+
+/// marks diagnostics that `--fix` (`[*]`) or `--unsafe-fixes` (`[~]`) would correct
+fn fixable_marker(fix: Option<&Fix>) -> &'static str {
+    match fix.map(|fix| fix.applicability) {
+        Some(Applicability::Safe) => " [*]",
+        Some(Applicability::Unsafe) => " [~]",
+        None => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bwq_linter::error::Position;
+
+    #[test]
+    fn test_sarif_locations() {
+        let span = Span::new(Position::new(1, 7, 6), Position::new(1, 10, 9));
+
+        let file = Source::File(PathBuf::from("./queries/apple juice.bwq"));
+        assert_eq!(
+            sarif_locations(Some(&file), &span),
+            serde_json::json!([{"physicalLocation": {
+                "artifactLocation": {"uri": "queries/apple%20juice.bwq"},
+                "region": {"startLine": 1, "startColumn": 7, "endLine": 1, "endColumn": 10},
+            }}])
+        );
+
+        // the span is within the record's query, not a position in the file
+        let record = Source::Record {
+            file: Some(PathBuf::from("queries.csv")),
+            id: "q1".to_string(),
+        };
+        assert_eq!(
+            sarif_locations(Some(&record), &span),
+            serde_json::json!([{
+                "physicalLocation": {"artifactLocation": {"uri": "queries.csv"}},
+                "logicalLocations": [{"name": "q1"}],
+            }])
+        );
+    }
+}
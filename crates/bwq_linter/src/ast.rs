@@ -4,6 +4,30 @@ use crate::error::Span;
 pub struct Query {
     pub expression: Expression,
     pub span: Span,
+    pub comments: Vec<Comment>,
+}
+
+/// a `<<<comment>>>`; comments can sit between any two tokens so they are kept beside the
+/// expression tree rather than in it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    pub text: String,
+    pub span: Span,
+}
+
+impl Comment {
+    /// whether the comment sits on a line of its own in `source`, the chars it was parsed from
+    pub fn is_standalone(&self, source: &[char]) -> bool {
+        let before = source[..self.span.start.offset]
+            .iter()
+            .rev()
+            .take_while(|&&ch| ch != '\n');
+        let after = source[self.span.end.offset..]
+            .iter()
+            .take_while(|&&ch| ch != '\n');
+
+        before.chain(after).all(|ch| ch.is_whitespace())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -43,6 +67,11 @@ pub enum Expression {
         term: Term,
         span: Span,
     },
+
+    /// placeholder for input the parser could not make sense of
+    Error {
+        span: Span,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -132,6 +161,67 @@ pub enum FieldType {
 }
 
 impl FieldType {
+    pub const ALL: [FieldType; 46] = [
+        Self::Title,
+        Self::Site,
+        Self::Url,
+        Self::Author,
+        Self::Links,
+        Self::Continent,
+        Self::Country,
+        Self::Region,
+        Self::City,
+        Self::Latitude,
+        Self::Longitude,
+        Self::Language,
+        Self::ChannelId,
+        Self::AuthorGender,
+        Self::AuthorVerified,
+        Self::AuthorVerifiedType,
+        Self::AuthorFollowers,
+        Self::BlogName,
+        Self::ParentBlogName,
+        Self::RootBlogName,
+        Self::ParentPostId,
+        Self::RootPostId,
+        Self::Tags,
+        Self::BrandIds,
+        Self::Objects,
+        Self::EngagementType,
+        Self::EngagingWith,
+        Self::EngagingWithGuid,
+        Self::Guid,
+        Self::ImageType,
+        Self::ItemReview,
+        Self::Rating,
+        Self::MinuteOfDay,
+        Self::PubType,
+        Self::PublisherSubType,
+        Self::Publication,
+        Self::RedditAuthorFlair,
+        Self::RedditPostFlair,
+        Self::RedditSpoiler,
+        Self::SensitiveContent,
+        Self::Subreddit,
+        Self::SubredditNSFW,
+        Self::SubredditTopics,
+        Self::TopLevelDomain,
+        Self::WeblogTitle,
+        Self::Sentiment,
+    ];
+
+    /// whether the field takes a `[start TO end]` range
+    pub fn accepts_range(&self) -> bool {
+        matches!(
+            self,
+            Self::AuthorFollowers
+                | Self::Latitude
+                | Self::Longitude
+                | Self::MinuteOfDay
+                | Self::Rating
+        )
+    }
+
     pub fn parse(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "title" => Some(Self::Title),
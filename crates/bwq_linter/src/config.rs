@@ -0,0 +1,674 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::error::{LintError, LintWarning};
+use crate::locations::{Locations, LocationsError};
+use crate::metrics::Limits;
+use crate::registry::{self, rule_name};
+use crate::validation::ValidationResult;
+
+/// file names checked in each directory, in order of precedence
+pub const CONFIG_FILE_NAMES: [&str; 3] = ["bwq.toml", ".bwq.toml", "pyproject.toml"];
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("failed to read {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("failed to parse {}: {message}", path.display())]
+    Parse { path: PathBuf, message: String },
+
+    #[error("unknown rule selector '{selector}' in {}", path.display())]
+    UnknownSelector { path: PathBuf, selector: String },
+
+    #[error(transparent)]
+    Locations(#[from] LocationsError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// contents of a `bwq.toml`, `.bwq.toml` or the `[tool.bwq]` table of a `pyproject.toml`
+///
+/// rules are selected by code (`E012`), code prefix (`E`, `W00`), rule name
+/// (`mixed-and-or`), check name (`location-field`) or `ALL`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    /// file extensions checked when a directory is given
+    pub extensions: Option<Vec<String>>,
+    /// rules to enable; everything is enabled when unset
+    pub select: Option<Vec<String>>,
+    /// rules to disable, taking precedence over `select`
+    pub ignore: Vec<String>,
+    /// severity to report a rule's diagnostics at
+    pub severity: BTreeMap<String, Severity>,
+    /// size and complexity limits, reported by the `query-limit` rule
+    pub limits: Limits,
+    /// settings for files under particular directories
+    pub overrides: Vec<Override>,
+    /// a JSON location dataset, relative to the config file, replacing the built-in one
+    pub locations: Option<PathBuf>,
+}
+
+/// rule settings for the files under `paths`, which are relative to the config file
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Override {
+    pub paths: Vec<String>,
+    /// replaces the top-level selection
+    pub select: Option<Vec<String>>,
+    /// added to the top-level ignores
+    pub ignore: Vec<String>,
+    /// merged over the top-level severities
+    pub severity: BTreeMap<String, Severity>,
+    /// merged over the top-level limits
+    pub limits: Limits,
+}
+
+#[derive(Deserialize)]
+struct PyProject {
+    tool: Option<PyProjectTool>,
+}
+
+#[derive(Deserialize)]
+struct PyProjectTool {
+    bwq: Option<Config>,
+}
+
+impl Config {
+    /// load a config file; `Ok(None)` for a `pyproject.toml` without a `[tool.bwq]` table
+    pub fn load(path: &Path) -> Result<Option<Self>, ConfigError> {
+        let contents = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let parse_error = |e: toml::de::Error| ConfigError::Parse {
+            path: path.to_path_buf(),
+            message: e.message().to_string(),
+        };
+
+        let config = if path
+            .file_name()
+            .is_some_and(|name| name == "pyproject.toml")
+        {
+            let pyproject: PyProject = toml::from_str(&contents).map_err(parse_error)?;
+            match pyproject.tool.and_then(|tool| tool.bwq) {
+                Some(config) => config,
+                None => return Ok(None),
+            }
+        } else {
+            toml::from_str(&contents).map_err(parse_error)?
+        };
+
+        config.check_selectors(path)?;
+        Ok(Some(config))
+    }
+
+    fn check_selectors(&self, path: &Path) -> Result<(), ConfigError> {
+        let top_level = self
+            .select
+            .iter()
+            .flatten()
+            .chain(&self.ignore)
+            .chain(self.severity.keys());
+        let overrides = self.overrides.iter().flat_map(|o| {
+            o.select
+                .iter()
+                .flatten()
+                .chain(&o.ignore)
+                .chain(o.severity.keys())
+        });
+
+        for selector in top_level.chain(overrides) {
+            if selector != "ALL"
+                && !is_code_prefix(selector)
+                && registry::rules(selector).is_empty()
+            {
+                return Err(ConfigError::UnknownSelector {
+                    path: path.to_path_buf(),
+                    selector: selector.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// the rule settings for `relative_path`, a path relative to the directory of this config
+    pub fn settings_for(&self, relative_path: &Path) -> RuleSettings {
+        let mut settings = RuleSettings {
+            select: self.select.clone(),
+            ignore: self.ignore.clone(),
+            severity: self.severity.clone(),
+            limits: self.limits.clone(),
+            locations: None,
+        };
+
+        let relative_path = normalize_path(relative_path);
+        for o in &self.overrides {
+            if o.paths
+                .iter()
+                .any(|p| relative_path.starts_with(normalize_path(Path::new(p))))
+            {
+                if o.select.is_some() {
+                    settings.select = o.select.clone();
+                }
+                settings.ignore.extend(o.ignore.iter().cloned());
+                settings.severity.extend(o.severity.clone());
+                settings.limits.merge(&o.limits);
+            }
+        }
+
+        settings
+    }
+}
+
+/// `path` without `.` components, so that `./legacy` and `legacy/` name the same directory
+fn normalize_path(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+/// whether `selector` is a code or code prefix, e.g. `E015` or `E0`, of at least one rule
+pub(crate) fn is_code_prefix(selector: &str) -> bool {
+    let mut chars = selector.chars();
+    matches!(chars.next(), Some('E' | 'W'))
+        && selector.len() <= 4
+        && chars.all(|ch| ch.is_ascii_digit())
+        && registry::RULES
+            .iter()
+            .any(|rule| rule.code.starts_with(selector))
+}
+
+/// which diagnostics to report, and at which severity
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RuleSettings {
+    pub select: Option<Vec<String>>,
+    pub ignore: Vec<String>,
+    pub severity: BTreeMap<String, Severity>,
+    pub limits: Limits,
+    /// the location codes to check against; the built-in dataset when unset
+    pub locations: Option<Arc<Locations>>,
+}
+
+impl RuleSettings {
+    /// whether diagnostics with `code`, raised by the rule named `rule` (if any), are reported
+    pub fn is_enabled(&self, code: &str, rule: Option<&str>) -> bool {
+        let selected = self.select.as_ref().is_none_or(|select| {
+            select
+                .iter()
+                .any(|selector| selector_matches(selector, code, rule))
+        });
+
+        selected
+            && !self
+                .ignore
+                .iter()
+                .any(|selector| selector_matches(selector, code, rule))
+    }
+
+    /// the configured severity for `code`; when several selectors match, a rule name or full
+    /// code beats a prefix, and a longer prefix beats a shorter one
+    pub fn severity(&self, code: &str, rule: Option<&str>) -> Option<Severity> {
+        self.severity
+            .iter()
+            .filter(|(selector, _)| selector_matches(selector, code, rule))
+            .max_by_key(|(selector, _)| {
+                if Some(selector.as_str()) == rule || Some(selector.as_str()) == rule_name(code) {
+                    usize::MAX
+                } else {
+                    selector.len()
+                }
+            })
+            .map(|(_, severity)| *severity)
+    }
+
+    /// drop disabled diagnostics and move the rest to their configured severity
+    pub fn apply(&self, result: ValidationResult, rule: Option<&str>) -> ValidationResult {
+        let mut applied = ValidationResult::new();
+
+        for error in result.errors {
+            if !self.is_enabled(error.code(), rule) {
+                continue;
+            }
+            match self.severity(error.code(), rule) {
+                Some(Severity::Warning) => applied.warnings.push(LintWarning::Demoted {
+                    error: Box::new(error),
+                }),
+                _ => applied.errors.push(error),
+            }
+        }
+
+        for warning in result.warnings {
+            if !self.is_enabled(warning.code(), rule) {
+                continue;
+            }
+            match self.severity(warning.code(), rule) {
+                Some(Severity::Error) => applied.errors.push(LintError::Promoted {
+                    warning: Box::new(warning),
+                }),
+                _ => applied.warnings.push(warning),
+            }
+        }
+
+        applied
+    }
+}
+
+fn selector_matches(selector: &str, code: &str, rule: Option<&str>) -> bool {
+    selector == "ALL"
+        || Some(selector) == rule
+        || Some(selector) == rule_name(code)
+        || (is_code_prefix(selector) && code.starts_with(selector))
+}
+
+/// a config file and the directory its paths are relative to
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadedConfig {
+    pub path: PathBuf,
+    pub root: PathBuf,
+    pub config: Config,
+    /// the dataset named by the config's `locations`, if any
+    pub locations: Option<Arc<Locations>>,
+}
+
+/// finds the config that applies to a path: the nearest one found walking up from it
+///
+/// lookups are cached per directory, so one resolver should be reused across the files of a
+/// single run.
+#[derive(Debug, Default)]
+pub struct ConfigResolver {
+    cache: HashMap<PathBuf, Option<Arc<LoadedConfig>>>,
+}
+
+impl ConfigResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the config for `path`, which may be a file or a directory
+    pub fn find(&mut self, path: &Path) -> Result<Option<Arc<LoadedConfig>>, ConfigError> {
+        let path = std::path::absolute(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let dir = if path.is_dir() {
+            path.as_path()
+        } else {
+            path.parent().unwrap_or(&path)
+        };
+
+        self.find_from_dir(dir)
+    }
+
+    fn find_from_dir(&mut self, dir: &Path) -> Result<Option<Arc<LoadedConfig>>, ConfigError> {
+        if let Some(cached) = self.cache.get(dir) {
+            return Ok(cached.clone());
+        }
+
+        let mut found = None;
+        for name in CONFIG_FILE_NAMES {
+            let candidate = dir.join(name);
+            if candidate.is_file()
+                && let Some(config) = Config::load(&candidate)?
+            {
+                let locations = match &config.locations {
+                    Some(path) => Some(Arc::new(Locations::load(&dir.join(path))?)),
+                    None => None,
+                };
+                found = Some(Arc::new(LoadedConfig {
+                    path: candidate,
+                    root: dir.to_path_buf(),
+                    config,
+                    locations,
+                }));
+                break;
+            }
+        }
+
+        if found.is_none()
+            && let Some(parent) = dir.parent()
+        {
+            found = self.find_from_dir(parent)?;
+        }
+
+        self.cache.insert(dir.to_path_buf(), found.clone());
+        Ok(found)
+    }
+
+    /// the rule settings for a file; the defaults when no config applies
+    pub fn settings_for(&mut self, file: &Path) -> Result<RuleSettings, ConfigError> {
+        let Some(loaded) = self.find(file)? else {
+            return Ok(RuleSettings::default());
+        };
+
+        let file = std::path::absolute(file).unwrap_or_else(|_| file.to_path_buf());
+        let relative = file.strip_prefix(&loaded.root).unwrap_or(&file);
+        Ok(RuleSettings {
+            locations: loaded.locations.clone(),
+            ..loaded.config.settings_for(relative)
+        })
+    }
+
+    /// the extensions configured for files under `path`, if any
+    pub fn extensions_for(&mut self, path: &Path) -> Result<Option<Vec<String>>, ConfigError> {
+        Ok(self
+            .find(path)?
+            .and_then(|loaded| loaded.config.extensions.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{Position, Span};
+    use pretty_assertions::assert_eq;
+
+    fn parse(contents: &str) -> Config {
+        toml::from_str(contents).unwrap()
+    }
+
+    fn span() -> Span {
+        Span::single(Position::new(1, 1, 0))
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config = parse(
+            r#"
+            extensions = ["bwq", "txt"]
+            select = ["E", "W001"]
+            ignore = ["mixed-and-or"]
+
+            [severity]
+            W001 = "error"
+
+            [[overrides]]
+            paths = ["legacy"]
+            ignore = ["E015"]
+            "#,
+        );
+
+        assert_eq!(
+            config.extensions,
+            Some(vec!["bwq".to_string(), "txt".to_string()])
+        );
+        assert_eq!(config.severity["W001"], Severity::Error);
+        assert_eq!(config.overrides[0].paths, vec!["legacy"]);
+        assert!(toml::from_str::<Config>("unknown = true").is_err());
+    }
+
+    #[test]
+    fn test_selection() {
+        let settings = RuleSettings {
+            select: Some(vec!["E".to_string(), "W001".to_string()]),
+            ignore: vec!["mixed-and-or".to_string(), "E01".to_string()],
+            severity: BTreeMap::new(),
+            limits: Limits::default(),
+            locations: None,
+        };
+
+        assert!(settings.is_enabled("E004", None));
+        assert!(settings.is_enabled("W001", None));
+        assert!(!settings.is_enabled("W003", None));
+        assert!(!settings.is_enabled("E012", None));
+        assert!(!settings.is_enabled("E005", Some("mixed-and-or")));
+        assert!(RuleSettings::default().is_enabled("W003", Some("short-term")));
+    }
+
+    #[test]
+    fn test_registry_names() {
+        let settings = RuleSettings {
+            select: None,
+            ignore: vec!["implicit-and".to_string()],
+            severity: BTreeMap::from([("broad-wildcard".to_string(), Severity::Warning)]),
+            limits: Limits::default(),
+            locations: None,
+        };
+
+        assert!(!settings.is_enabled("W001", None));
+        assert!(settings.is_enabled("W005", None));
+        assert_eq!(settings.severity("E020", None), Some(Severity::Warning));
+        assert_eq!(settings.severity("E021", None), None);
+    }
+
+    #[test]
+    fn test_severity_overrides() {
+        let settings = RuleSettings {
+            select: None,
+            ignore: Vec::new(),
+            severity: BTreeMap::from([
+                ("W".to_string(), Severity::Error),
+                ("W002".to_string(), Severity::Warning),
+                ("mixed-and-or".to_string(), Severity::Warning),
+            ]),
+            limits: Limits::default(),
+            locations: None,
+        };
+
+        let mixing = ValidationResult::with_error(LintError::OperatorMixingError {
+            span: span(),
+            message: "mixed".to_string(),
+            fix: None,
+        });
+        let applied = settings.apply(mixing, Some("mixed-and-or"));
+        assert!(applied.errors.is_empty());
+        assert_eq!(applied.warnings[0].code(), "E015");
+        assert!(matches!(applied.warnings[0], LintWarning::Demoted { .. }));
+
+        let syntax = ValidationResult {
+            errors: Vec::new(),
+            warnings: vec![
+                LintWarning::PotentialTypo {
+                    span: span(),
+                    suggestion: "AND".to_string(),
+                    fix: None,
+                },
+                LintWarning::DeprecatedOperator {
+                    span: span(),
+                    replacement: "NEAR".to_string(),
+                },
+            ],
+        };
+        let applied = settings.apply(syntax, None);
+        assert_eq!(applied.errors.len(), 1);
+        assert_eq!(applied.errors[0].code(), "W001");
+        assert_eq!(applied.warnings.len(), 1);
+        assert_eq!(applied.warnings[0].code(), "W002");
+    }
+
+    #[test]
+    fn test_overrides() {
+        let config = parse(
+            r#"
+            ignore = ["W001"]
+            [severity]
+            E015 = "warning"
+
+            [[overrides]]
+            paths = ["legacy", "archive/old"]
+            select = ["E"]
+            ignore = ["E012"]
+            severity = { E015 = "error" }
+            "#,
+        );
+
+        let settings = config.settings_for(Path::new("legacy/query.bwq"));
+        assert_eq!(settings.select, Some(vec!["E".to_string()]));
+        assert_eq!(settings.ignore, vec!["W001", "E012"]);
+        assert_eq!(settings.severity["E015"], Severity::Error);
+
+        let settings = config.settings_for(Path::new("archive/query.bwq"));
+        assert_eq!(settings.select, None);
+        assert_eq!(settings.severity["E015"], Severity::Warning);
+    }
+
+    #[test]
+    fn test_override_paths_are_normalized() {
+        let config = parse(
+            r#"
+            [[overrides]]
+            paths = ["./legacy", "archive/old/"]
+            ignore = ["E012"]
+            "#,
+        );
+
+        for path in [
+            "legacy/query.bwq",
+            "./legacy/query.bwq",
+            "archive/old/query.bwq",
+        ] {
+            assert_eq!(config.settings_for(Path::new(path)).ignore, vec!["E012"]);
+        }
+        assert!(
+            config
+                .settings_for(Path::new("legacy2/query.bwq"))
+                .ignore
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_limits() {
+        let config = parse(
+            r#"
+            [limits]
+            max-characters = 4000
+            max-near-operators = 5
+
+            [[overrides]]
+            paths = ["legacy"]
+            limits = { max-near-operators = 10 }
+            "#,
+        );
+
+        let settings = config.settings_for(Path::new("legacy/query.bwq"));
+        assert_eq!(settings.limits.max_characters, Some(4000));
+        assert_eq!(settings.limits.max_near_operators, Some(10));
+        assert_eq!(
+            config
+                .settings_for(Path::new("query.bwq"))
+                .limits
+                .max_near_operators,
+            Some(5)
+        );
+        assert!(toml::from_str::<Config>("limits = { max-length = 10 }").is_err());
+
+        let linter = &mut crate::BrandwatchLinter::with_settings(RuleSettings {
+            severity: BTreeMap::from([("query-limit".to_string(), Severity::Error)]),
+            ..settings
+        });
+        let report = linter.lint("apple NEAR/2 juice");
+        assert!(report.errors.is_empty());
+        let report = linter.lint(&["(apple NEAR/2 juice)"; 11].join(" OR "));
+        assert_eq!(report.errors[0].code(), "W008");
+    }
+
+    #[test]
+    fn test_discovery() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("queries/nested")).unwrap();
+        fs::create_dir_all(root.join("other")).unwrap();
+        fs::write(root.join("bwq.toml"), "ignore = [\"W001\"]").unwrap();
+        fs::write(root.join("other/pyproject.toml"), "[project]\nname = \"x\"").unwrap();
+        fs::write(
+            root.join("queries/pyproject.toml"),
+            "[tool.bwq]\nextensions = [\"txt\"]",
+        )
+        .unwrap();
+
+        let mut resolver = ConfigResolver::new();
+
+        let nested = resolver
+            .find(&root.join("queries/nested/query.bwq"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(nested.path, root.join("queries/pyproject.toml"));
+        assert_eq!(
+            resolver.extensions_for(&root.join("queries")).unwrap(),
+            Some(vec!["txt".to_string()])
+        );
+
+        // a pyproject.toml without [tool.bwq] is skipped
+        let other = resolver
+            .find(&root.join("other/query.bwq"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(other.path, root.join("bwq.toml"));
+        assert_eq!(
+            resolver
+                .settings_for(&root.join("other/query.bwq"))
+                .unwrap()
+                .ignore,
+            vec!["W001"]
+        );
+    }
+
+    #[test]
+    fn test_locations_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("locations.json"),
+            r#"{"countries": {"gbr": {"name": "United Kingdom"}}}"#,
+        )
+        .unwrap();
+        fs::write(root.join("bwq.toml"), "locations = \"locations.json\"").unwrap();
+
+        let settings = ConfigResolver::new()
+            .settings_for(&root.join("query.bwq"))
+            .unwrap();
+        let locations = settings.locations.unwrap();
+        assert_eq!(locations.country_name("gbr"), Some("United Kingdom"));
+        assert!(locations.country_name("usa").is_none());
+
+        fs::write(root.join("bwq.toml"), "locations = \"missing.json\"").unwrap();
+        assert!(matches!(
+            ConfigResolver::new().settings_for(&root.join("query.bwq")),
+            Err(ConfigError::Locations(LocationsError::Io { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_unknown_selector() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bwq.toml");
+        fs::write(&path, "ignore = [\"unterminated-quote\", \"site-field\"]").unwrap();
+        assert!(Config::load(&path).is_ok());
+
+        fs::write(&path, "select = [\"E0\", \"W012\"]").unwrap();
+        assert!(Config::load(&path).is_ok());
+
+        for selector in ["not-a-rule", "E999", "W9"] {
+            fs::write(&path, format!("select = [\"{selector}\"]")).unwrap();
+            assert!(matches!(
+                Config::load(&path),
+                Err(ConfigError::UnknownSelector { selector: unknown, .. }) if unknown == selector
+            ));
+        }
+    }
+}
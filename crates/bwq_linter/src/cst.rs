@@ -0,0 +1,469 @@
+use std::fmt;
+
+use crate::ast::{Expression, Query};
+use crate::error::{LintError, Position, Span};
+use crate::lexer::{Lexer, TokenType};
+use crate::parser::Parser;
+
+/// kind of a node in the syntax tree; one per `ast::Expression` variant plus the root
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    Query,
+    BooleanOp,
+    Group,
+    Proximity,
+    Field,
+    Range,
+    Term,
+    Error,
+}
+
+/// text between tokens that doesn't change what the query matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriviaKind {
+    Whitespace,
+    Comment,
+    /// characters the lexer rejected and dropped
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub text: String,
+    pub span: Span,
+}
+
+/// a significant token together with the trivia in front of it
+///
+/// the root node always ends with an `Eof` token, which carries any trivia after the last
+/// real token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxToken {
+    pub token_type: TokenType,
+    pub text: String,
+    pub span: Span,
+    pub leading_trivia: Vec<Trivia>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+/// a node of the lossless syntax tree; printing it gives back exactly the source it covers
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxNode {
+    pub kind: NodeKind,
+    pub span: Span,
+    pub children: Vec<SyntaxElement>,
+}
+
+/// a query parsed into both its expression tree and the lossless syntax tree over it
+pub struct SyntaxTree {
+    pub root: SyntaxNode,
+    pub query: Query,
+    pub errors: Vec<LintError>,
+}
+
+/// parse `source` into a syntax tree
+///
+/// the tree is built for any input, including input with syntax errors: text the grammar
+/// can't place ends up as tokens of the nearest enclosing node or as skipped trivia.
+pub fn parse(source: &str) -> SyntaxTree {
+    let mut lexer = Lexer::new(source);
+    let (lexed, mut errors) = lexer.tokenize_with_errors();
+    let result = Parser::new(lexed.clone()).parse();
+    errors.extend(result.errors);
+
+    let chars: Vec<char> = source.chars().collect();
+    let positions = char_positions(&chars);
+
+    // comments are trivia, so only the rest of the tokens take part in the tree
+    let mut comments = result.query.comments.iter().peekable();
+    let mut tokens = Vec::new();
+    let mut trivia_start = 0;
+    for token in lexed {
+        if matches!(
+            token.token_type,
+            TokenType::CommentStart | TokenType::CommentText(_) | TokenType::CommentEnd
+        ) {
+            continue;
+        }
+
+        let start = token.span.start.offset;
+        let mut leading_trivia = Vec::new();
+        let mut offset = trivia_start;
+        while offset < start {
+            while comments
+                .peek()
+                .is_some_and(|comment| comment.span.end.offset <= offset)
+            {
+                comments.next();
+            }
+
+            let (kind, end) = match comments.peek() {
+                Some(comment) if comment.span.start.offset == offset => {
+                    (TriviaKind::Comment, comment.span.end.offset.min(start))
+                }
+                _ => {
+                    let next_comment = comments
+                        .peek()
+                        .map_or(start, |comment| comment.span.start.offset.min(start));
+                    let whitespace = chars[offset].is_whitespace();
+                    let run = chars[offset..next_comment]
+                        .iter()
+                        .take_while(|ch| ch.is_whitespace() == whitespace)
+                        .count();
+                    let kind = if whitespace {
+                        TriviaKind::Whitespace
+                    } else {
+                        TriviaKind::Skipped
+                    };
+                    (kind, offset + run)
+                }
+            };
+
+            leading_trivia.push(Trivia {
+                kind,
+                text: chars[offset..end].iter().collect(),
+                span: Span::new(positions[offset].clone(), positions[end].clone()),
+            });
+            offset = end;
+        }
+
+        trivia_start = token.span.end.offset;
+        tokens.push(SyntaxToken {
+            text: chars[start..token.span.end.offset].iter().collect(),
+            token_type: token.token_type,
+            span: token.span,
+            leading_trivia,
+        });
+    }
+
+    let mut builder = TreeBuilder {
+        tokens: tokens.into_iter().peekable(),
+    };
+    let mut children = Vec::new();
+    builder.take_tokens_before(result.query.expression.span().start.offset, &mut children);
+    children.push(SyntaxElement::Node(builder.node(&result.query.expression)));
+    builder.take_tokens_before(usize::MAX, &mut children);
+
+    let root = SyntaxNode {
+        kind: NodeKind::Query,
+        span: Span::new(positions[0].clone(), positions[chars.len()].clone()),
+        children,
+    };
+
+    SyntaxTree {
+        root,
+        query: result.query,
+        errors,
+    }
+}
+
+struct TreeBuilder<I: Iterator<Item = SyntaxToken>> {
+    tokens: std::iter::Peekable<I>,
+}
+
+impl<I: Iterator<Item = SyntaxToken>> TreeBuilder<I> {
+    /// every token is placed exactly once, in source order: a node owns the tokens inside its
+    /// span that none of its children claimed
+    fn node(&mut self, expr: &Expression) -> SyntaxNode {
+        let mut children = Vec::new();
+
+        for child in sub_expressions(expr) {
+            self.take_tokens_before(child.span().start.offset, &mut children);
+            children.push(SyntaxElement::Node(self.node(child)));
+        }
+        self.take_tokens_before(expr.span().end.offset, &mut children);
+
+        SyntaxNode {
+            kind: node_kind(expr),
+            span: expr.span().clone(),
+            children,
+        }
+    }
+
+    fn take_tokens_before(&mut self, offset: usize, children: &mut Vec<SyntaxElement>) {
+        while let Some(token) = self
+            .tokens
+            .next_if(|token| token.span.start.offset < offset)
+        {
+            children.push(SyntaxElement::Token(token));
+        }
+    }
+}
+
+fn sub_expressions(expr: &Expression) -> Vec<&Expression> {
+    match expr {
+        Expression::BooleanOp { left, right, .. } => {
+            let mut children = vec![left.as_ref()];
+            children.extend(right.as_deref());
+            children
+        }
+        Expression::Group { expression, .. } => vec![expression.as_ref()],
+        Expression::Proximity { terms, .. } => terms.iter().collect(),
+        Expression::Field { value, .. } => vec![value.as_ref()],
+        Expression::Range { .. } | Expression::Term { .. } | Expression::Error { .. } => Vec::new(),
+    }
+}
+
+fn node_kind(expr: &Expression) -> NodeKind {
+    match expr {
+        Expression::BooleanOp { .. } => NodeKind::BooleanOp,
+        Expression::Group { .. } => NodeKind::Group,
+        Expression::Proximity { .. } => NodeKind::Proximity,
+        Expression::Field { .. } => NodeKind::Field,
+        Expression::Range { .. } => NodeKind::Range,
+        Expression::Term { .. } => NodeKind::Term,
+        Expression::Error { .. } => NodeKind::Error,
+    }
+}
+
+/// the position of every char offset in `chars`, plus the one just past the end
+fn char_positions(chars: &[char]) -> Vec<Position> {
+    let mut positions = Vec::with_capacity(chars.len() + 1);
+    let (mut line, mut column) = (1, 1);
+
+    for (offset, &ch) in chars.iter().enumerate() {
+        positions.push(Position::new(line, column, offset));
+        if ch == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+    positions.push(Position::new(line, column, chars.len()));
+
+    positions
+}
+
+impl SyntaxNode {
+    pub fn child_nodes(&self) -> impl Iterator<Item = &SyntaxNode> {
+        self.children.iter().filter_map(|child| match child {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+
+    /// every token under this node, in source order
+    pub fn tokens(&self) -> Vec<&SyntaxToken> {
+        let mut tokens = Vec::new();
+        self.collect_tokens(&mut tokens);
+        tokens
+    }
+
+    fn collect_tokens<'a>(&'a self, tokens: &mut Vec<&'a SyntaxToken>) {
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => node.collect_tokens(tokens),
+                SyntaxElement::Token(token) => tokens.push(token),
+            }
+        }
+    }
+
+    /// comments directly in front of this node
+    pub fn leading_comments(&self) -> Vec<&Trivia> {
+        self.tokens()
+            .first()
+            .map(|token| token.comments().collect())
+            .unwrap_or_default()
+    }
+
+    /// every comment inside this node, not counting the ones in front of it
+    pub fn inner_comments(&self) -> Vec<&Trivia> {
+        self.tokens()
+            .into_iter()
+            .skip(1)
+            .flat_map(|token| token.comments())
+            .collect()
+    }
+}
+
+impl SyntaxTree {
+    /// every comment in the query, in source order, with whether it sits on a line of its own
+    pub fn comments(&self) -> Vec<(&Trivia, bool)> {
+        let tokens = self.root.tokens();
+        let mut comments = Vec::new();
+
+        for (index, token) in tokens.iter().enumerate() {
+            let trivia = &token.leading_trivia;
+            for (i, comment) in trivia.iter().enumerate() {
+                if comment.kind != TriviaKind::Comment {
+                    continue;
+                }
+                // the nearest trivia on either side decides: a line break means the comment's
+                // line ends there, anything else shares it
+                let line_break = |trivia: &Trivia| match trivia.kind {
+                    TriviaKind::Whitespace => trivia.text.contains('\n').then_some(true),
+                    TriviaKind::Comment | TriviaKind::Skipped => Some(false),
+                };
+                let alone_before = trivia[..i]
+                    .iter()
+                    .rev()
+                    .find_map(line_break)
+                    .unwrap_or(index == 0);
+                let alone_after = trivia[i + 1..]
+                    .iter()
+                    .find_map(line_break)
+                    .unwrap_or(token.token_type == TokenType::Eof);
+                comments.push((comment, alone_before && alone_after));
+            }
+        }
+
+        comments
+    }
+}
+
+impl SyntaxToken {
+    pub fn comments(&self) -> impl Iterator<Item = &Trivia> {
+        self.leading_trivia
+            .iter()
+            .filter(|trivia| trivia.kind == TriviaKind::Comment)
+    }
+}
+
+impl fmt::Display for SyntaxToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for trivia in &self.leading_trivia {
+            f.write_str(&trivia.text)?;
+        }
+        f.write_str(&self.text)
+    }
+}
+
+impl fmt::Display for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => write!(f, "{node}")?,
+                SyntaxElement::Token(token) => write!(f, "{token}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn kinds(node: &SyntaxNode) -> Vec<NodeKind> {
+        node.child_nodes().map(|child| child.kind).collect()
+    }
+
+    #[test]
+    fn test_round_trips_fixtures() {
+        for dir in [
+            "resources/test/fixtures/valid",
+            "resources/test/fixtures/invalid",
+        ] {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                let source = std::fs::read_to_string(&path).unwrap();
+
+                let tree = parse(&source);
+                assert_eq!(tree.root.to_string(), source, "{}", path.display());
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_trips_broken_input() {
+        for source in [
+            "",
+            "  \n",
+            "apple ~5x OR juice",
+            "apple AND (juice OR",
+            "apple) OR juice",
+            "apple <<<unclosed comment",
+            "apple >>> juice",
+            "\"unterminated phrase",
+            "apple ^ juice",
+            "title:",
+        ] {
+            assert_eq!(parse(source).root.to_string(), source);
+        }
+    }
+
+    #[test]
+    fn test_tree_shape() {
+        let tree = parse("apple AND (juice OR \"orange juice\"~5)");
+
+        let root = &tree.root;
+        assert_eq!(kinds(root), vec![NodeKind::BooleanOp]);
+
+        let and = root.child_nodes().next().unwrap();
+        assert_eq!(kinds(and), vec![NodeKind::Term, NodeKind::Group]);
+        assert_eq!(and.tokens()[1].token_type, TokenType::And);
+
+        let group = and.child_nodes().nth(1).unwrap();
+        assert_eq!(group.to_string(), " (juice OR \"orange juice\"~5)");
+
+        let or = group.child_nodes().next().unwrap();
+        let proximity = or.child_nodes().nth(1).unwrap();
+        assert_eq!(proximity.kind, NodeKind::Proximity);
+        assert_eq!(proximity.to_string(), " \"orange juice\"~5");
+    }
+
+    #[test]
+    fn test_comments_are_trivia() {
+        let tree =
+            parse("<<<fruit>>> apple AND <<<drinks>>>\n(juice <<<more>>> OR orange) <<<end>>>");
+        let and = tree.root.child_nodes().next().unwrap();
+        let group = and.child_nodes().nth(1).unwrap();
+
+        let comments = |trivia: Vec<&Trivia>| -> Vec<String> {
+            trivia.iter().map(|t| t.text.clone()).collect()
+        };
+
+        assert_eq!(comments(and.leading_comments()), vec!["<<<fruit>>>"]);
+        assert_eq!(comments(group.leading_comments()), vec!["<<<drinks>>>"]);
+        assert_eq!(comments(group.inner_comments()), vec!["<<<more>>>"]);
+
+        let eof = tree.root.tokens().pop().unwrap();
+        assert_eq!(eof.token_type, TokenType::Eof);
+        assert_eq!(eof.comments().next().unwrap().text, "<<<end>>>");
+        assert_eq!(eof.comments().next().unwrap().span.start.line, 2);
+    }
+
+    #[test]
+    fn test_standalone_comments() {
+        let tree = parse("<<<fruit>>>\napple <<<note>>> AND\n  <<<drinks>>>  \njuice\n<<<end>>>");
+        let comments: Vec<_> = tree
+            .comments()
+            .into_iter()
+            .map(|(comment, standalone)| (comment.text.as_str(), standalone))
+            .collect();
+
+        assert_eq!(
+            comments,
+            vec![
+                ("<<<fruit>>>", true),
+                ("<<<note>>>", false),
+                ("<<<drinks>>>", true),
+                ("<<<end>>>", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_skipped_characters_are_trivia() {
+        let tree = parse("apple ~5x OR juice");
+        let skipped: Vec<_> = tree
+            .root
+            .tokens()
+            .into_iter()
+            .flat_map(|token| &token.leading_trivia)
+            .filter(|trivia| trivia.kind == TriviaKind::Skipped)
+            .map(|trivia| trivia.text.as_str())
+            .collect();
+
+        assert_eq!(skipped, vec!["x"]);
+        assert!(!tree.errors.is_empty());
+    }
+}
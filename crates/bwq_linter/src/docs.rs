@@ -0,0 +1,445 @@
+use crate::ast::FieldType;
+
+/// documentation for an operator or field, as given in `brandwatch-query-operators.md`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OperatorDoc {
+    /// how the operator is written, e.g. `NEAR/x` or `authorGender:`
+    pub syntax: &'static str,
+    pub description: &'static str,
+    /// the accepted values, for fields with a closed vocabulary
+    pub values: &'static [&'static str],
+    pub example: &'static str,
+}
+
+impl OperatorDoc {
+    const fn new(
+        syntax: &'static str,
+        description: &'static str,
+        values: &'static [&'static str],
+        example: &'static str,
+    ) -> Self {
+        Self {
+            syntax,
+            description,
+            values,
+            example,
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("**`{}`**\n\n{}", self.syntax, self.description);
+
+        if !self.values.is_empty() {
+            let values: Vec<_> = self.values.iter().map(|v| format!("`{v}`")).collect();
+            markdown.push_str(&format!("\n\nValues: {}", values.join(", ")));
+        }
+
+        markdown.push_str(&format!("\n\n```bwq\n{}\n```", self.example));
+        markdown
+    }
+}
+
+/// operators that aren't fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    And,
+    Or,
+    Not,
+    Phrase,
+    Group,
+    Proximity,
+    Near,
+    NearForward,
+    Wildcard,
+    Replacement,
+    CaseSensitive,
+    Comment,
+}
+
+pub const BOOLEAN_VALUES: &[&str] = &["true", "false"];
+
+pub fn operator_doc(operator: Operator) -> OperatorDoc {
+    match operator {
+        Operator::And => OperatorDoc::new(
+            "AND",
+            "Will find mentions of both terms on the **same** webpage. Must be capitalized.",
+            &[],
+            "apple AND juice",
+        ),
+        Operator::Or => OperatorDoc::new(
+            "OR",
+            "Will find mentions of either term on any webpage. Must be capitalized.",
+            &[],
+            "apple OR juice",
+        ),
+        Operator::Not => OperatorDoc::new(
+            "NOT",
+            "Will find mentions of the first term on a page as long as the second is not mentioned on that page. Must be capitalized.",
+            &[],
+            "apple NOT juice",
+        ),
+        Operator::Phrase => OperatorDoc::new(
+            "\" \"",
+            "Will find mentions of the exact phrase on any webpage.",
+            &[],
+            "\"apple juice\"",
+        ),
+        Operator::Group => OperatorDoc::new(
+            "( )",
+            "Groups terms so operators apply to them together. Needed to mix AND and OR in one query.",
+            &[],
+            "(apple AND juice) OR (apple AND sauce)",
+        ),
+        Operator::Proximity => OperatorDoc::new(
+            "~",
+            "Will find mentions of the exact phrase, and of its words within the given number of words of each other. Following two groups of keywords, finds them within that many words of each other; this form does not allow more than one layer of nesting (use `NEAR/x` instead).",
+            &[],
+            "\"apple juice\"~5",
+        ),
+        Operator::Near => OperatorDoc::new(
+            "NEAR/x",
+            "Will find mentions of the left terms within x words of the right terms, in either order.",
+            &[],
+            "((apple OR orange) NEAR/5 (smartphone OR phone))",
+        ),
+        Operator::NearForward => OperatorDoc::new(
+            "NEAR/xf",
+            "Will find mentions where the left terms appear before the right terms with x or fewer words in between.",
+            &[],
+            "(logitech NEAR/2f keyboard)",
+        ),
+        Operator::Wildcard => OperatorDoc::new(
+            "*",
+            "Will find mentions of words starting with the root, e.g. 'complain', 'complaints', 'complained'. Short wildcards (2 characters) are limited to 150 expansions. Only compatible with plain text and can only be used within or at the end of a word, not at the beginning.",
+            &[],
+            "complain*",
+        ),
+        Operator::Replacement => OperatorDoc::new(
+            "?",
+            "Stands for any single letter, to accommodate variations of spelling such as 'customise' and 'customize'.",
+            &[],
+            "customi?e",
+        ),
+        Operator::CaseSensitive => OperatorDoc::new(
+            "{ }",
+            "Will only retrieve mentions where upper case letters are used in exactly the way specified inside the braces. Works for words with up to 20 characters.",
+            &[],
+            "{BT}",
+        ),
+        Operator::Comment => OperatorDoc::new(
+            "<<< >>>",
+            "Adds your own comments to the query string. Does not work within a bracketed string.",
+            &[],
+            "<<<my comment here>>>",
+        ),
+    }
+}
+
+pub fn field_doc(field: &FieldType) -> OperatorDoc {
+    match field {
+        FieldType::Title => OperatorDoc::new(
+            "title:",
+            "Will find mentions where the term appears in the page title.",
+            &[],
+            "title:\"apple juice\"",
+        ),
+        FieldType::Site => OperatorDoc::new(
+            "site:",
+            "Will find mentions on a particular site. Be sure not to include 'www.'.",
+            &[],
+            "site:twitter.com AND \"apple juice\"",
+        ),
+        FieldType::Url => OperatorDoc::new(
+            "url:",
+            "Will find mentions on a particular part of a site.",
+            &[],
+            "url:\"msn.com/news\" AND \"Simon Cowell\"",
+        ),
+        FieldType::Author => OperatorDoc::new(
+            "author:",
+            "Will find mentions with a specific author name. Does not work with Tumblr (see `blogName:`) and some forums (see `weblogTitle:`). \".\" is treated as a space, so quote author names that include one and replace the \".\" with a space.",
+            &[],
+            "author:ladygaga",
+        ),
+        FieldType::Links => OperatorDoc::new(
+            "links:",
+            "For X (Twitter) only, will find mentions containing links to the given website, including shortened links.",
+            &[],
+            "links:msn.com",
+        ),
+        FieldType::Continent => OperatorDoc::new(
+            "continent:",
+            "Will only find mentions identified as from the given continent.",
+            &[],
+            "continent:europe AND \"apple juice\"",
+        ),
+        FieldType::Country => OperatorDoc::new(
+            "country:",
+            "Will only find mentions identified as from the given country, written as its location code.",
+            &[],
+            "country:gbr AND \"apple juice\"",
+        ),
+        FieldType::Region => OperatorDoc::new(
+            "region:",
+            "Will only find mentions identified as from the given state, region or province: a country code followed by the region's name or abbreviation.",
+            &[],
+            "region:usa.fl AND \"apple juice\"",
+        ),
+        FieldType::City => OperatorDoc::new(
+            "city:",
+            "Will only find mentions identified as from the given city: a country code, then a region code, then a city code. Quote city codes that contain a space.",
+            &[],
+            "city:\"deu.berlin.berlin\" AND \"apple juice\"",
+        ),
+        FieldType::Latitude => OperatorDoc::new(
+            "latitude:",
+            "Restricts mentions to a range of latitudes. Can be used alone or with `longitude:`.",
+            &[],
+            "latitude:[41 TO 44] AND longitude:[-73 TO -69]",
+        ),
+        FieldType::Longitude => OperatorDoc::new(
+            "longitude:",
+            "Restricts mentions to a range of longitudes. Can be used alone or with `latitude:`.",
+            &[],
+            "longitude:[-73 TO -69] AND latitude:[41 TO 44]",
+        ),
+        FieldType::Language => OperatorDoc::new(
+            "language:",
+            "Will only find mentions in the specified language, written as an ISO 639-1 code.",
+            &[],
+            "language:en",
+        ),
+        FieldType::ChannelId => OperatorDoc::new(
+            "channelId:",
+            "Will find mentions on a specified channel.",
+            &[],
+            "channelId:\"141273439246434\" AND \"summer\"",
+        ),
+        FieldType::AuthorGender => OperatorDoc::new(
+            "authorGender:",
+            "The gender of the authors you are interested in, for Facebook, Forums, Reviews and X (Twitter) data.",
+            &["F", "M"],
+            "authorGender:F",
+        ),
+        FieldType::AuthorVerified => OperatorDoc::new(
+            "authorVerified:",
+            "Searches for posts by X (Twitter) verified authors.",
+            BOOLEAN_VALUES,
+            "authorVerified:true",
+        ),
+        FieldType::AuthorVerifiedType => OperatorDoc::new(
+            "authorVerifiedType:",
+            "Searches for posts by specific X (Twitter) verified authors. Values must be written all lowercase.",
+            &["blue", "business", "government"],
+            "authorVerifiedType:blue",
+        ),
+        FieldType::AuthorFollowers => OperatorDoc::new(
+            "authorFollowers:",
+            "Will find posts by X (Twitter) authors with a follower count within the specified range.",
+            &[],
+            "authorFollowers:[500 TO 100000]",
+        ),
+        FieldType::BlogName => OperatorDoc::new(
+            "blogName:",
+            "Tracks original and reblogged mentions from a specific Tumblr page.",
+            &[],
+            "blogName:comedycentral",
+        ),
+        FieldType::ParentBlogName => OperatorDoc::new(
+            "parentBlogName:",
+            "Tracks content reblogged from a specific Tumblr page.",
+            &[],
+            "parentBlogName:comedycentral",
+        ),
+        FieldType::RootBlogName => OperatorDoc::new(
+            "rootBlogName:",
+            "Tracks mentions reblogged from a specific Tumblr page and reblogs of that same content.",
+            &[],
+            "rootBlogName:comedycentral",
+        ),
+        FieldType::ParentPostId => OperatorDoc::new(
+            "parentPostId:",
+            "Tracks reblogs of a specific post ID on Reddit and Tumblr, and reblogs of those.",
+            &[],
+            "parentPostId:129596930020",
+        ),
+        FieldType::RootPostId => OperatorDoc::new(
+            "rootPostId:",
+            "Tracks reblogs of a specific post on Reddit and Tumblr, and reblogs of those.",
+            &[],
+            "rootPostId:129596930020",
+        ),
+        FieldType::Tags => OperatorDoc::new(
+            "tags:",
+            "Tracks mentions that include the specified tag on Tumblr.",
+            &[],
+            "tags:photography",
+        ),
+        FieldType::BrandIds => OperatorDoc::new(
+            "brandIds:",
+            "Tracks mentions that contain a logo from the specified logo brand ID.",
+            &[],
+            "brandIds:12 AND brandIds:14",
+        ),
+        FieldType::Objects => OperatorDoc::new(
+            "objects:",
+            "Tracks mentions that contain an object within an image that relates to the object ID.",
+            &[],
+            "objects:3902",
+        ),
+        FieldType::EngagementType => OperatorDoc::new(
+            "engagementType:",
+            "Tracks comments, replies, reposts (retweets) or quote posts. Values are case-sensitive.",
+            &["COMMENT", "REPLY", "RETWEET", "QUOTE"],
+            "engagementType:QUOTE",
+        ),
+        FieldType::EngagingWith => OperatorDoc::new(
+            "engagingWith:",
+            "Tracks replies or reposts (retweets) of a specific X (Twitter) handle. Also tracks owned Instagram accounts.",
+            &[],
+            "engagingWith:brandwatch",
+        ),
+        FieldType::EngagingWithGuid => OperatorDoc::new(
+            "engagingWithGuid:",
+            "Tracks reposts (retweets) and replies to a specific X (Twitter) post ID, or replies to a specific Facebook post ID. Use `parentPostId:` and `rootPostId:` for Reddit.",
+            &[],
+            "engagingWithGuid:857284431631011841",
+        ),
+        FieldType::Guid => OperatorDoc::new(
+            "guid:",
+            "Tracks a specific post. Facebook posts are written as PageID_PostID.",
+            &[],
+            "guid:857284431631011841",
+        ),
+        FieldType::ImageType => OperatorDoc::new(
+            "imageType:",
+            "Tracks posts that contain images only.",
+            &["image"],
+            "imageType:image",
+        ),
+        FieldType::ItemReview => OperatorDoc::new(
+            "itemReview:",
+            "Tracks reviews of a specific product by product name.",
+            &[],
+            "site:\"amazon.com\" AND itemReview:(\"Xbox One\")",
+        ),
+        FieldType::Rating => OperatorDoc::new(
+            "rating:",
+            "Tracks reviews with the given star rating, or a range of ratings.",
+            &["1", "2", "3", "4", "5"],
+            "rating:[3 TO 5]",
+        ),
+        FieldType::MinuteOfDay => OperatorDoc::new(
+            "minuteOfDay:",
+            "Tracks mentions published within a range of minutes of the day (0 to 1439, in UTC).",
+            &[],
+            "minuteOfDay:[1110 TO 1140]",
+        ),
+        FieldType::PubType => OperatorDoc::new(
+            "pubType:",
+            "Specific to custom content sources: returns mentions uploaded via the Content Upload API under the user-defined name.",
+            &[],
+            "pubType:MY_MENTIONS",
+        ),
+        FieldType::PublisherSubType => OperatorDoc::new(
+            "publisherSubType:",
+            "Tracks Instagram posts that include images only or videos only.",
+            &["IMAGE", "VIDEO"],
+            "publisherSubType:IMAGE",
+        ),
+        FieldType::Publication => OperatorDoc::new(
+            "publication:",
+            "Returns mentions from news sources where a publication name is given rather than a site name. Only compatible with rules and dashboard searches, not queries.",
+            &[],
+            "publication:\"MSN UK\"",
+        ),
+        FieldType::RedditAuthorFlair => OperatorDoc::new(
+            "redditAuthorFlair:",
+            "Will find Reddit posts and comments where the author flair has been assigned.",
+            &[],
+            "redditAuthorFlair:PhD",
+        ),
+        FieldType::RedditPostFlair => OperatorDoc::new(
+            "redditPostFlair:",
+            "Will find Reddit posts and comments where the post flair has been assigned.",
+            &[],
+            "redditPostFlair:Advice",
+        ),
+        FieldType::RedditSpoiler => OperatorDoc::new(
+            "redditSpoiler:",
+            "Will find Reddit posts and comments marked as containing spoilers.",
+            BOOLEAN_VALUES,
+            "redditSpoiler:true",
+        ),
+        FieldType::SensitiveContent => OperatorDoc::new(
+            "sensitiveContent:",
+            "Will find X (Twitter) posts and comments flagged as sensitive content.",
+            BOOLEAN_VALUES,
+            "sensitiveContent:true",
+        ),
+        FieldType::Subreddit => OperatorDoc::new(
+            "subreddit:",
+            "Returns all posts belonging to the named subreddits.",
+            &[],
+            "subreddit:nba",
+        ),
+        FieldType::SubredditNSFW => OperatorDoc::new(
+            "subredditNSFW:",
+            "Will find mentions from subreddits that have been flagged as NSFW.",
+            BOOLEAN_VALUES,
+            "subredditNSFW:true",
+        ),
+        FieldType::SubredditTopics => OperatorDoc::new(
+            "subredditTopics:",
+            "Returns all posts which match the human-readable subreddit topics.",
+            &[],
+            "subredditTopics:\"Food & Recipes\"",
+        ),
+        FieldType::TopLevelDomain => OperatorDoc::new(
+            "topLevelDomain:",
+            "Tracks mentions from all sites ending in the given top-level domain.",
+            &[],
+            "topLevelDomain:com OR topLevelDomain:org",
+        ),
+        FieldType::WeblogTitle => OperatorDoc::new(
+            "weblogTitle:",
+            "Tracks mentions created by a specific Reddit or forum author.",
+            &[],
+            "weblogTitle:feelslikegold",
+        ),
+        FieldType::Sentiment => OperatorDoc::new(
+            "sentiment:",
+            "Tracks mentions with the given sentiment.",
+            &["positive", "negative", "neutral"],
+            "sentiment:positive",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_doc_markdown() {
+        assert_eq!(
+            field_doc(&FieldType::AuthorVerifiedType).to_markdown(),
+            "**`authorVerifiedType:`**
+
+Searches for posts by specific X (Twitter) verified authors. Values must be written all lowercase.
+
+Values: `blue`, `business`, `government`
+
+```bwq
+authorVerifiedType:blue
+```"
+        );
+    }
+
+    #[test]
+    fn test_field_syntax_matches_name() {
+        let field = FieldType::parse("engagingwithguid").unwrap();
+        assert_eq!(field_doc(&field).syntax, format!("{}:", field.as_str()));
+    }
+}
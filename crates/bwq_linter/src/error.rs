@@ -43,6 +43,104 @@ impl Span {
     }
 }
 
+/// replace the text covered by `span` with `content`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edit {
+    pub span: Span,
+    pub content: String,
+}
+
+impl Edit {
+    pub fn replacement(span: Span, content: impl Into<String>) -> Self {
+        Self {
+            span,
+            content: content.into(),
+        }
+    }
+
+    pub fn insertion(at: Position, content: impl Into<String>) -> Self {
+        Self::replacement(Span::single(at), content)
+    }
+
+    pub fn deletion(span: Span) -> Self {
+        Self::replacement(span, "")
+    }
+}
+
+/// whether a fix can be applied without the user reviewing it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Applicability {
+    /// keeps what the query means, or what it was evidently meant to mean
+    Safe,
+    /// may change what the query matches
+    Unsafe,
+}
+
+/// a correction for a diagnostic
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fix {
+    pub message: String,
+    pub edits: Vec<Edit>,
+    pub applicability: Applicability,
+}
+
+impl Fix {
+    pub fn safe(message: impl Into<String>, edits: Vec<Edit>) -> Self {
+        Self {
+            message: message.into(),
+            edits,
+            applicability: Applicability::Safe,
+        }
+    }
+
+    pub fn unsafe_edit(message: impl Into<String>, edits: Vec<Edit>) -> Self {
+        Self {
+            message: message.into(),
+            edits,
+            applicability: Applicability::Unsafe,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let edits: Vec<_> = self
+            .edits
+            .iter()
+            .map(|edit| {
+                serde_json::json!({
+                    "content": edit.content,
+                    "span": span_json(&edit.span)
+                })
+            })
+            .collect();
+
+        serde_json::json!({
+            "message": self.message,
+            "applicability": match self.applicability {
+                Applicability::Safe => "safe",
+                Applicability::Unsafe => "unsafe",
+            },
+            "edits": edits
+        })
+    }
+}
+
+fn span_json(span: &Span) -> serde_json::Value {
+    serde_json::json!({
+        "start": {"line": span.start.line, "column": span.start.column, "offset": span.start.offset},
+        "end": {"line": span.end.line, "column": span.end.column, "offset": span.end.offset}
+    })
+}
+
+/// add a "fix" key to a diagnostic's JSON when it has one
+fn with_fix(mut json: serde_json::Value, fix: Option<&Fix>) -> serde_json::Value {
+    if let Some(fix) = fix
+        && let Some(object) = json.as_object_mut()
+    {
+        object.insert("fix".to_string(), fix.to_json());
+    }
+    json
+}
+
 #[derive(Error, Debug, Clone, PartialEq)]
 pub enum LintError {
     #[error("{message}")]
@@ -51,11 +149,15 @@ pub enum LintError {
     #[error("{message}")]
     ParserError { span: Span, message: String },
 
-    #[error("{message}")]
-    ValidationError { span: Span, message: String },
+    #[error("Please add a >>> mark to close this commented text.")]
+    UnclosedComment { span: Span, fix: Option<Box<Fix>> },
 
     #[error("Boolean operator '{operator}' must be capitalized")]
-    InvalidBooleanCase { span: Span, operator: String },
+    InvalidBooleanCase {
+        span: Span,
+        operator: String,
+        fix: Option<Box<Fix>>,
+    },
 
     #[error("Unbalanced parentheses")]
     UnbalancedParentheses { span: Span },
@@ -82,23 +184,100 @@ pub enum LintError {
         found: String,
     },
 
+    /// a `rating:` value outside 0 to 5
     #[error("{message}")]
-    FieldValidationError { span: Span, message: String },
+    InvalidRating { span: Span, message: String },
 
     #[error("{message}")]
     ProximityOperatorError { span: Span, message: String },
 
     #[error("{message}")]
-    RangeValidationError { span: Span, message: String },
+    RangeValidationError {
+        span: Span,
+        message: String,
+        fix: Option<Box<Fix>>,
+    },
 
     #[error("{message}")]
-    OperatorMixingError { span: Span, message: String },
+    OperatorMixingError {
+        span: Span,
+        message: String,
+        fix: Option<Box<Fix>>,
+    },
 
     #[error("{message}")]
     PureNegativeQueryError { span: Span, message: String },
 
     #[error("{message}")]
     InvalidFieldOperatorSpacing { span: Span, message: String },
+
+    #[error("{message}")]
+    MissingOperand { span: Span, message: String },
+
+    #[error("{message}")]
+    InvalidTildeUsage { span: Span, message: String },
+
+    #[error("This wildcard matches too many unique terms. Please make it more specific.")]
+    BroadWildcard { span: Span },
+
+    #[error("{message}")]
+    EmptyTerm { span: Span, message: String },
+
+    #[error("Unterminated quoted string")]
+    UnterminatedQuote { span: Span },
+
+    /// `{}` around something that can't be matched case-sensitively
+    #[error("{message}")]
+    CaseSensitiveError { span: Span, message: String },
+
+    /// a `latitude:` or `longitude:` value outside the range of coordinates
+    #[error("{message}")]
+    InvalidCoordinate { span: Span, message: String },
+
+    /// a value other than `true` or `false` for a boolean field such as `authorVerified:`
+    #[error("{message}")]
+    InvalidBooleanValue {
+        span: Span,
+        message: String,
+        fix: Option<Box<Fix>>,
+    },
+
+    /// an `authorVerifiedType:` value other than `blue`, `business` or `government`
+    #[error("{message}")]
+    InvalidVerifiedType { span: Span, message: String },
+
+    /// a `minuteOfDay:` value outside 0 to 1439
+    #[error("{message}")]
+    InvalidMinuteOfDay { span: Span, message: String },
+
+    /// a `site:`, `url:`, `links:` or `topLevelDomain:` value that isn't written as Brandwatch
+    /// expects, e.g. with a scheme
+    #[error("{message}")]
+    InvalidDomain {
+        span: Span,
+        message: String,
+        fix: Option<Box<Fix>>,
+    },
+
+    /// a hostname with a part that isn't letters, digits and hyphens, e.g. `bbc_news.com`
+    #[error("{message}")]
+    InvalidHostname { span: Span, message: String },
+
+    /// a `site:` domain without a dot, e.g. `site:bbc`
+    #[error("{message}")]
+    IncompleteDomain { span: Span, message: String },
+
+    /// a `site:` domain starting with `www.`, which leaves out the site's other subdomains
+    #[error("{message}")]
+    SiteWwwPrefix {
+        span: Span,
+        message: String,
+        fix: Option<Box<Fix>>,
+    },
+
+    /// a warning reported as an error because of a severity override
+    #[error("{warning}")]
+    Promoted { warning: Box<LintWarning> },
 }
 
 impl LintError {
@@ -106,7 +285,7 @@ impl LintError {
         match self {
             LintError::LexerError { .. } => "E001",
             LintError::ParserError { .. } => "E002",
-            LintError::ValidationError { .. } => "E003",
+            LintError::UnclosedComment { .. } => "E003",
             LintError::InvalidBooleanCase { .. } => "E004",
             LintError::UnbalancedParentheses { .. } => "E005",
             LintError::InvalidWildcardPlacement { .. } => "E006",
@@ -115,20 +294,35 @@ impl LintError {
             LintError::InvalidRangeSyntax { .. } => "E009",
             LintError::UnexpectedToken { .. } => "E010",
             LintError::ExpectedToken { .. } => "E011",
-            LintError::FieldValidationError { .. } => "E012",
+            LintError::InvalidRating { .. } => "E012",
             LintError::ProximityOperatorError { .. } => "E013",
             LintError::RangeValidationError { .. } => "E014",
             LintError::OperatorMixingError { .. } => "E015",
             LintError::PureNegativeQueryError { .. } => "E016",
             LintError::InvalidFieldOperatorSpacing { .. } => "E017",
+            LintError::MissingOperand { .. } => "E018",
+            LintError::InvalidTildeUsage { .. } => "E019",
+            LintError::BroadWildcard { .. } => "E020",
+            LintError::EmptyTerm { .. } => "E021",
+            LintError::UnterminatedQuote { .. } => "E022",
+            LintError::CaseSensitiveError { .. } => "E023",
+            LintError::InvalidCoordinate { .. } => "E024",
+            LintError::InvalidBooleanValue { .. } => "E025",
+            LintError::InvalidVerifiedType { .. } => "E026",
+            LintError::InvalidMinuteOfDay { .. } => "E027",
+            LintError::InvalidDomain { .. } => "E028",
+            LintError::InvalidHostname { .. } => "E029",
+            LintError::IncompleteDomain { .. } => "E030",
+            LintError::SiteWwwPrefix { .. } => "E031",
+            LintError::Promoted { warning } => warning.code(),
         }
     }
 
-    pub fn span_json(&self) -> serde_json::Value {
-        let span = match self {
+    pub fn span(&self) -> &Span {
+        match self {
             LintError::LexerError { span, .. }
             | LintError::ParserError { span, .. }
-            | LintError::ValidationError { span, .. }
+            | LintError::UnclosedComment { span, .. }
             | LintError::InvalidBooleanCase { span, .. }
             | LintError::UnbalancedParentheses { span }
             | LintError::InvalidWildcardPlacement { span }
@@ -137,34 +331,139 @@ impl LintError {
             | LintError::InvalidRangeSyntax { span }
             | LintError::UnexpectedToken { span, .. }
             | LintError::ExpectedToken { span, .. }
-            | LintError::FieldValidationError { span, .. }
+            | LintError::InvalidRating { span, .. }
             | LintError::ProximityOperatorError { span, .. }
             | LintError::RangeValidationError { span, .. }
             | LintError::OperatorMixingError { span, .. }
             | LintError::PureNegativeQueryError { span, .. }
-            | LintError::InvalidFieldOperatorSpacing { span, .. } => span,
-        };
+            | LintError::InvalidFieldOperatorSpacing { span, .. }
+            | LintError::MissingOperand { span, .. }
+            | LintError::InvalidTildeUsage { span, .. }
+            | LintError::BroadWildcard { span }
+            | LintError::EmptyTerm { span, .. }
+            | LintError::UnterminatedQuote { span }
+            | LintError::CaseSensitiveError { span, .. }
+            | LintError::InvalidCoordinate { span, .. }
+            | LintError::InvalidBooleanValue { span, .. }
+            | LintError::InvalidVerifiedType { span, .. }
+            | LintError::InvalidMinuteOfDay { span, .. }
+            | LintError::InvalidDomain { span, .. }
+            | LintError::InvalidHostname { span, .. }
+            | LintError::IncompleteDomain { span, .. }
+            | LintError::SiteWwwPrefix { span, .. } => span,
+            LintError::Promoted { warning } => warning.span(),
+        }
+    }
 
-        serde_json::json!({
-            "start": {"line": span.start.line, "column": span.start.column, "offset": span.start.offset},
-            "end": {"line": span.end.line, "column": span.end.column, "offset": span.end.offset}
-        })
+    pub fn fix(&self) -> Option<&Fix> {
+        match self {
+            LintError::UnclosedComment { fix, .. }
+            | LintError::InvalidBooleanCase { fix, .. }
+            | LintError::InvalidBooleanValue { fix, .. }
+            | LintError::InvalidDomain { fix, .. }
+            | LintError::SiteWwwPrefix { fix, .. }
+            | LintError::RangeValidationError { fix, .. }
+            | LintError::OperatorMixingError { fix, .. } => fix.as_deref(),
+            LintError::Promoted { warning } => warning.fix(),
+            _ => None,
+        }
+    }
+
+    pub fn span_json(&self) -> serde_json::Value {
+        span_json(self.span())
     }
 
     pub fn to_json(&self) -> serde_json::Value {
-        serde_json::json!({
+        let json = serde_json::json!({
             "code": self.code(),
             "message": format!("{}", self),
             "span": self.span_json()
-        })
+        });
+        with_fix(json, self.fix())
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum LintWarning {
-    PotentialTypo { span: Span, suggestion: String },
-    DeprecatedOperator { span: Span, replacement: String },
-    PerformanceWarning { span: Span, message: String },
+    PotentialTypo {
+        span: Span,
+        suggestion: String,
+        fix: Option<Box<Fix>>,
+    },
+    DeprecatedOperator {
+        span: Span,
+        replacement: String,
+    },
+    PerformanceWarning {
+        span: Span,
+        message: String,
+    },
+    /// a `<<<bwq: ...>>>` directive that is malformed or no longer suppresses anything
+    SuppressionWarning {
+        span: Span,
+        message: String,
+    },
+    /// `~` on a single term, which does fuzzy matching rather than proximity
+    SingleTermProximity {
+        span: Span,
+        message: String,
+    },
+    /// a `language:` value that isn't a lowercase ISO 639-1 code
+    InvalidLanguage {
+        span: Span,
+        message: String,
+        fix: Option<Box<Fix>>,
+    },
+    /// a field name in a different case to the field's own, e.g. `authorverified`
+    FieldNameCase {
+        span: Span,
+        expected: String,
+        fix: Option<Box<Fix>>,
+    },
+    /// a query metric above the limit configured for it
+    LimitExceeded {
+        span: Span,
+        metric: String,
+        value: usize,
+        limit: usize,
+    },
+    /// more two-character wildcard stems across the query than Brandwatch allows, or nearly so
+    ShortWildcardBudget {
+        span: Span,
+        count: usize,
+        limit: usize,
+    },
+    /// a `*` where Brandwatch doesn't expand it, e.g. in a quoted phrase or a hashtag
+    UnsupportedWildcard {
+        span: Span,
+        message: String,
+    },
+    /// `{}` that makes no difference to what is matched
+    IneffectiveCaseSensitive {
+        span: Span,
+        message: String,
+        fix: Option<Box<Fix>>,
+    },
+    /// a `continent:`, `country:`, `region:` or `city:` value missing from the location dataset
+    InvalidLocation {
+        span: Span,
+        message: String,
+        fix: Option<Box<Fix>>,
+    },
+    /// an `authorGender:` value other than the ones Brandwatch assigns
+    UnexpectedAuthorGender {
+        span: Span,
+        message: String,
+    },
+    /// an `engagementType:` value other than the common ones
+    UnexpectedEngagementType {
+        span: Span,
+        message: String,
+    },
+    /// an error reported as a warning because of a severity override
+    Demoted {
+        error: Box<LintError>,
+    },
 }
 
 impl std::fmt::Display for LintWarning {
@@ -179,6 +478,36 @@ impl std::fmt::Display for LintWarning {
             LintWarning::PerformanceWarning { message, .. } => {
                 write!(f, "Performance warning: {message}")
             }
+            LintWarning::SuppressionWarning { message, .. }
+            | LintWarning::SingleTermProximity { message, .. }
+            | LintWarning::InvalidLanguage { message, .. }
+            | LintWarning::UnsupportedWildcard { message, .. }
+            | LintWarning::IneffectiveCaseSensitive { message, .. }
+            | LintWarning::InvalidLocation { message, .. }
+            | LintWarning::UnexpectedAuthorGender { message, .. }
+            | LintWarning::UnexpectedEngagementType { message, .. } => write!(f, "{message}"),
+            LintWarning::FieldNameCase { expected, .. } => {
+                write!(
+                    f,
+                    "Field names are case-sensitive. Did you mean '{expected}'?"
+                )
+            }
+            LintWarning::LimitExceeded {
+                metric,
+                value,
+                limit,
+                ..
+            } => write!(f, "Query has {value} {metric}, above the limit of {limit}"),
+            LintWarning::ShortWildcardBudget { count, limit, .. } => write!(
+                f,
+                "Query has {count} short wildcards (two-character stems like 'ab*'), {} Brandwatch's limit of {limit}",
+                if count > limit {
+                    "more than"
+                } else {
+                    "close to"
+                }
+            ),
+            LintWarning::Demoted { error } => write!(f, "{error}"),
         }
     }
 }
@@ -189,6 +518,18 @@ impl LintWarning {
             LintWarning::PotentialTypo { .. } => "W001",
             LintWarning::DeprecatedOperator { .. } => "W002",
             LintWarning::PerformanceWarning { .. } => "W003",
+            LintWarning::SuppressionWarning { .. } => "W004",
+            LintWarning::SingleTermProximity { .. } => "W005",
+            LintWarning::InvalidLanguage { .. } => "W006",
+            LintWarning::FieldNameCase { .. } => "W007",
+            LintWarning::LimitExceeded { .. } => "W008",
+            LintWarning::ShortWildcardBudget { .. } => "W009",
+            LintWarning::UnsupportedWildcard { .. } => "W010",
+            LintWarning::IneffectiveCaseSensitive { .. } => "W011",
+            LintWarning::InvalidLocation { .. } => "W012",
+            LintWarning::UnexpectedAuthorGender { .. } => "W013",
+            LintWarning::UnexpectedEngagementType { .. } => "W014",
+            LintWarning::Demoted { error } => error.code(),
         }
     }
 
@@ -196,20 +537,41 @@ impl LintWarning {
         match self {
             LintWarning::PotentialTypo { span, .. }
             | LintWarning::DeprecatedOperator { span, .. }
-            | LintWarning::PerformanceWarning { span, .. } => span,
+            | LintWarning::PerformanceWarning { span, .. }
+            | LintWarning::SuppressionWarning { span, .. }
+            | LintWarning::SingleTermProximity { span, .. }
+            | LintWarning::InvalidLanguage { span, .. }
+            | LintWarning::FieldNameCase { span, .. }
+            | LintWarning::LimitExceeded { span, .. }
+            | LintWarning::ShortWildcardBudget { span, .. }
+            | LintWarning::UnsupportedWildcard { span, .. }
+            | LintWarning::IneffectiveCaseSensitive { span, .. }
+            | LintWarning::InvalidLocation { span, .. }
+            | LintWarning::UnexpectedAuthorGender { span, .. }
+            | LintWarning::UnexpectedEngagementType { span, .. } => span,
+            LintWarning::Demoted { error } => error.span(),
+        }
+    }
+
+    pub fn fix(&self) -> Option<&Fix> {
+        match self {
+            LintWarning::PotentialTypo { fix, .. }
+            | LintWarning::InvalidLanguage { fix, .. }
+            | LintWarning::FieldNameCase { fix, .. }
+            | LintWarning::IneffectiveCaseSensitive { fix, .. }
+            | LintWarning::InvalidLocation { fix, .. } => fix.as_deref(),
+            LintWarning::Demoted { error } => error.fix(),
+            _ => None,
         }
     }
 
     pub fn to_json(&self) -> serde_json::Value {
-        let span = self.span();
-        serde_json::json!({
+        let json = serde_json::json!({
             "code": self.code(),
             "message": format!("{}", self),
-            "span": {
-                "start": {"line": span.start.line, "column": span.start.column, "offset": span.start.offset},
-                "end": {"line": span.end.line, "column": span.end.column, "offset": span.end.offset}
-            }
-        })
+            "span": span_json(self.span())
+        });
+        with_fix(json, self.fix())
     }
 }
 
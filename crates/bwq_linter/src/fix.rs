@@ -0,0 +1,244 @@
+use crate::BrandwatchLinter;
+use crate::error::{Applicability, Edit, Fix};
+
+/// fixing one problem can reveal another, so fixes are applied over several passes
+const MAX_PASSES: usize = 10;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixResult {
+    pub output: String,
+    /// number of fixes applied across all passes
+    pub applied: usize,
+}
+
+/// apply every available fix to `source`, including unsafe ones when `unsafe_fixes` is set
+pub fn fix_source(linter: &mut BrandwatchLinter, source: &str, unsafe_fixes: bool) -> FixResult {
+    let mut output = source.to_string();
+    let mut applied = 0;
+    // the text before the last pass, how many fixes that pass applied and how many problems
+    // were left before it
+    let mut previous: Option<(String, usize, usize)> = None;
+
+    for _ in 0..MAX_PASSES {
+        let report = linter.lint(&output);
+        let problems = report.errors.len() + report.warnings.len();
+        // a pass that didn't leave fewer problems isn't converging, so it is undone
+        if let Some((before, count, problems_before)) = previous.take()
+            && problems >= problems_before
+        {
+            output = before;
+            applied -= count;
+            break;
+        }
+
+        let fixes: Vec<Fix> = report
+            .errors
+            .iter()
+            .filter_map(|error| error.fix())
+            .chain(report.warnings.iter().filter_map(|warning| warning.fix()))
+            .filter(|fix| unsafe_fixes || fix.applicability == Applicability::Safe)
+            .cloned()
+            .collect();
+
+        let (fixed, count) = apply_fixes(&output, fixes);
+        if count == 0 {
+            break;
+        }
+        previous = Some((std::mem::replace(&mut output, fixed), count, problems));
+        applied += count;
+    }
+
+    FixResult { output, applied }
+}
+
+/// apply the fixes that don't overlap, returning the new text and how many were applied.
+/// fixes that were skipped are picked up by the next pass
+pub fn apply_fixes(source: &str, mut fixes: Vec<Fix>) -> (String, usize) {
+    fixes.retain(|fix| !fix.edits.is_empty());
+    fixes.sort_by_key(fix_range);
+
+    let mut edits: Vec<&Edit> = Vec::new();
+    let mut applied = 0;
+    let mut last_end = None;
+
+    for fix in &fixes {
+        let (start, end) = fix_range(fix);
+        // edits that touch can't be ordered reliably, so they wait for the next pass
+        if last_end.is_some_and(|last_end| start <= last_end) {
+            continue;
+        }
+        last_end = Some(end);
+        edits.extend(&fix.edits);
+        applied += 1;
+    }
+
+    let mut chars: Vec<char> = source.chars().collect();
+    edits.sort_by_key(|edit| edit.span.start.offset);
+    for edit in edits.iter().rev() {
+        let start = edit.span.start.offset.min(chars.len());
+        let end = edit.span.end.offset.clamp(start, chars.len());
+        chars.splice(start..end, edit.content.chars());
+    }
+
+    (chars.into_iter().collect(), applied)
+}
+
+fn fix_range(fix: &Fix) -> (usize, usize) {
+    let start = fix.edits.iter().map(|edit| edit.span.start.offset).min();
+    let end = fix.edits.iter().map(|edit| edit.span.end.offset).max();
+    (start.unwrap_or(0), end.unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn fix(source: &str, unsafe_fixes: bool) -> FixResult {
+        fix_source(&mut BrandwatchLinter::new(), source, unsafe_fixes)
+    }
+
+    #[test]
+    fn test_safe_fixes() {
+        let result = fix(
+            "(apple juice) or site:www.bbc.co.uk AND authorVerified:True AND authorFollowers:[100 TO 10]",
+            false,
+        );
+
+        assert_eq!(
+            result.output,
+            "(apple AND juice) or site:bbc.co.uk AND authorVerified:true AND authorFollowers:[10 TO 100]"
+        );
+        assert_eq!(result.applied, 4);
+    }
+
+    #[test]
+    fn test_unsafe_fixes_are_opt_in() {
+        let source = "apple OR <<<check this  \n";
+
+        assert_eq!(fix(source, false).output, source);
+        assert_eq!(fix(source, true).output, "apple OR <<<check this>>>  \n");
+    }
+
+    #[test]
+    fn test_field_case_and_mixed_operator_fixes() {
+        let source = "authorverified:true AND apple OR juice";
+
+        let result = fix(source, false);
+        assert_eq!(result.output, "authorVerified:true AND apple OR juice");
+        assert_eq!(result.applied, 1);
+
+        let result = fix(source, true);
+        assert_eq!(result.output, "(authorVerified:true AND apple) OR juice");
+        assert_eq!(result.applied, 2);
+    }
+
+    #[test]
+    fn test_ineffective_braces_fix() {
+        let result = fix("{apple} OR {\"cherry pie\"} OR {apple juice}", false);
+
+        assert_eq!(result.output, "apple OR \"cherry pie\" OR {apple juice}");
+        assert_eq!(result.applied, 2);
+    }
+
+    #[test]
+    fn test_domain_field_fixes() {
+        let source = "site:https://www.twitter.com/ OR site:bbc.co.uk/news OR topLevelDomain:.uk OR links:\"msn..com\"";
+
+        let result = fix(source, false);
+        assert_eq!(
+            result.output,
+            "site:twitter.com OR site:bbc.co.uk/news OR topLevelDomain:uk OR links:\"msn..com\""
+        );
+
+        // the path and the stray dot are guesses at what was meant
+        let result = fix(source, true);
+        assert_eq!(
+            result.output,
+            "site:twitter.com OR url:bbc.co.uk/news OR topLevelDomain:uk OR links:\"msn.com\""
+        );
+    }
+
+    #[test]
+    fn test_invalid_hostnames_are_not_fixed() {
+        let source = "site:bbc_news.com OR site:-bbc.com OR site:bbc OR site:www.";
+        let result = fix(source, true);
+
+        assert_eq!(result.output, source);
+        assert_eq!(result.applied, 0);
+    }
+
+    #[test]
+    fn test_lowercase_operator_is_not_an_implicit_and() {
+        // it changes what the query matches, so only unsafe fixes make it an operator
+        assert_eq!(fix("apple and juice", false).output, "apple and juice");
+
+        let result = fix("apple and juice", true);
+        assert_eq!(result.output, "apple AND juice");
+        assert_eq!(result.applied, 1);
+    }
+
+    #[test]
+    fn test_lowercase_operator_fix_needs_two_operands() {
+        for source in [
+            "and apple",
+            "apple or",
+            "apple and and banana",
+            "(apple or)",
+        ] {
+            let result = fix(source, true);
+            assert_eq!(result.output, source);
+            assert_eq!(result.applied, 0);
+        }
+
+        let result = fix("(apple or juice) and cherry", true);
+        assert_eq!(result.output, "(apple OR juice) AND cherry");
+    }
+
+    #[test]
+    fn test_language_code_fixes() {
+        let result = fix("language:EN OR language:french OR language:xx", false);
+
+        assert_eq!(result.output, "language:en OR language:fr OR language:xx");
+        assert_eq!(result.applied, 2);
+    }
+
+    #[test]
+    fn test_location_code_fixes() {
+        let result = fix(
+            "country:uk OR region:USA.FL OR region:usa.florida OR city:gbr.eng.london OR city:leeds",
+            false,
+        );
+
+        assert_eq!(
+            result.output,
+            "country:gbr OR region:usa.fl OR region:usa.fl OR city:gbr.england.london OR city:gbr.england.leeds"
+        );
+        assert_eq!(result.applied, 5);
+    }
+
+    #[test]
+    fn test_cities_sharing_a_name_are_left_alone() {
+        let source = "city:usa.me.portland OR city:\"usa.ks.kansas city\" OR city:usa.xx.portland OR city:portland";
+        let result = fix(source, true);
+
+        assert_eq!(result.output, source);
+        assert_eq!(result.applied, 0);
+    }
+
+    #[test]
+    fn test_syntax_errors_are_not_parenthesized() {
+        let result = fix("a AND OR b", true);
+
+        assert_eq!(result.output, "a AND OR b");
+        assert_eq!(result.applied, 0);
+    }
+
+    #[test]
+    fn test_overlapping_fixes_are_deferred() {
+        let result = fix("a b c d", false);
+
+        assert_eq!(result.output, "a AND b AND c AND d");
+        assert_eq!(result.applied, 3);
+    }
+}
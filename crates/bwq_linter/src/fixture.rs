@@ -0,0 +1,261 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::ast::{FieldType, Query};
+use crate::matcher::{Document, Explanation, evaluate};
+
+/// appended to a query's file stem to find its fixture, e.g. `brand.test.toml` for `brand.bwq`
+pub const FIXTURE_SUFFIX: &str = ".test.toml";
+
+#[derive(Error, Debug)]
+pub enum FixtureError {
+    #[error("failed to read {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("failed to parse {}: {message}", path.display())]
+    Parse { path: PathBuf, message: String },
+
+    #[error("unknown field '{field}' in {}", path.display())]
+    UnknownField { path: PathBuf, field: String },
+}
+
+/// documents a query must and must not match
+///
+/// each document has a `text`, an optional `name` and metadata for field operators keyed by
+/// field name, e.g.
+///
+/// ```toml
+/// [[should_match]]
+/// name = "news coverage"
+/// text = "Apple juice sales are up"
+/// site = "bbc.co.uk"
+/// links = ["https://example.com/a", "https://example.com/b"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Fixture {
+    pub should_match: Vec<Case>,
+    pub should_not_match: Vec<Case>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case {
+    /// the document's `name` or position, e.g. `should_match 'news'` or `should_match[1]`
+    pub label: String,
+    pub document: Document,
+}
+
+/// the outcome of evaluating a query against one fixture document
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaseResult<'a> {
+    pub case: &'a Case,
+    pub should_match: bool,
+    pub explanation: Explanation,
+}
+
+impl CaseResult<'_> {
+    pub fn passed(&self) -> bool {
+        self.explanation.matched == self.should_match
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawFixture {
+    #[serde(default)]
+    should_match: Vec<BTreeMap<String, toml::Value>>,
+    #[serde(default)]
+    should_not_match: Vec<BTreeMap<String, toml::Value>>,
+}
+
+/// the fixture file for a query file
+pub fn fixture_path(query_path: &Path) -> PathBuf {
+    let stem = query_path.file_stem().unwrap_or_default().to_string_lossy();
+    query_path.with_file_name(format!("{stem}{FIXTURE_SUFFIX}"))
+}
+
+impl Fixture {
+    pub fn load(path: &Path) -> Result<Self, FixtureError> {
+        let contents = fs::read_to_string(path).map_err(|source| FixtureError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse(&contents, path)
+    }
+
+    /// parse the contents of the fixture at `path`
+    pub fn parse(contents: &str, path: &Path) -> Result<Self, FixtureError> {
+        let raw: RawFixture = toml::from_str(contents).map_err(|e| FixtureError::Parse {
+            path: path.to_path_buf(),
+            message: e.message().to_string(),
+        })?;
+
+        let cases = |kind: &str, tables: Vec<BTreeMap<String, toml::Value>>| {
+            tables
+                .into_iter()
+                .enumerate()
+                .map(|(index, table)| parse_case(kind, index, table, path))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(Self {
+            should_match: cases("should_match", raw.should_match)?,
+            should_not_match: cases("should_not_match", raw.should_not_match)?,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.should_match.len() + self.should_not_match.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// evaluate `query` against every document
+    pub fn run(&self, query: &Query) -> Vec<CaseResult<'_>> {
+        let should_match = self.should_match.iter().map(|case| (case, true));
+        let should_not_match = self.should_not_match.iter().map(|case| (case, false));
+
+        should_match
+            .chain(should_not_match)
+            .map(|(case, should_match)| CaseResult {
+                case,
+                should_match,
+                explanation: evaluate(query, &case.document),
+            })
+            .collect()
+    }
+}
+
+fn parse_case(
+    kind: &str,
+    index: usize,
+    table: BTreeMap<String, toml::Value>,
+    path: &Path,
+) -> Result<Case, FixtureError> {
+    let mut label = format!("{kind}[{index}]");
+    let mut document = Document::default();
+
+    for (key, value) in table {
+        let values = values(&value).ok_or_else(|| FixtureError::Parse {
+            path: path.to_path_buf(),
+            message: format!("unsupported value for '{key}' in {label}"),
+        })?;
+
+        match key.as_str() {
+            "text" => document.text = values.join("\n"),
+            "name" => label = format!("{kind} '{}'", values.join(" ")),
+            _ => {
+                let field = FieldType::parse(&key).ok_or_else(|| FixtureError::UnknownField {
+                    path: path.to_path_buf(),
+                    field: key.clone(),
+                })?;
+                for value in values {
+                    document = document.with_field(field.clone(), value);
+                }
+            }
+        }
+    }
+
+    Ok(Case { label, document })
+}
+
+/// the strings a TOML value stands for; arrays give one per element
+fn values(value: &toml::Value) -> Option<Vec<String>> {
+    match value {
+        toml::Value::Array(items) => items.iter().map(scalar).collect(),
+        value => scalar(value).map(|value| vec![value]),
+    }
+}
+
+fn scalar(value: &toml::Value) -> Option<String> {
+    match value {
+        toml::Value::String(s) => Some(s.clone()),
+        toml::Value::Integer(i) => Some(i.to_string()),
+        toml::Value::Float(f) => Some(f.to_string()),
+        toml::Value::Boolean(b) => Some(b.to_string()),
+        toml::Value::Datetime(_) | toml::Value::Table(_) | toml::Value::Array(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Lexer, parser::Parser};
+    use pretty_assertions::assert_eq;
+
+    const FIXTURE: &str = r#"
+[[should_match]]
+name = "news"
+text = "Apple juice sales are up"
+site = "bbc.co.uk"
+authorFollowers = 250
+
+[[should_not_match]]
+text = "Apple pie recipes"
+links = ["https://example.com/a", "https://example.com/b"]
+"#;
+
+    fn parse(contents: &str) -> Result<Fixture, FixtureError> {
+        Fixture::parse(contents, Path::new("brand.test.toml"))
+    }
+
+    #[test]
+    fn test_parse() {
+        let fixture = parse(FIXTURE).unwrap();
+
+        assert_eq!(fixture.len(), 2);
+        assert_eq!(fixture.should_match[0].label, "should_match 'news'");
+        assert_eq!(
+            fixture.should_match[0].document,
+            Document::new("Apple juice sales are up")
+                .with_field(FieldType::AuthorFollowers, "250")
+                .with_field(FieldType::Site, "bbc.co.uk")
+        );
+        assert_eq!(fixture.should_not_match[0].label, "should_not_match[0]");
+        assert_eq!(fixture.should_not_match[0].document.fields.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            parse("[[should_match]]\nsight = \"bbc.co.uk\""),
+            Err(FixtureError::UnknownField { field, .. }) if field == "sight"
+        ));
+        assert!(matches!(
+            parse("[[should_match]]\ntext = { a = 1 }"),
+            Err(FixtureError::Parse { .. })
+        ));
+        assert!(matches!(
+            parse("[[should_mach]]\ntext = \"\""),
+            Err(FixtureError::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn test_run() {
+        let (tokens, _) = Lexer::new("apple AND (juice OR pie)").tokenize_with_errors();
+        let query = Parser::new(tokens).parse().query;
+        let fixture = parse(FIXTURE).unwrap();
+
+        let results = fixture.run(&query);
+        assert!(results[0].passed());
+        assert!(!results[1].passed());
+        assert_eq!(results[1].explanation.cause().span.start.offset, 0);
+    }
+
+    #[test]
+    fn test_fixture_path() {
+        assert_eq!(
+            fixture_path(Path::new("queries/brand.bwq")),
+            PathBuf::from("queries/brand.test.toml")
+        );
+    }
+}
@@ -0,0 +1,599 @@
+use thiserror::Error;
+
+use crate::ast::*;
+use crate::cst::{self, SyntaxToken, SyntaxTree, Trivia, TriviaKind};
+use crate::error::LintError;
+
+/// layout options for the formatter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// expressions that don't fit within this many characters are broken over several lines
+    pub line_width: usize,
+    /// spaces per nesting level inside broken groups
+    pub indent_width: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            line_width: 80,
+            indent_width: 4,
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum FormatError {
+    #[error("cannot format a query with syntax errors ({} found)", .0.len())]
+    SyntaxErrors(Vec<LintError>),
+}
+
+/// format query source text into the canonical layout
+///
+/// queries with syntax errors are left alone, since there is no tree to print them from.
+pub fn format_source(source: &str, options: &FormatOptions) -> Result<String, FormatError> {
+    if source.trim().is_empty() {
+        return Ok(String::new());
+    }
+
+    let tree = cst::parse(source);
+    if !tree.errors.is_empty() {
+        return Err(FormatError::SyntaxErrors(tree.errors));
+    }
+
+    Ok(format_tree(&tree, options))
+}
+
+/// print a parsed query back to text
+///
+/// term text is copied verbatim from the tree's tokens so that formatting never changes what a
+/// term matches, and comments are taken from its trivia so that each is printed once.
+pub fn format_tree(tree: &SyntaxTree, options: &FormatOptions) -> String {
+    let mut printer = Printer::new(tree, options);
+    printer.write_expr(&tree.query.expression);
+    printer.finish()
+}
+
+/// pretty printer that keeps an expression on one line when it fits and otherwise breaks it up:
+/// one boolean operand per line (operator first) and the contents of groups indented
+struct Printer<'a> {
+    source: Vec<char>,
+    tokens: Vec<&'a SyntaxToken>,
+    comments: Vec<&'a Trivia>,
+    standalone: Vec<bool>,
+    options: &'a FormatOptions,
+    next_comment: usize,
+    output: String,
+    indent: usize,
+    column: usize,
+}
+
+impl<'a> Printer<'a> {
+    fn new(tree: &'a SyntaxTree, options: &'a FormatOptions) -> Self {
+        let (comments, standalone) = tree.comments().into_iter().unzip();
+
+        Self {
+            source: tree.root.to_string().chars().collect(),
+            tokens: tree.root.tokens(),
+            comments,
+            standalone,
+            options,
+            next_comment: 0,
+            output: String::new(),
+            indent: 0,
+            column: 0,
+        }
+    }
+
+    fn finish(mut self) -> String {
+        // comments after the last token
+        while self.next_comment < self.comments.len() {
+            if self.standalone[self.next_comment] || self.output.contains('\n') {
+                self.newline();
+            } else {
+                self.text(" ");
+            }
+            self.write_comment(self.next_comment);
+            self.next_comment += 1;
+        }
+
+        self.output.push('\n');
+        self.output
+    }
+
+    fn write_expr(&mut self, expr: &Expression) {
+        self.standalone_comments_before(expr.span().start.offset);
+
+        if let Some((flat, next_comment)) = self.flat(expr)
+            && self.fits(&flat)
+        {
+            self.text(&flat);
+            self.next_comment = next_comment;
+            return;
+        }
+
+        match expr {
+            Expression::BooleanOp {
+                operator,
+                right: Some(_),
+                ..
+            } => {
+                let mut operands = Vec::new();
+                collect_operands(expr, operator, &mut operands);
+
+                self.write_expr(operands[0]);
+                for operand in &operands[1..] {
+                    self.newline();
+                    self.standalone_comments_before(operand.span().start.offset);
+                    self.text(operator.as_str());
+                    self.text(" ");
+                    self.write_expr(operand);
+                }
+            }
+            Expression::BooleanOp {
+                operator,
+                left,
+                right: None,
+                span,
+            } => {
+                self.comments_before(span.start.offset);
+                self.text(operator.as_str());
+                self.text(" ");
+                self.write_expr(left);
+            }
+            Expression::Group { expression, span } => {
+                self.comments_before(span.start.offset);
+                self.text("(");
+                self.indent += 1;
+                self.newline();
+                self.write_expr(expression);
+
+                while self.next_comment < self.comments.len()
+                    && self.comments[self.next_comment].span.start.offset < span.end.offset
+                {
+                    self.newline();
+                    self.write_comment(self.next_comment);
+                    self.next_comment += 1;
+                }
+
+                self.indent -= 1;
+                self.newline();
+                self.text(")");
+                self.trailing_comments(span.end.offset);
+            }
+            Expression::Proximity {
+                operator, terms, ..
+            } => match operator {
+                ProximityOperator::Proximity { distance } => {
+                    self.write_expr(&terms[0]);
+                    self.text(&format!("~{}", distance.unwrap_or_default()));
+                }
+                ProximityOperator::Near { .. } | ProximityOperator::NearForward { .. } => {
+                    self.write_expr(&terms[0]);
+                    self.text(&format!(" {} ", near_operator(operator)));
+                    self.write_expr(&terms[1]);
+                }
+            },
+            Expression::Field { field, value, .. } => {
+                // a comment between the colon and the value is written ahead of the field, since
+                // the value has to follow the colon directly
+                self.comments_before(value.span().start.offset);
+                self.text(field.as_str());
+                self.text(":");
+                self.write_expr(value);
+            }
+            Expression::Range { span, .. }
+            | Expression::Term { span, .. }
+            | Expression::Error { span } => {
+                // likewise for a comment inside a term, e.g. after the colon of `-rating:1`
+                self.comments_before(span.end.offset);
+                let leaf = self.leaf(expr);
+                self.text(&leaf);
+                self.trailing_comments(span.end.offset);
+            }
+        }
+    }
+
+    /// render `expr` on a single line, returning it with the index of the first comment it
+    /// didn't consume. `None` if it holds a comment that has to stay on its own line.
+    fn flat(&self, expr: &Expression) -> Option<(String, usize)> {
+        let mut out = String::new();
+        let mut cursor = self.next_comment;
+        self.flat_expr(expr, &mut cursor, &mut out)?;
+        Some((out, cursor))
+    }
+
+    fn flat_expr(&self, expr: &Expression, cursor: &mut usize, out: &mut String) -> Option<()> {
+        match expr {
+            Expression::BooleanOp {
+                operator,
+                right: Some(_),
+                ..
+            } => {
+                let mut operands = Vec::new();
+                collect_operands(expr, operator, &mut operands);
+
+                for (i, operand) in operands.iter().enumerate() {
+                    if i > 0 {
+                        out.push(' ');
+                        out.push_str(operator.as_str());
+                        out.push(' ');
+                    }
+                    self.flat_expr(operand, cursor, out)?;
+                }
+            }
+            Expression::BooleanOp {
+                operator,
+                left,
+                right: None,
+                span,
+            } => {
+                self.flat_comments_before(span.start.offset, cursor, out)?;
+                out.push_str(operator.as_str());
+                out.push(' ');
+                self.flat_expr(left, cursor, out)?;
+            }
+            Expression::Group { expression, span } => {
+                self.flat_comments_before(span.start.offset, cursor, out)?;
+                out.push('(');
+                self.flat_expr(expression, cursor, out)?;
+                while *cursor < self.comments.len()
+                    && self.comments[*cursor].span.start.offset < span.end.offset
+                {
+                    if self.standalone[*cursor] {
+                        return None;
+                    }
+                    out.push(' ');
+                    out.push_str(&self.comment_text(*cursor));
+                    *cursor += 1;
+                }
+                out.push(')');
+                self.flat_trailing_comments(span.end.offset, cursor, out);
+            }
+            Expression::Proximity {
+                operator, terms, ..
+            } => match operator {
+                ProximityOperator::Proximity { distance } => {
+                    self.flat_expr(&terms[0], cursor, out)?;
+                    out.push_str(&format!("~{}", distance.unwrap_or_default()));
+                }
+                ProximityOperator::Near { .. } | ProximityOperator::NearForward { .. } => {
+                    self.flat_expr(&terms[0], cursor, out)?;
+                    out.push_str(&format!(" {} ", near_operator(operator)));
+                    self.flat_expr(&terms[1], cursor, out)?;
+                }
+            },
+            Expression::Field { field, value, .. } => {
+                self.flat_comments_before(value.span().start.offset, cursor, out)?;
+                out.push_str(field.as_str());
+                out.push(':');
+                self.flat_expr(value, cursor, out)?;
+            }
+            Expression::Range { span, .. }
+            | Expression::Term { span, .. }
+            | Expression::Error { span } => {
+                self.flat_comments_before(span.end.offset, cursor, out)?;
+                out.push_str(&self.leaf(expr));
+                self.flat_trailing_comments(span.end.offset, cursor, out);
+            }
+        }
+
+        Some(())
+    }
+
+    fn flat_comments_before(
+        &self,
+        offset: usize,
+        cursor: &mut usize,
+        out: &mut String,
+    ) -> Option<()> {
+        while *cursor < self.comments.len() && self.comments[*cursor].span.start.offset < offset {
+            if self.standalone[*cursor] {
+                return None;
+            }
+            out.push_str(&self.comment_text(*cursor));
+            out.push(' ');
+            *cursor += 1;
+        }
+        Some(())
+    }
+
+    fn flat_trailing_comments(&self, offset: usize, cursor: &mut usize, out: &mut String) {
+        let end = self.attached_comments(offset, *cursor);
+        while *cursor < end {
+            out.push(' ');
+            out.push_str(&self.comment_text(*cursor));
+            *cursor += 1;
+        }
+    }
+
+    /// the index after the comments from `first` that follow the token ending at `offset` on its
+    /// line, which stay attached to it rather than moving past the operator that comes next
+    fn attached_comments(&self, mut offset: usize, first: usize) -> usize {
+        let mut index = first;
+        while index < self.comments.len() && !self.standalone[index] {
+            let span = &self.comments[index].span;
+            if span.start.offset < offset
+                || !self.source[offset..span.start.offset]
+                    .iter()
+                    .all(|ch| ch.is_whitespace())
+            {
+                break;
+            }
+            offset = span.end.offset;
+            index += 1;
+        }
+        index
+    }
+
+    /// write the comments attached to the token ending at `offset`, after it
+    fn trailing_comments(&mut self, offset: usize) {
+        let end = self.attached_comments(offset, self.next_comment);
+        while self.next_comment < end {
+            self.text(" ");
+            self.write_comment(self.next_comment);
+            self.next_comment += 1;
+        }
+    }
+
+    /// write the comments that come before `offset`, ahead of the token found there
+    fn comments_before(&mut self, offset: usize) {
+        while self.next_comment < self.comments.len()
+            && self.comments[self.next_comment].span.start.offset < offset
+        {
+            self.write_comment(self.next_comment);
+            if self.standalone[self.next_comment] {
+                self.newline();
+            } else {
+                self.text(" ");
+            }
+            self.next_comment += 1;
+        }
+    }
+
+    /// write own-line comments before `offset` at the start of the current line, so that they
+    /// stay above an operand rather than between it and its operator
+    fn standalone_comments_before(&mut self, offset: usize) {
+        while self.next_comment < self.comments.len()
+            && self.standalone[self.next_comment]
+            && self.comments[self.next_comment].span.start.offset < offset
+        {
+            self.write_comment(self.next_comment);
+            self.newline();
+            self.next_comment += 1;
+        }
+    }
+
+    fn write_comment(&mut self, index: usize) {
+        let comment = self.comment_text(index);
+        self.text(&comment);
+    }
+
+    fn comment_text(&self, index: usize) -> String {
+        self.comments[index].text.clone()
+    }
+
+    /// the text of a term, range or error, without the comments inside it
+    fn leaf(&self, expr: &Expression) -> String {
+        if let Expression::Range { start, end, .. } = expr {
+            return format!("[{start} TO {end}]");
+        }
+
+        let span = expr.span();
+        let first = self
+            .tokens
+            .partition_point(|token| token.span.start.offset < span.start.offset);
+        let mut text = String::new();
+        for (i, token) in self.tokens[first..]
+            .iter()
+            .take_while(|token| token.span.start.offset < span.end.offset)
+            .enumerate()
+        {
+            if i > 0 {
+                for trivia in &token.leading_trivia {
+                    if trivia.kind != TriviaKind::Comment {
+                        text.push_str(&trivia.text);
+                    }
+                }
+            }
+            text.push_str(&token.text);
+        }
+        text
+    }
+
+    fn fits(&self, flat: &str) -> bool {
+        !flat.contains('\n') && self.column + flat.chars().count() <= self.options.line_width
+    }
+
+    fn text(&mut self, text: &str) {
+        self.output.push_str(text);
+        match text.rfind('\n') {
+            Some(index) => self.column = text[index + 1..].chars().count(),
+            None => self.column += text.chars().count(),
+        }
+    }
+
+    fn newline(&mut self) {
+        let trimmed_len = self.output.trim_end_matches([' ', '\t']).len();
+        self.output.truncate(trimmed_len);
+
+        self.output.push('\n');
+        self.column = self.indent * self.options.indent_width;
+        self.output.push_str(&" ".repeat(self.column));
+    }
+}
+
+/// flatten a left-associative chain of the same boolean operator into its operands
+fn collect_operands<'e>(
+    expr: &'e Expression,
+    operator: &BooleanOperator,
+    operands: &mut Vec<&'e Expression>,
+) {
+    match expr {
+        Expression::BooleanOp {
+            operator: op,
+            left,
+            right: Some(right),
+            ..
+        } if op == operator => {
+            collect_operands(left, operator, operands);
+            operands.push(right);
+        }
+        _ => operands.push(expr),
+    }
+}
+
+fn near_operator(operator: &ProximityOperator) -> String {
+    match operator {
+        ProximityOperator::Near { distance } => format!("NEAR/{distance}"),
+        ProximityOperator::NearForward { distance } => format!("NEAR/{distance}f"),
+        ProximityOperator::Proximity { distance } => format!("~{}", distance.unwrap_or_default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn format(source: &str) -> String {
+        format_source(source, &FormatOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn test_normalizes_spacing() {
+        assert_eq!(
+            format("apple   AND  (  juice OR  orange )"),
+            "apple AND (juice OR orange)\n"
+        );
+        assert_eq!(
+            format("apple   NEAR/5f    juice AND \"fresh fruit\"~3"),
+            "apple NEAR/5f juice AND \"fresh fruit\"~3\n"
+        );
+        assert_eq!(
+            format("authorverified:true AND author:  brandwatch AND rating:[ 1 TO 5 ]"),
+            "authorVerified:true AND author:brandwatch AND rating:[1 TO 5]\n"
+        );
+    }
+
+    #[test]
+    fn test_implicit_and_made_explicit() {
+        assert_eq!(format("apple juice"), "apple AND juice\n");
+    }
+
+    #[test]
+    fn test_terms_are_copied_verbatim() {
+        assert_eq!(
+            format("{Brand Watch} OR \"apple  juice\" OR test:test OR #tag OR @handle OR 🎉"),
+            "{Brand Watch} OR \"apple  juice\" OR test:test OR #tag OR @handle OR 🎉\n"
+        );
+    }
+
+    #[test]
+    fn test_breaks_long_or_lists() {
+        let source = "(apple OR orange OR banana OR pineapple OR strawberry OR raspberry OR blueberry OR cranberry) AND (juice OR smoothie)";
+        assert_eq!(
+            format(source),
+            "(
+    apple
+    OR orange
+    OR banana
+    OR pineapple
+    OR strawberry
+    OR raspberry
+    OR blueberry
+    OR cranberry
+)
+AND (juice OR smoothie)
+"
+        );
+    }
+
+    #[test]
+    fn test_nested_groups_are_indented() {
+        let options = FormatOptions {
+            line_width: 30,
+            indent_width: 2,
+        };
+        let source = "((apple OR orange) NEAR/5 (smartphone OR phone OR tablet)) OR juice";
+        assert_eq!(
+            format_source(source, &options).unwrap(),
+            "(
+  (apple OR orange) NEAR/5 (
+    smartphone
+    OR phone
+    OR tablet
+  )
+)
+OR juice
+"
+        );
+    }
+
+    #[test]
+    fn test_keeps_comments() {
+        assert_eq!(
+            format("apple <<<a note>>> AND   juice <<<end>>>"),
+            "apple <<<a note>>> AND juice <<<end>>>\n"
+        );
+        assert_eq!(
+            format("apple AND (banana OR cherry)  <<<note>>>  NOT  \"foo bar\"~5"),
+            "apple AND (banana OR cherry) <<<note>>> NOT \"foo bar\"~5\n"
+        );
+        assert_eq!(
+            format("apple AND <<<before juice>>> juice"),
+            "apple AND <<<before juice>>> juice\n"
+        );
+
+        let source = "<<<Fruit>>>
+(apple OR orange)
+OR
+<<<Drinks>>>
+(juice OR smoothie <<<more to come>>>)";
+        assert_eq!(
+            format(source),
+            "<<<Fruit>>>
+(apple OR orange)
+<<<Drinks>>>
+OR (juice OR smoothie <<<more to come>>>)
+"
+        );
+    }
+
+    #[test]
+    fn test_comments_inside_fields_are_kept_once() {
+        for (source, expected) in [
+            ("-rating:<<<c>>>1", "<<<c>>> -rating:1\n"),
+            ("title:<<<c>>>apple", "<<<c>>> title:apple\n"),
+            (
+                "juice AND rating:<<<c>>>[1 TO 2]",
+                "juice AND <<<c>>> rating:[1 TO 2]\n",
+            ),
+        ] {
+            let once = format(source);
+            assert_eq!(once, expected);
+            assert_eq!(format(&once), once);
+        }
+    }
+
+    #[test]
+    fn test_refuses_invalid_queries() {
+        assert!(matches!(
+            format_source("(apple OR", &FormatOptions::default()),
+            Err(FormatError::SyntaxErrors(_))
+        ));
+        assert_eq!(format("  \n"), "");
+    }
+
+    #[test]
+    fn test_idempotent_on_fixtures() {
+        for entry in std::fs::read_dir("resources/test/fixtures/valid").unwrap() {
+            let path = entry.unwrap().path();
+            let source = std::fs::read_to_string(&path).unwrap();
+
+            let once = format(&source);
+            let twice = format(&once);
+            assert_eq!(once, twice, "not idempotent: {}", path.display());
+        }
+    }
+}
@@ -27,6 +27,7 @@ pub enum TokenType {
     NearForward(u32),
 
     CommentStart,
+    CommentText(String),
     CommentEnd,
 
     Field(String),
@@ -62,6 +63,7 @@ impl fmt::Display for TokenType {
             TokenType::Near(n) => write!(f, "NEAR/{n}"),
             TokenType::NearForward(n) => write!(f, "NEAR/{n}f"),
             TokenType::CommentStart => write!(f, "<<<"),
+            TokenType::CommentText(_) => write!(f, "comment"),
             TokenType::CommentEnd => write!(f, ">>>"),
             TokenType::Field(f_name) => write!(f, "field '{f_name}'"),
             TokenType::Hashtag(h) => write!(f, "hashtag '{h}'"),
@@ -97,6 +99,8 @@ pub struct Lexer {
     line: usize,
     column: usize,
     inside_comment: bool,
+    errors: Vec<LintError>,
+    pending: Option<Token>,
 }
 
 impl Lexer {
@@ -133,23 +137,19 @@ impl Lexer {
             )
     }
 
-    fn handle_comment_transition(&mut self, ch: char) -> LintResult<Option<Token>> {
+    fn handle_comment_transition(&mut self, ch: char) -> Option<Token> {
         if self.inside_comment {
             if ch == '>' && self.peek_ahead(2) == ">>" {
                 self.inside_comment = false;
-                return self.read_comment_end();
-            } else {
-                // Skip any character inside comments
-                self.advance_with_position_tracking(ch);
-                return self.next_token();
+                return Some(self.read_comment_end());
             }
         } else if ch == '<' && self.peek_ahead(2) == "<<" {
-            return self.read_comment_start();
+            return Some(self.read_comment_start());
         } else if ch == '>' && self.peek_ahead(2) == ">>" {
-            return self.read_comment_end();
+            return Some(self.read_comment_end());
         }
 
-        Ok(None)
+        None
     }
 
     fn advance_with_position_tracking(&mut self, ch: char) {
@@ -168,20 +168,34 @@ impl Lexer {
             line: 1,
             column: 1,
             inside_comment: false,
+            errors: Vec::new(),
+            pending: None,
         }
     }
 
+    /// tokenize the input, failing on the first lexer error
     pub fn tokenize(&mut self) -> LintResult<Vec<Token>> {
+        let (tokens, mut errors) = self.tokenize_with_errors();
+
+        if errors.is_empty() {
+            Ok(tokens)
+        } else {
+            Err(errors.remove(0))
+        }
+    }
+
+    /// tokenize the whole input, collecting every lexer error instead of stopping at the first
+    pub fn tokenize_with_errors(&mut self) -> (Vec<Token>, Vec<LintError>) {
         let mut tokens = Vec::new();
 
         while !self.is_at_end() {
-            match self.next_token()? {
-                Some(token) => {
-                    if !matches!(token.token_type, TokenType::Whitespace) {
-                        tokens.push(token);
-                    }
-                }
-                None => break,
+            if let Some(token) = self.next_token()
+                && !matches!(token.token_type, TokenType::Whitespace)
+            {
+                tokens.push(token);
+            }
+            if let Some(token) = self.pending.take() {
+                tokens.push(token);
             }
         }
 
@@ -192,24 +206,23 @@ impl Lexer {
             String::new(),
         ));
 
-        Ok(tokens)
+        (tokens, std::mem::take(&mut self.errors))
     }
 
-    fn next_token(&mut self) -> LintResult<Option<Token>> {
+    fn next_token(&mut self) -> Option<Token> {
         if self.is_at_end() {
-            return Ok(None);
+            return None;
         }
 
         let start_pos = self.current_position();
         let ch = self.current_char();
 
-        if let Some(token) = self.handle_comment_transition(ch)? {
-            return Ok(Some(token));
+        if let Some(token) = self.handle_comment_transition(ch) {
+            return Some(token);
         }
 
-        // If we're inside a comment and didn't find an end marker, continue
         if self.inside_comment {
-            return self.next_token();
+            return Some(self.read_comment_text());
         }
 
         match ch {
@@ -222,68 +235,68 @@ impl Lexer {
                     self.column += 1;
                 }
                 let end_pos = self.current_position();
-                Ok(Some(Token::new(
+                Some(Token::new(
                     TokenType::Whitespace,
                     Span::new(start_pos, end_pos),
                     ch.to_string(),
-                )))
+                ))
             }
 
-            '"' => self.read_quoted_string(),
+            '"' => Some(self.read_quoted_string()),
 
             '(' => {
                 self.advance();
                 self.column += 1;
-                Ok(Some(Token::new(
+                Some(Token::new(
                     TokenType::LeftParen,
                     Span::new(start_pos, self.current_position()),
                     "(".to_string(),
-                )))
+                ))
             }
             ')' => {
                 self.advance();
                 self.column += 1;
-                Ok(Some(Token::new(
+                Some(Token::new(
                     TokenType::RightParen,
                     Span::new(start_pos, self.current_position()),
                     ")".to_string(),
-                )))
+                ))
             }
             '[' => {
                 self.advance();
                 self.column += 1;
-                Ok(Some(Token::new(
+                Some(Token::new(
                     TokenType::LeftBracket,
                     Span::new(start_pos, self.current_position()),
                     "[".to_string(),
-                )))
+                ))
             }
             ']' => {
                 self.advance();
                 self.column += 1;
-                Ok(Some(Token::new(
+                Some(Token::new(
                     TokenType::RightBracket,
                     Span::new(start_pos, self.current_position()),
                     "]".to_string(),
-                )))
+                ))
             }
             '{' => {
                 self.advance();
                 self.column += 1;
-                Ok(Some(Token::new(
+                Some(Token::new(
                     TokenType::LeftBrace,
                     Span::new(start_pos, self.current_position()),
                     "{".to_string(),
-                )))
+                ))
             }
             '}' => {
                 self.advance();
                 self.column += 1;
-                Ok(Some(Token::new(
+                Some(Token::new(
                     TokenType::RightBrace,
                     Span::new(start_pos, self.current_position()),
                     "}".to_string(),
-                )))
+                ))
             }
 
             '~' => {
@@ -306,66 +319,89 @@ impl Lexer {
                         )
                         && !self.current_char().is_ascii_digit()
                     {
-                        return Err(LintError::LexerError {
-                            span: Span::single_character(start_pos),
+                        self.errors.push(LintError::LexerError {
+                            span: Span::single_character(start_pos.clone()),
                             message: "Invalid characters after proximity operator. Tilde operator format should be ~5 (with proper word boundary).".to_string(),
                         });
+
+                        // keep the distance and drop the trailing characters so parsing can continue
+                        let digits_end = self.current_position();
+                        let digits: String = self.input[tilde_end.offset..digits_end.offset]
+                            .iter()
+                            .collect();
+                        self.pending = Some(Token::new(
+                            TokenType::Number(digits.clone()),
+                            Span::new(tilde_end.clone(), digits_end),
+                            digits,
+                        ));
+                        while !self.is_at_end() && !self.is_word_boundary_char(self.current_char())
+                        {
+                            self.advance();
+                            self.column += 1;
+                        }
+
+                        return Some(Token::new(
+                            TokenType::Tilde,
+                            Span::new(start_pos, tilde_end),
+                            "~".to_string(),
+                        ));
                     }
 
                     self.position -= self.current_position().offset - tilde_end.offset;
                     self.column = tilde_end.column;
                 }
 
-                Ok(Some(Token::new(
+                Some(Token::new(
                     TokenType::Tilde,
                     Span::new(start_pos, tilde_end),
                     "~".to_string(),
-                )))
+                ))
             }
             ':' => {
                 // Check for a space before colon - always fail if there's a space before
                 if self.position > 0 && self.input[self.position - 1].is_whitespace() {
-                    return Err(LintError::InvalidFieldOperatorSpacing {
-                        span: Span::single_character(start_pos),
+                    self.errors.push(LintError::InvalidFieldOperatorSpacing {
+                        span: Span::single_character(start_pos.clone()),
                         message: "Field operator colon must be directly attached to the field name. If the colon is a search term, you'll need to put it in quote marks".to_string(),
                     });
                 }
 
                 self.advance();
                 self.column += 1;
-                Ok(Some(Token::new(
+                Some(Token::new(
                     TokenType::Colon,
                     Span::new(start_pos, self.current_position()),
                     ":".to_string(),
-                )))
+                ))
             }
 
-            '#' => self.read_hashtag(),
+            '#' => Some(self.read_hashtag()),
 
-            '@' => self.read_mention(),
+            '@' => Some(self.read_mention()),
 
             _ if ch.is_ascii_digit() || ch == '-' => {
                 // look ahead to see if this is actually an alphanumeric word starting with digits
                 if (ch.is_ascii_digit() || ch == '-') && self.has_word_chars_ahead() {
-                    self.read_word_or_operator()
+                    Some(self.read_word_or_operator())
                 } else {
-                    self.read_number()
+                    Some(self.read_number())
                 }
             }
-            _ if self.is_word_char(ch) => self.read_word_or_operator(),
+            _ if self.is_word_char(ch) => Some(self.read_word_or_operator()),
 
             _ => {
                 self.advance();
                 self.column += 1;
-                Err(LintError::LexerError {
+                self.errors.push(LintError::LexerError {
                     span: Span::single_character(start_pos),
                     message: format!("Unexpected character '{ch}'"),
-                })
+                });
+                None
             }
         }
     }
 
-    fn read_quoted_string(&mut self) -> LintResult<Option<Token>> {
+    fn read_quoted_string(&mut self) -> Token {
         let start_pos = self.current_position();
         let mut value = String::new();
         let mut raw = String::new();
@@ -390,25 +426,25 @@ impl Lexer {
         }
 
         if self.is_at_end() {
-            return Err(LintError::LexerError {
-                span: Span::single_character(start_pos),
-                message: "Unterminated quoted string".to_string(),
+            // treat the rest of the input as the phrase so parsing can continue
+            self.errors.push(LintError::UnterminatedQuote {
+                span: Span::single_character(start_pos.clone()),
             });
+        } else {
+            raw.push(self.current_char());
+            self.advance();
+            self.column += 1;
         }
 
-        raw.push(self.current_char());
-        self.advance();
-        self.column += 1;
-
         let end_pos = self.current_position();
-        Ok(Some(Token::new(
+        Token::new(
             TokenType::QuotedString(value),
             Span::new(start_pos, end_pos),
             raw,
-        )))
+        )
     }
 
-    fn read_word_or_operator(&mut self) -> LintResult<Option<Token>> {
+    fn read_word_or_operator(&mut self) -> Token {
         let start_pos = self.current_position();
         let mut value = String::new();
 
@@ -450,10 +486,10 @@ impl Lexer {
             }
         };
 
-        Ok(Some(Token::new(token_type, span, value)))
+        Token::new(token_type, span, value)
     }
 
-    fn read_number(&mut self) -> LintResult<Option<Token>> {
+    fn read_number(&mut self) -> Token {
         let start_pos = self.current_position();
         let mut value = String::new();
 
@@ -472,14 +508,14 @@ impl Lexer {
         }
 
         let end_pos = self.current_position();
-        Ok(Some(Token::new(
+        Token::new(
             TokenType::Number(value.clone()),
             Span::new(start_pos, end_pos),
             value,
-        )))
+        )
     }
 
-    fn read_hashtag(&mut self) -> LintResult<Option<Token>> {
+    fn read_hashtag(&mut self) -> Token {
         let start_pos = self.current_position();
         let mut value = String::new();
 
@@ -493,14 +529,14 @@ impl Lexer {
         }
 
         let end_pos = self.current_position();
-        Ok(Some(Token::new(
+        Token::new(
             TokenType::Hashtag(value.clone()),
             Span::new(start_pos, end_pos),
             format!("#{value}"),
-        )))
+        )
     }
 
-    fn read_mention(&mut self) -> LintResult<Option<Token>> {
+    fn read_mention(&mut self) -> Token {
         let start_pos = self.current_position();
         let mut value = String::new();
 
@@ -514,14 +550,14 @@ impl Lexer {
         }
 
         let end_pos = self.current_position();
-        Ok(Some(Token::new(
+        Token::new(
             TokenType::Mention(value.clone()),
             Span::new(start_pos, end_pos),
             format!("@{value}"),
-        )))
+        )
     }
 
-    fn read_comment_start(&mut self) -> LintResult<Option<Token>> {
+    fn read_comment_start(&mut self) -> Token {
         let start_pos = self.current_position();
 
         self.advance();
@@ -532,14 +568,36 @@ impl Lexer {
         self.inside_comment = true;
 
         let end_pos = self.current_position();
-        Ok(Some(Token::new(
+        Token::new(
             TokenType::CommentStart,
             Span::new(start_pos, end_pos),
             "<<<".to_string(),
-        )))
+        )
     }
 
-    fn read_comment_end(&mut self) -> LintResult<Option<Token>> {
+    /// read everything up to the closing `>>>` (or the end of input) as comment text
+    fn read_comment_text(&mut self) -> Token {
+        let start_pos = self.current_position();
+        let mut value = String::new();
+
+        while !self.is_at_end() {
+            let ch = self.current_char();
+            if ch == '>' && self.peek_ahead(2) == ">>" {
+                break;
+            }
+            value.push(ch);
+            self.advance_with_position_tracking(ch);
+        }
+
+        let end_pos = self.current_position();
+        Token::new(
+            TokenType::CommentText(value.clone()),
+            Span::new(start_pos, end_pos),
+            value,
+        )
+    }
+
+    fn read_comment_end(&mut self) -> Token {
         let start_pos = self.current_position();
 
         self.advance();
@@ -548,11 +606,11 @@ impl Lexer {
         self.column += 3;
 
         let end_pos = self.current_position();
-        Ok(Some(Token::new(
+        Token::new(
             TokenType::CommentEnd,
             Span::new(start_pos, end_pos),
             ">>>".to_string(),
-        )))
+        )
     }
 
     fn current_char(&self) -> char {
@@ -753,4 +811,46 @@ mod tests {
         assert!(matches!(tokens[2].token_type, TokenType::Word(ref w) if w == "test"));
         assert!(matches!(tokens[3].token_type, TokenType::Eof));
     }
+
+    #[test]
+    fn test_tokenize_with_errors_continues_past_errors() {
+        let mut lexer = Lexer::new("apple ! juice~5t AND x");
+        let (tokens, errors) = lexer.tokenize_with_errors();
+
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|e| e.code() == "E001"));
+
+        // the bad character is skipped and the tilde keeps its distance
+        let types: Vec<_> = tokens.iter().map(|t| t.token_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                TokenType::Word("apple".to_string()),
+                TokenType::Word("juice".to_string()),
+                TokenType::Tilde,
+                TokenType::Number("5".to_string()),
+                TokenType::And,
+                TokenType::Word("x".to_string()),
+                TokenType::Eof,
+            ]
+        );
+
+        // the strict entry point still fails on the first error
+        assert!(Lexer::new("apple ! juice").tokenize().is_err());
+    }
+
+    #[test]
+    fn test_comment_text() {
+        let mut lexer = Lexer::new("apple <<<a (comment) OR\nnote>>> juice");
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens.len(), 6);
+        assert!(matches!(tokens[1].token_type, TokenType::CommentStart));
+        assert!(
+            matches!(tokens[2].token_type, TokenType::CommentText(ref c) if c == "a (comment) OR\nnote")
+        );
+        assert!(matches!(tokens[3].token_type, TokenType::CommentEnd));
+        assert!(matches!(tokens[4].token_type, TokenType::Word(ref w) if w == "juice"));
+        assert_eq!(tokens[4].span.start.line, 2);
+    }
 }
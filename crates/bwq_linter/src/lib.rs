@@ -1,53 +1,113 @@
 pub mod ast;
+pub mod config;
+pub mod cst;
+pub mod docs;
 pub mod error;
+pub mod fix;
+pub mod fixture;
+pub mod formatter;
+pub mod languages;
 pub mod lexer;
+pub mod locations;
+pub mod matcher;
+pub mod metrics;
 pub mod parser;
+pub mod registry;
+mod suggest;
+pub mod suppression;
 pub mod validation;
 pub mod validator;
 
-use error::{LintError, LintReport, LintResult};
+use config::RuleSettings;
+use error::{LintError, LintReport};
 use lexer::Lexer;
+use metrics::QueryMetrics;
 use parser::Parser;
+use suppression::{apply_suppressions, parse_suppressions};
+use validation::ValidationResult;
 use validator::Validator;
 
 pub struct BrandwatchLinter {
     validator: Validator,
+    settings: RuleSettings,
 }
 
 impl BrandwatchLinter {
     pub fn new() -> Self {
+        Self::with_settings(RuleSettings::default())
+    }
+
+    /// a linter that reports diagnostics according to the given rule selection and severities
+    pub fn with_settings(settings: RuleSettings) -> Self {
         Self {
-            validator: Validator::new(),
+            validator: Validator::with_settings(settings.clone()),
+            settings,
         }
     }
 
-    pub fn lint(&mut self, query: &str) -> LintResult<LintReport> {
+    /// lint a query, reporting every syntax error alongside the validation results for
+    /// whatever parts of the query could be parsed
+    pub fn lint(&mut self, query: &str) -> LintReport {
         let mut lexer = Lexer::new(query);
-        let tokens = lexer.tokenize()?;
+        let (tokens, mut syntax_errors) = lexer.tokenize_with_errors();
+
+        let mut parser = Parser::new(tokens);
+        let parse_result = parser.parse();
+
+        syntax_errors.extend(parse_result.errors);
+        syntax_errors.sort_by_key(|error| error.span().start.offset);
 
-        let mut parser = Parser::new(tokens)?;
-        let parse_result = parser.parse()?;
+        // syntax diagnostics don't come from a rule, so only their codes can be configured
+        let syntax = self.settings.apply(
+            ValidationResult {
+                errors: syntax_errors,
+                warnings: parse_result.warnings,
+            },
+            None,
+        );
 
         let mut report = self.validator.validate(&parse_result.query);
-        report.warnings.extend(parse_result.warnings);
+        report.errors.splice(0..0, syntax.errors);
+        report.warnings.extend(syntax.warnings);
+
+        if !self.settings.limits.is_empty() {
+            let metrics = QueryMetrics::new(&parse_result.query, query);
+            let limits = self.settings.apply(
+                ValidationResult {
+                    errors: Vec::new(),
+                    warnings: self.settings.limits.check(&metrics, &parse_result.query),
+                },
+                None,
+            );
+            report.errors.extend(limits.errors);
+            report.warnings.extend(limits.warnings);
+        }
 
-        Ok(report)
+        // `<<<bwq: ignore[...]>>>` comments are applied last so they can silence anything
+        let (suppressions, mut suppression_warnings) =
+            parse_suppressions(&parse_result.query.comments, query);
+        suppression_warnings.extend(apply_suppressions(&mut report, suppressions));
+        let suppression = self.settings.apply(
+            ValidationResult {
+                errors: Vec::new(),
+                warnings: suppression_warnings,
+            },
+            None,
+        );
+        report.errors.extend(suppression.errors);
+        report.warnings.extend(suppression.warnings);
+
+        report
     }
 
     pub fn analyze(&mut self, query: &str) -> AnalysisResult {
-        match self.lint(query) {
-            Ok(report) => AnalysisResult {
-                is_valid: !report.has_errors(),
-                errors: report.errors,
-                warnings: report.warnings,
-                query: Some(query.to_string()),
-            },
-            Err(error) => AnalysisResult {
-                is_valid: false,
-                errors: vec![error],
-                warnings: vec![],
-                query: Some(query.to_string()),
-            },
+        let report = self.lint(query);
+
+        AnalysisResult {
+            is_valid: !report.has_errors(),
+            errors: report.errors,
+            warnings: report.warnings,
+            query: Some(query.to_string()),
         }
     }
 
@@ -134,7 +194,7 @@ impl AnalysisResult {
     }
 }
 
-pub fn lint_query(query: &str) -> LintResult<LintReport> {
+pub fn lint_query(query: &str) -> LintReport {
     let mut linter = BrandwatchLinter::new();
     linter.lint(query)
 }
@@ -151,14 +211,14 @@ mod tests {
     #[test]
     fn test_basic_linting() {
         let mut linter = BrandwatchLinter::new();
-        let report = linter.lint("apple AND juice").unwrap();
+        let report = linter.lint("apple AND juice");
         assert!(!report.has_errors());
     }
 
     #[test]
     fn test_invalid_query() {
         let mut linter = BrandwatchLinter::new();
-        let report = linter.lint("rating:6").unwrap();
+        let report = linter.lint("rating:6");
         assert!(report.has_errors());
     }
 
@@ -166,7 +226,7 @@ mod tests {
     fn test_complex_query() {
         let query = r#"(apple OR orange) AND "fruit juice" NOT bitter"#;
         let mut linter = BrandwatchLinter::new();
-        let report = linter.lint(query).unwrap();
+        let report = linter.lint(query);
         assert!(!report.has_errors());
     }
 
@@ -174,7 +234,7 @@ mod tests {
     fn test_field_query() {
         let query = r#"title:"apple juice" AND site:twitter.com"#;
         let mut linter = BrandwatchLinter::new();
-        let report = linter.lint(query).unwrap();
+        let report = linter.lint(query);
         assert!(!report.has_errors());
     }
 
@@ -183,11 +243,11 @@ mod tests {
         let mut linter = BrandwatchLinter::new();
 
         let query1 = r#"apple NEAR/3 juice"#;
-        let report1 = linter.lint(query1).unwrap();
+        let report1 = linter.lint(query1);
         assert!(!report1.has_errors());
 
         let query2 = r#""apple juice"~5"#;
-        let report2 = linter.lint(query2).unwrap();
+        let report2 = linter.lint(query2);
         assert!(!report2.has_errors());
     }
 
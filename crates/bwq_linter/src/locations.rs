@@ -0,0 +1,202 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+/// the dataset used when no `locations` file is configured
+const BUILTIN: &str = include_str!("../resources/locations.json");
+
+#[derive(Error, Debug)]
+pub enum LocationsError {
+    #[error("failed to read {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("failed to parse {}: {message}", path.display())]
+    Parse { path: PathBuf, message: String },
+}
+
+/// the location codes accepted by `continent:`, `country:`, `region:` and `city:`
+///
+/// countries are ISO 3166 alpha-3 codes, regions are `country.region` and cities are
+/// `country.region.city`, all lowercase. regions are only checked for countries that have some
+/// listed, and cities are only used to spot unquoted names containing spaces, so a partial
+/// dataset never rejects a valid code.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Locations {
+    pub continents: BTreeSet<String>,
+    pub countries: BTreeMap<String, Country>,
+    /// other names people use for a country, e.g. `uk`, and its code
+    pub aliases: BTreeMap<String, String>,
+    /// region codes and the region's name
+    pub regions: BTreeMap<String, String>,
+    pub cities: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Country {
+    pub name: String,
+    /// the ISO 3166 alpha-2 code
+    pub alpha2: Option<String>,
+}
+
+impl Locations {
+    /// the dataset embedded in bwq
+    pub fn builtin() -> Arc<Self> {
+        static LOCATIONS: OnceLock<Arc<Locations>> = OnceLock::new();
+        LOCATIONS
+            .get_or_init(|| {
+                Arc::new(
+                    Self::parse(BUILTIN, Path::new("locations.json"))
+                        .expect("built-in locations are valid"),
+                )
+            })
+            .clone()
+    }
+
+    pub fn load(path: &Path) -> Result<Self, LocationsError> {
+        let contents = fs::read_to_string(path).map_err(|source| LocationsError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse(&contents, path)
+    }
+
+    /// parse the contents of the JSON dataset at `path`
+    pub fn parse(contents: &str, path: &Path) -> Result<Self, LocationsError> {
+        serde_json::from_str(contents).map_err(|e| LocationsError::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })
+    }
+
+    pub fn country_name(&self, code: &str) -> Option<&str> {
+        self.countries
+            .get(code)
+            .map(|country| country.name.as_str())
+    }
+
+    /// the code for a country given by its alpha-2 code, an alias or its name, ignoring case
+    pub fn country_code(&self, value: &str) -> Option<&str> {
+        let value = value.to_lowercase();
+        self.aliases
+            .get_key_value(&value)
+            .map(|(_, code)| code.as_str())
+            .or_else(|| {
+                self.countries
+                    .iter()
+                    .find(|(_, country)| {
+                        country.alpha2.as_deref() == Some(value.as_str())
+                            || country.name.to_lowercase() == value
+                    })
+                    .map(|(code, _)| code.as_str())
+            })
+    }
+
+    /// the regions listed for a country, as `(code, name)`
+    pub fn regions_of<'a>(&'a self, country: &str) -> impl Iterator<Item = (&'a str, &'a str)> {
+        let prefix = format!("{country}.");
+        self.regions
+            .range(prefix.clone()..)
+            .take_while(move |(code, _)| code.starts_with(&prefix))
+            .map(|(code, name)| (code.as_str(), name.as_str()))
+    }
+
+    /// the code for a region of `country` given by its name, ignoring case
+    pub fn region_code(&self, country: &str, name: &str) -> Option<&str> {
+        self.regions_of(country)
+            .find(|(_, region)| region.eq_ignore_ascii_case(name))
+            .map(|(code, _)| code)
+    }
+
+    /// the codes of every listed city called `name`
+    pub fn cities_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.cities
+            .iter()
+            .filter(move |code| code.rsplit_once('.').is_some_and(|(_, city)| city == name))
+            .map(String::as_str)
+    }
+
+    /// every continent, region and city code that starts with `prefix`
+    pub fn codes_starting_with<'a>(&'a self, prefix: &str) -> impl Iterator<Item = &'a str> {
+        let prefix = prefix.to_string();
+        self.continents
+            .iter()
+            .chain(self.regions.keys())
+            .chain(&self.cities)
+            .map(String::as_str)
+            .filter(move |code| code.starts_with(&prefix))
+    }
+
+    pub fn is_code(&self, code: &str) -> bool {
+        self.continents.contains(code)
+            || self.countries.contains_key(code)
+            || self.regions.contains_key(code)
+            || self.cities.contains(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_builtin() {
+        let locations = Locations::builtin();
+
+        assert_eq!(locations.country_name("gbr"), Some("United Kingdom"));
+        assert!(locations.regions.contains_key("usa.fl"));
+        assert!(locations.is_code("deu.berlin.berlin"));
+        assert!(locations.is_code("north america"));
+
+        for code in locations.aliases.values() {
+            assert!(locations.countries.contains_key(code), "{code}");
+        }
+        for code in locations.regions.keys().chain(&locations.cities) {
+            let (parent, _) = code.rsplit_once('.').unwrap();
+            assert!(locations.is_code(parent), "{code}");
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        let locations = Locations::builtin();
+
+        assert_eq!(locations.country_code("uk"), Some("gbr"));
+        assert_eq!(locations.country_code("DE"), Some("deu"));
+        assert_eq!(locations.country_code("germany"), Some("deu"));
+        assert_eq!(locations.country_code("zz"), None);
+        assert_eq!(locations.region_code("usa", "florida"), Some("usa.fl"));
+        assert_eq!(locations.regions_of("gbr").count(), 4);
+        assert_eq!(
+            locations.cities_named("london").collect::<Vec<_>>(),
+            ["gbr.england.london"]
+        );
+        assert_eq!(locations.cities_named("portland").count(), 2);
+    }
+
+    #[test]
+    fn test_parse() {
+        let path = Path::new("locations.json");
+        let locations = Locations::parse(
+            r#"{"countries": {"gbr": {"name": "United Kingdom"}}, "regions": {"gbr.kent": "Kent"}}"#,
+            path,
+        )
+        .unwrap();
+
+        assert_eq!(locations.country_code("united kingdom"), Some("gbr"));
+        assert!(locations.continents.is_empty());
+        assert!(matches!(
+            Locations::parse(r#"{"counties": {}}"#, path),
+            Err(LocationsError::Parse { .. })
+        ));
+    }
+}
@@ -0,0 +1,874 @@
+use crate::ast::{BooleanOperator, Expression, FieldType, ProximityOperator, Query, Term};
+use crate::error::Span;
+
+/// a post to evaluate a query against: its text plus metadata for field operators
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Document {
+    pub text: String,
+    /// metadata values; a field can have several values, e.g. `links`
+    pub fields: Vec<(FieldType, String)>,
+}
+
+impl Document {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn with_field(mut self, field: FieldType, value: impl Into<String>) -> Self {
+        self.fields.push((field, value.into()));
+        self
+    }
+
+    fn values(&self, field: &FieldType) -> impl Iterator<Item = &str> {
+        self.fields
+            .iter()
+            .filter(move |(f, _)| f == field)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// whether a sub-expression of the query matched, with the same for its operands
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    /// where the sub-expression is in the query
+    pub span: Span,
+    pub matched: bool,
+    pub children: Vec<Explanation>,
+    /// token ranges of the text the sub-expression matched, used by proximity operators
+    hits: Vec<(usize, usize)>,
+    /// the child that decided the outcome, if there was a single one
+    cause: Option<usize>,
+}
+
+impl Explanation {
+    fn new(span: &Span, hits: Vec<(usize, usize)>) -> Self {
+        Self {
+            span: span.clone(),
+            matched: !hits.is_empty(),
+            children: Vec::new(),
+            hits,
+            cause: None,
+        }
+    }
+
+    fn with_children(mut self, children: Vec<Explanation>) -> Self {
+        self.children = children;
+        self
+    }
+
+    fn leaf(span: &Span, matched: bool) -> Self {
+        Self {
+            span: span.clone(),
+            matched,
+            children: Vec::new(),
+            hits: Vec::new(),
+            cause: None,
+        }
+    }
+
+    /// the most specific sub-expression responsible for whether the query matched, e.g. the
+    /// missing term of an AND or the excluded term that was found
+    pub fn cause(&self) -> &Explanation {
+        match self.cause {
+            Some(index) => self.children[index].cause(),
+            None => self,
+        }
+    }
+
+    /// the sub-expression's text in `source`, the query the explanation was made for
+    pub fn text(&self, source: &str) -> String {
+        source
+            .chars()
+            .skip(self.span.start.offset)
+            .take(self.span.end.offset - self.span.start.offset)
+            .collect()
+    }
+
+    /// one line per sub-expression, indented by depth and marked with whether it matched
+    pub fn render(&self, source: &str) -> String {
+        let mut lines = Vec::new();
+        self.render_into(source, 0, &mut lines);
+        lines.join("\n")
+    }
+
+    fn render_into(&self, source: &str, depth: usize, lines: &mut Vec<String>) {
+        let mark = if self.matched { "✓" } else { "✗" };
+        lines.push(format!(
+            "{}{mark} {}",
+            "  ".repeat(depth),
+            self.text(source)
+        ));
+        for child in &self.children {
+            child.render_into(source, depth + 1, lines);
+        }
+    }
+}
+
+/// evaluate `query` against `document`, following Brandwatch's matching rules:
+///
+/// - words match whole words of the text, ignoring case; punctuation inside a word (`coca-cola`)
+///   matches any separator, so the parts are matched as a phrase
+/// - an unaccented letter also matches its accented forms, but an accented letter only matches
+///   itself
+/// - `*` matches any number of characters and `?` exactly one, except in phrases, hashtags and
+///   mentions, where they aren't expanded and are read as punctuation, like in the text
+/// - `{...}` matches case-sensitively
+/// - `"a b"~N` matches when the words appear in any order with at most N other words among
+///   them, and `x NEAR/N y` when at most N words come between `x` and `y` (`NEAR/Nf`: `x` first)
+/// - `title:` searches the title like the text; other fields compare against the whole
+///   metadata value, with `site:` also matching subdomains and `url:`/`links:` any part of a URL
+pub fn evaluate(query: &Query, document: &Document) -> Explanation {
+    let evaluator = Evaluator {
+        document,
+        text: tokenize(&document.text, false),
+        title: document
+            .values(&FieldType::Title)
+            .flat_map(|title| tokenize(title, false))
+            .collect(),
+    };
+
+    let tokens = &evaluator.text;
+    evaluator.eval(
+        &query.expression,
+        Scope::Text {
+            tokens,
+            start: 0,
+            end: tokens.len(),
+        },
+    )
+}
+
+/// what terms are compared against
+#[derive(Clone, Copy)]
+enum Scope<'a> {
+    /// words of the text (or title) between `start` and `end`
+    Text {
+        tokens: &'a [Token],
+        start: usize,
+        end: usize,
+    },
+    /// the document's values for a metadata field
+    Field(&'a FieldType),
+}
+
+struct Evaluator<'a> {
+    document: &'a Document,
+    text: Vec<Token>,
+    title: Vec<Token>,
+}
+
+impl Evaluator<'_> {
+    fn eval(&self, expr: &Expression, scope: Scope) -> Explanation {
+        match expr {
+            Expression::BooleanOp {
+                operator: BooleanOperator::Not,
+                left,
+                right: None,
+                span,
+            } => {
+                let operand = self.eval(left, scope);
+                Explanation {
+                    span: span.clone(),
+                    matched: !operand.matched,
+                    children: vec![operand],
+                    hits: Vec::new(),
+                    cause: Some(0),
+                }
+            }
+            Expression::BooleanOp {
+                operator: BooleanOperator::Not,
+                left,
+                right: Some(right),
+                span,
+            } => {
+                let included = self.eval(left, scope);
+                let excluded = self.eval(right, scope);
+                let cause = if included.matched && excluded.matched {
+                    1
+                } else {
+                    0
+                };
+                Explanation {
+                    span: span.clone(),
+                    matched: included.matched && !excluded.matched,
+                    hits: included.hits.clone(),
+                    children: vec![included, excluded],
+                    cause: Some(cause),
+                }
+            }
+            Expression::BooleanOp { operator, span, .. } => {
+                let mut operands = Vec::new();
+                collect_operands(expr, operator, &mut operands);
+                let children: Vec<_> = operands
+                    .into_iter()
+                    .map(|operand| self.eval(operand, scope))
+                    .collect();
+
+                let matched = if *operator == BooleanOperator::And {
+                    children.iter().all(|child| child.matched)
+                } else {
+                    children.iter().any(|child| child.matched)
+                };
+                // a missing operand fails an AND and a found one passes an OR
+                let decisive = (*operator == BooleanOperator::And) != matched;
+                let cause = decisive
+                    .then(|| children.iter().position(|child| child.matched == matched))
+                    .flatten();
+                let hits = if matched {
+                    children
+                        .iter()
+                        .flat_map(|child| child.hits.iter().copied())
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+
+                Explanation {
+                    span: span.clone(),
+                    matched,
+                    children,
+                    hits,
+                    cause,
+                }
+            }
+            Expression::Group { expression, span } => Explanation {
+                span: span.clone(),
+                ..self.eval(expression, scope)
+            },
+            Expression::Proximity {
+                operator,
+                terms,
+                span,
+            } => self.eval_proximity(operator, terms, span, scope),
+            Expression::Field { field, value, span } => {
+                let value_scope = if *field == FieldType::Title {
+                    Scope::Text {
+                        tokens: &self.title,
+                        start: 0,
+                        end: self.title.len(),
+                    }
+                } else {
+                    Scope::Field(field)
+                };
+
+                Explanation::leaf(span, self.eval(value, value_scope).matched)
+            }
+            Expression::Range {
+                field: Some(field),
+                start,
+                end,
+                span,
+            } => Explanation::leaf(
+                span,
+                self.document
+                    .values(field)
+                    .any(|value| in_range(value, start, end)),
+            ),
+            Expression::Range { span, .. } | Expression::Error { span } => {
+                Explanation::new(span, Vec::new())
+            }
+            Expression::Term { term, span } => match scope {
+                Scope::Text { tokens, start, end } => {
+                    Explanation::new(span, find_term(term, &tokens[..end], start))
+                }
+                Scope::Field(field) => {
+                    let matched = term_value(term).is_some_and(|(expected, case_sensitive)| {
+                        self.document
+                            .values(field)
+                            .any(|actual| field_matches(field, expected, actual, case_sensitive))
+                    });
+                    Explanation::leaf(span, matched)
+                }
+            },
+        }
+    }
+
+    fn eval_proximity(
+        &self,
+        operator: &ProximityOperator,
+        terms: &[Expression],
+        span: &Span,
+        scope: Scope,
+    ) -> Explanation {
+        match operator {
+            ProximityOperator::Proximity { distance } => {
+                let Some(operand) = terms.first() else {
+                    return Explanation::new(span, Vec::new());
+                };
+                let (Some(distance), Scope::Text { tokens, start, end }) = (distance, scope) else {
+                    let child = self.eval(operand, scope);
+                    return Explanation::new(span, child.hits.clone()).with_children(vec![child]);
+                };
+
+                // like NEAR, the distance counts the words in between, so the operand has to
+                // match within some run of its own words plus `distance` others
+                let window = word_count(operand) + *distance as usize;
+                let child = (start..end)
+                    .map(|first| Scope::Text {
+                        tokens,
+                        start: first,
+                        end: (first + window).min(end),
+                    })
+                    .map(|window| self.eval_bag(operand, window))
+                    .find(|explanation| explanation.matched)
+                    .unwrap_or_else(|| Explanation {
+                        matched: false,
+                        hits: Vec::new(),
+                        cause: None,
+                        // still show which parts matched, just not close enough together
+                        ..self.eval_bag(operand, scope)
+                    });
+                Explanation::new(span, child.hits.clone()).with_children(vec![child])
+            }
+            ProximityOperator::Near { distance } | ProximityOperator::NearForward { distance } => {
+                let forward = matches!(operator, ProximityOperator::NearForward { .. });
+                let [left, right] = terms else {
+                    return Explanation::new(span, Vec::new());
+                };
+                let left = self.eval(left, scope);
+                let right = self.eval(right, scope);
+
+                let mut hits = Vec::new();
+                for &l in &left.hits {
+                    for &r in &right.hits {
+                        // the distance counts the words in between
+                        let near = (r.0 > l.1 && r.0 - l.1 - 1 <= *distance as usize)
+                            || (!forward && l.0 > r.1 && l.0 - r.1 - 1 <= *distance as usize);
+                        if near {
+                            hits.extend([l, r]);
+                        }
+                    }
+                }
+
+                let mut explanation = Explanation::new(span, hits);
+                if !explanation.matched {
+                    explanation.cause = [&left, &right].iter().position(|child| !child.matched);
+                }
+                explanation.with_children(vec![left, right])
+            }
+        }
+    }
+
+    /// evaluate the operand of `~`, where a phrase's words can appear in any order
+    fn eval_bag(&self, operand: &Expression, scope: Scope) -> Explanation {
+        let (
+            Expression::Term {
+                term: Term::Phrase { value },
+                span,
+            },
+            Scope::Text { tokens, start, end },
+        ) = (operand, scope)
+        else {
+            return self.eval(operand, scope);
+        };
+
+        let mut hits = Vec::new();
+        for word in tokenize(value, true) {
+            let word_hits = find_sequence(&tokens[..end], start, &[word], false);
+            if word_hits.is_empty() {
+                return Explanation::new(span, Vec::new());
+            }
+            hits.extend(word_hits);
+        }
+        Explanation::new(span, hits)
+    }
+}
+
+/// how many words of the text `expr` takes up when it matches, counting the longest alternative
+/// of an OR
+fn word_count(expr: &Expression) -> usize {
+    match expr {
+        Expression::Term { term, .. } => term_value(term)
+            .map_or(1, |(value, _)| tokenize(value, true).len())
+            .max(1),
+        Expression::BooleanOp {
+            operator: BooleanOperator::And,
+            left,
+            right: Some(right),
+            ..
+        } => word_count(left) + word_count(right),
+        Expression::BooleanOp {
+            operator: BooleanOperator::Or,
+            left,
+            right: Some(right),
+            ..
+        } => word_count(left).max(word_count(right)),
+        Expression::BooleanOp {
+            right: Some(_),
+            left,
+            ..
+        } => word_count(left),
+        Expression::Group { expression, .. } => word_count(expression),
+        Expression::Proximity { terms, .. } => terms.iter().map(word_count).sum(),
+        Expression::Field { .. } | Expression::Range { .. } => 1,
+        Expression::BooleanOp { right: None, .. } | Expression::Error { .. } => 0,
+    }
+}
+
+/// the operands of a chain of the same boolean operator, e.g. `a`, `b` and `c` in `a OR b OR c`
+fn collect_operands<'a>(
+    expr: &'a Expression,
+    operator: &BooleanOperator,
+    operands: &mut Vec<&'a Expression>,
+) {
+    match expr {
+        Expression::BooleanOp {
+            operator: op,
+            left,
+            right: Some(right),
+            ..
+        } if op == operator => {
+            collect_operands(left, operator, operands);
+            collect_operands(right, operator, operands);
+        }
+        _ => operands.push(expr),
+    }
+}
+
+/// where a term appears among `tokens`, starting from `start`
+fn find_term(term: &Term, tokens: &[Token], start: usize) -> Vec<(usize, usize)> {
+    match term {
+        Term::Word { value }
+        | Term::Wildcard { value }
+        | Term::Replacement { value }
+        | Term::Emoji { value } => find_sequence(tokens, start, &tokenize(value, true), false),
+        Term::Phrase { value } => find_sequence(tokens, start, &tokenize(value, false), false),
+        Term::CaseSensitive { value } => find_sequence(
+            tokens,
+            start,
+            &tokenize(value.trim_matches('"'), true),
+            true,
+        ),
+        Term::Hashtag { value } | Term::Mention { value } => {
+            let prefix = if matches!(term, Term::Hashtag { .. }) {
+                '#'
+            } else {
+                '@'
+            };
+            find_sequence(tokens, start, &tokenize(value, false), false)
+                .into_iter()
+                .filter(|&(first, _)| tokens[first].prefix == Some(prefix))
+                .collect()
+        }
+    }
+}
+
+/// where the words of `query` appear consecutively among `tokens`
+fn find_sequence(
+    tokens: &[Token],
+    start: usize,
+    query: &[Token],
+    case_sensitive: bool,
+) -> Vec<(usize, usize)> {
+    if query.is_empty() || tokens.len() < query.len() {
+        return Vec::new();
+    }
+
+    (start..=tokens.len() - query.len())
+        .filter(|&first| {
+            query.iter().enumerate().all(|(i, word)| {
+                pattern_matches(&word.pattern(), &tokens[first + i].chars, case_sensitive)
+            })
+        })
+        .map(|first| (first, first + query.len() - 1))
+        .collect()
+}
+
+/// a word of a text or query
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    chars: Vec<char>,
+    /// `#` or `@` directly before the word
+    prefix: Option<char>,
+}
+
+impl Token {
+    fn pattern(&self) -> Vec<Pattern> {
+        self.chars
+            .iter()
+            .map(|&ch| match ch {
+                '*' => Pattern::Any,
+                '?' => Pattern::One,
+                ch => Pattern::Char(ch),
+            })
+            .collect()
+    }
+}
+
+/// split text into words: runs of letters and digits, with anything else that isn't
+/// whitespace or ASCII punctuation (e.g. an emoji) as a word of its own
+fn tokenize(text: &str, wildcards: bool) -> Vec<Token> {
+    let chars: Vec<char> = text.chars().collect();
+    let is_word_char = |ch: char| ch.is_alphanumeric() || (wildcards && matches!(ch, '*' | '?'));
+
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let ch = chars[i];
+        let start = i;
+        if is_word_char(ch) {
+            while i < chars.len() && is_word_char(chars[i]) {
+                i += 1;
+            }
+        } else if !ch.is_whitespace() && !ch.is_ascii() {
+            i += 1;
+        } else {
+            i += 1;
+            continue;
+        }
+
+        let prefix = start
+            .checked_sub(1)
+            .map(|before| chars[before])
+            .filter(|&before| {
+                matches!(before, '#' | '@') && (start < 2 || !chars[start - 2].is_alphanumeric())
+            });
+        tokens.push(Token {
+            chars: chars[start..i].to_vec(),
+            prefix,
+        });
+    }
+
+    tokens
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Pattern {
+    Char(char),
+    /// `?`
+    One,
+    /// `*`
+    Any,
+}
+
+fn pattern_matches(pattern: &[Pattern], text: &[char], case_sensitive: bool) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((Pattern::Any, rest)) => {
+            (0..=text.len()).any(|skip| pattern_matches(rest, &text[skip..], case_sensitive))
+        }
+        Some((Pattern::One, rest)) => {
+            !text.is_empty() && pattern_matches(rest, &text[1..], case_sensitive)
+        }
+        Some((Pattern::Char(ch), rest)) => {
+            !text.is_empty()
+                && chars_match(*ch, text[0], case_sensitive)
+                && pattern_matches(rest, &text[1..], case_sensitive)
+        }
+    }
+}
+
+/// whether `query` matches `text`; an unaccented query letter also matches accented letters
+fn chars_match(query: char, text: char, case_sensitive: bool) -> bool {
+    let (query, text) = if case_sensitive {
+        (query, text)
+    } else {
+        (lowercase(query), lowercase(text))
+    };
+
+    query == text || (fold_accent(query) == query && fold_accent(text) == query)
+}
+
+fn lowercase(ch: char) -> char {
+    ch.to_lowercase().next().unwrap_or(ch)
+}
+
+/// the unaccented form of a Latin letter
+fn fold_accent(ch: char) -> char {
+    const ACCENTED: &[(&str, char)] = &[
+        ("àáâãäåāăą", 'a'),
+        ("ÀÁÂÃÄÅĀĂĄ", 'A'),
+        ("çćĉċč", 'c'),
+        ("ÇĆĈĊČ", 'C'),
+        ("ďđ", 'd'),
+        ("ĎĐ", 'D'),
+        ("èéêëēĕėęě", 'e'),
+        ("ÈÉÊËĒĔĖĘĚ", 'E'),
+        ("ĝğġģ", 'g'),
+        ("ĜĞĠĢ", 'G'),
+        ("ĥħ", 'h'),
+        ("ĤĦ", 'H'),
+        ("ìíîïĩīĭįı", 'i'),
+        ("ÌÍÎÏĨĪĬĮİ", 'I'),
+        ("ĵ", 'j'),
+        ("Ĵ", 'J'),
+        ("ķ", 'k'),
+        ("Ķ", 'K'),
+        ("ĺļľŀł", 'l'),
+        ("ĹĻĽĿŁ", 'L'),
+        ("ñńņň", 'n'),
+        ("ÑŃŅŇ", 'N'),
+        ("òóôõöøōŏő", 'o'),
+        ("ÒÓÔÕÖØŌŎŐ", 'O'),
+        ("ŕŗř", 'r'),
+        ("ŔŖŘ", 'R'),
+        ("śŝşšș", 's'),
+        ("ŚŜŞŠȘ", 'S'),
+        ("ţťŧț", 't'),
+        ("ŢŤŦȚ", 'T'),
+        ("ùúûüũūŭůűų", 'u'),
+        ("ÙÚÛÜŨŪŬŮŰŲ", 'U'),
+        ("ŵ", 'w'),
+        ("Ŵ", 'W'),
+        ("ýÿŷ", 'y'),
+        ("ÝŸŶ", 'Y'),
+        ("źżž", 'z'),
+        ("ŹŻŽ", 'Z'),
+    ];
+
+    if ch.is_ascii() {
+        return ch;
+    }
+
+    ACCENTED
+        .iter()
+        .find(|(accented, _)| accented.contains(ch))
+        .map_or(ch, |&(_, plain)| plain)
+}
+
+/// the value a term compares a metadata value against, and whether it's case-sensitive
+fn term_value(term: &Term) -> Option<(&str, bool)> {
+    match term {
+        Term::Word { value }
+        | Term::Phrase { value }
+        | Term::Wildcard { value }
+        | Term::Replacement { value } => Some((value, false)),
+        Term::CaseSensitive { value } => Some((value.trim_matches('"'), true)),
+        Term::Hashtag { .. } | Term::Mention { .. } | Term::Emoji { .. } => None,
+    }
+}
+
+/// whether a metadata value matches the value given to a field operator
+fn field_matches(field: &FieldType, expected: &str, actual: &str, case_sensitive: bool) -> bool {
+    let compile = |value: &str| Token {
+        chars: value.chars().collect(),
+        prefix: None,
+    };
+
+    match field {
+        FieldType::Site | FieldType::Url | FieldType::Links => {
+            let pattern = compile(strip_url(expected)).pattern();
+            let actual: Vec<char> = strip_url(actual).chars().collect();
+            let (prefix, suffix): (&[Pattern], &[Pattern]) = if *field == FieldType::Site {
+                // subdomains and pages of the site
+                (
+                    &[Pattern::Any, Pattern::Char('.')],
+                    &[Pattern::Char('/'), Pattern::Any],
+                )
+            } else {
+                (&[Pattern::Any], &[Pattern::Any])
+            };
+
+            pattern_matches(&pattern, &actual, case_sensitive)
+                || pattern_matches(&[prefix, &pattern].concat(), &actual, case_sensitive)
+                || pattern_matches(&[&pattern, suffix].concat(), &actual, case_sensitive)
+                || pattern_matches(
+                    &[prefix, &pattern, suffix].concat(),
+                    &actual,
+                    case_sensitive,
+                )
+        }
+        _ => {
+            let chars: Vec<char> = actual.chars().collect();
+            pattern_matches(&compile(expected).pattern(), &chars, case_sensitive)
+                || matches!(
+                    (expected.parse::<f64>(), actual.parse::<f64>()),
+                    (Ok(expected), Ok(actual)) if expected == actual
+                )
+        }
+    }
+}
+
+/// a URL without its scheme, `www.` or trailing slash
+fn strip_url(url: &str) -> &str {
+    let url = url
+        .split_once("://")
+        .map_or(url, |(_, rest)| rest)
+        .trim_end_matches('/');
+    url.strip_prefix("www.").unwrap_or(url)
+}
+
+/// whether a numeric value is within `[start TO end]`, where `*` leaves a bound open
+fn in_range(value: &str, start: &str, end: &str) -> bool {
+    let Ok(value) = value.parse::<f64>() else {
+        return false;
+    };
+    let bound = |bound: &str| bound.parse::<f64>().ok();
+
+    (start == "*" || bound(start).is_some_and(|start| start <= value))
+        && (end == "*" || bound(end).is_some_and(|end| value <= end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Lexer, parser::Parser};
+    use pretty_assertions::assert_eq;
+
+    fn parse(query: &str) -> Query {
+        let (tokens, _) = Lexer::new(query).tokenize_with_errors();
+        Parser::new(tokens).parse().query
+    }
+
+    fn matches(query: &str, document: &Document) -> bool {
+        evaluate(&parse(query), document).matched
+    }
+
+    fn matches_text(query: &str, text: &str) -> bool {
+        matches(query, &Document::new(text))
+    }
+
+    #[test]
+    fn test_words_and_phrases() {
+        let text = "I love Apple juice, but not the coca-cola.";
+
+        assert!(matches_text("apple", text));
+        assert!(matches_text("\"apple juice\"", text));
+        assert!(!matches_text("\"juice apple\"", text));
+        assert!(!matches_text("app", text));
+        assert!(matches_text("coca-cola", text));
+        assert!(matches_text("\"coca cola\"", text));
+    }
+
+    #[test]
+    fn test_boolean_operators() {
+        let text = "apple juice";
+
+        assert!(matches_text("apple AND juice", text));
+        assert!(matches_text("apple juice", text));
+        assert!(!matches_text("apple AND cherry", text));
+        assert!(matches_text("cherry OR apple", text));
+        assert!(!matches_text("apple NOT juice", text));
+        assert!(matches_text("apple NOT cherry", text));
+        assert!(matches_text("NOT cherry", text));
+        assert!(matches_text("(cherry OR apple) AND (juice NOT pie)", text));
+    }
+
+    #[test]
+    fn test_wildcards_and_case() {
+        let text = "Customise your Apple";
+
+        assert!(matches_text("custom*", text));
+        assert!(matches_text("customi?e", text));
+        assert!(!matches_text("custo?e", text));
+        assert!(matches_text("{Apple}", text));
+        assert!(!matches_text("{apple}", text));
+
+        // not expanded in phrases, hashtags and mentions
+        assert!(!matches_text("\"apple*\"", "applesauce"));
+        assert!(matches_text("\"apple*\"", "apple sauce"));
+        assert!(!matches_text("#apple*", "#applesauce"));
+        assert!(!matches_text("@brand?atch", "@brandwatch"));
+    }
+
+    #[test]
+    fn test_accents() {
+        assert!(matches_text("cafe", "a café in Paris"));
+        assert!(matches_text("café", "a café in Paris"));
+        assert!(!matches_text("café", "a cafe in Paris"));
+    }
+
+    #[test]
+    fn test_proximity() {
+        let text = "apple is a very nice juice";
+
+        // four words come between apple and juice
+        assert!(matches_text("apple NEAR/4 juice", text));
+        assert!(!matches_text("apple NEAR/3 juice", text));
+        assert!(matches_text("juice NEAR/4 apple", text));
+        assert!(matches_text("apple NEAR/4f juice", text));
+        assert!(!matches_text("juice NEAR/4f apple", text));
+        assert!(matches_text("(apple OR pear) NEAR/3 \"nice juice\"", text));
+        assert!(matches_text(
+            "logitech NEAR/2f keyboard",
+            "Logitech a b Keyboard"
+        ));
+        assert!(matches_text("apple NEAR/0f juice", "apple juice"));
+
+        // `~` counts the words in between the same way
+        assert!(matches_text("\"juice apple\"~4", text));
+        assert!(!matches_text("\"juice apple\"~3", text));
+        assert!(matches_text("(apple AND nice)~3", text));
+        assert!(!matches_text("(apple AND juice)~3", text));
+        assert!(matches_text("\"apple juice\"~0", "apple juice"));
+        assert!(matches_text("\"apple juice\"~0", "juice apple"));
+        assert!(!matches_text("\"apple juice\"~0", "apple nice juice"));
+        assert!(matches_text("\"apple juice\"~1", "apple nice juice"));
+        assert!(!matches_text("\"apple juice\"~1", "apple very nice juice"));
+    }
+
+    #[test]
+    fn test_hashtags_and_mentions() {
+        let text = "#apple by @brandwatch";
+
+        assert!(matches_text("#apple", text));
+        assert!(matches_text("apple", text));
+        assert!(!matches_text("#brandwatch", text));
+        assert!(matches_text("@brandwatch", text));
+    }
+
+    #[test]
+    fn test_fields() {
+        let document = Document::new("apple juice")
+            .with_field(FieldType::Site, "https://www.news.bbc.co.uk/")
+            .with_field(FieldType::Title, "Breaking news")
+            .with_field(FieldType::Language, "en")
+            .with_field(FieldType::AuthorFollowers, "250")
+            .with_field(FieldType::Url, "https://twitter.com/brandwatch/status/1");
+
+        assert!(matches("apple AND site:bbc.co.uk", &document));
+        assert!(!matches("site:cnn.com", &document));
+        assert!(matches("title:breaking", &document));
+        assert!(!matches("title:apple", &document));
+        assert!(matches("language:(fr OR en)", &document));
+        assert!(!matches("language:fr", &document));
+        assert!(matches("authorFollowers:[100 TO 1000]", &document));
+        assert!(!matches("authorFollowers:[300 TO *]", &document));
+        assert!(matches("url:twitter.com/brandwatch", &document));
+        assert!(!matches("country:usa", &document));
+    }
+
+    #[test]
+    fn test_cause() {
+        let cause = |query: &str, text: &str| {
+            evaluate(&parse(query), &Document::new(text))
+                .cause()
+                .text(query)
+        };
+
+        assert_eq!(
+            cause("apple AND (juice OR pie)", "apple cider"),
+            "(juice OR pie)"
+        );
+        assert_eq!(
+            cause("apple AND (juice OR pie)", "apple pie"),
+            "apple AND (juice OR pie)"
+        );
+        assert_eq!(cause("(apple OR pear) NOT pie", "apple pie"), "pie");
+        assert_eq!(cause("apple NEAR/2 juice", "apple cider"), "juice");
+        assert_eq!(
+            cause("apple NEAR/2 juice", "juice is not really apple"),
+            "apple NEAR/2 juice"
+        );
+    }
+
+    #[test]
+    fn test_explanation() {
+        let query = "apple AND (juice OR cherry) NOT pie";
+        let explanation = evaluate(&parse(query), &Document::new("apple juice"));
+
+        assert_eq!(
+            explanation.render(query),
+            "✓ apple AND (juice OR cherry) NOT pie
+  ✓ apple
+  ✓ (juice OR cherry) NOT pie
+    ✓ (juice OR cherry)
+      ✓ juice
+      ✗ cherry
+    ✗ pie"
+        );
+    }
+}
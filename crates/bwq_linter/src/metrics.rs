@@ -0,0 +1,308 @@
+use serde::Deserialize;
+
+use crate::ast::{Expression, ProximityOperator, Query, Term};
+use crate::error::LintWarning;
+
+/// size and complexity figures for a query, as checked against [`Limits`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryMetrics {
+    /// characters in the query once comments and surrounding whitespace are removed
+    pub characters: usize,
+    /// search terms, not counting field values
+    pub terms: usize,
+    /// terms containing `*`
+    pub wildcards: usize,
+    /// wildcards with a two-character stem, e.g. `ab*`
+    pub short_wildcards: usize,
+    /// the deepest nesting of parentheses
+    pub max_depth: usize,
+    /// `NEAR/x` and `NEAR/xf` operators
+    pub near_operators: usize,
+    /// field operators, e.g. `title:` and `rating:[1 TO 3]`
+    pub field_operators: usize,
+}
+
+impl QueryMetrics {
+    /// the metrics of `query`, parsed from `source`
+    pub fn new(query: &Query, source: &str) -> Self {
+        let mut metrics = Self {
+            characters: characters(query, source),
+            ..Self::default()
+        };
+        metrics.collect(&query.expression, 0, false);
+        metrics
+    }
+
+    fn collect(&mut self, expr: &Expression, depth: usize, in_field: bool) {
+        match expr {
+            Expression::BooleanOp { left, right, .. } => {
+                self.collect(left, depth, in_field);
+                if let Some(right) = right {
+                    self.collect(right, depth, in_field);
+                }
+            }
+            Expression::Group { expression, .. } => {
+                self.max_depth = self.max_depth.max(depth + 1);
+                self.collect(expression, depth + 1, in_field);
+            }
+            Expression::Proximity {
+                operator, terms, ..
+            } => {
+                if !matches!(operator, ProximityOperator::Proximity { .. }) {
+                    self.near_operators += 1;
+                }
+                for term in terms {
+                    self.collect(term, depth, in_field);
+                }
+            }
+            Expression::Field { value, .. } => {
+                self.field_operators += 1;
+                self.collect(value, depth, true);
+            }
+            Expression::Range { .. } | Expression::Error { .. } => {}
+            Expression::Term { term, .. } => {
+                if !in_field {
+                    self.terms += 1;
+                }
+                if let Term::Wildcard { value } = term {
+                    self.wildcards += 1;
+                    if WildcardStem::of(value) == WildcardStem::Short {
+                        self.short_wildcards += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    /// each metric by name, in the order they're reported
+    pub fn entries(&self) -> [(&'static str, usize); 7] {
+        [
+            ("characters", self.characters),
+            ("terms", self.terms),
+            ("wildcards", self.wildcards),
+            ("short_wildcards", self.short_wildcards),
+            ("max_depth", self.max_depth),
+            ("near_operators", self.near_operators),
+            ("field_operators", self.field_operators),
+        ]
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        self.entries()
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value.into()))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+}
+
+/// a wildcard term classified by the length of its stem, the part before the first `*`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WildcardStem {
+    /// `*ab`, which Brandwatch rejects
+    Leading,
+    /// `a*`, which Brandwatch rejects as too broad
+    Single,
+    /// `ab*`, of which Brandwatch allows a limited number
+    Short,
+    /// `abc*` or longer
+    Long,
+}
+
+impl WildcardStem {
+    pub fn of(value: &str) -> Self {
+        match value.split('*').next().unwrap_or_default().chars().count() {
+            0 => Self::Leading,
+            1 => Self::Single,
+            2 => Self::Short,
+            _ => Self::Long,
+        }
+    }
+}
+
+fn characters(query: &Query, source: &str) -> usize {
+    let mut comments = query
+        .comments
+        .iter()
+        .map(|comment| &comment.span)
+        .peekable();
+    let text: String = source
+        .chars()
+        .enumerate()
+        .filter(|(offset, _)| {
+            while comments
+                .next_if(|span| span.end.offset <= *offset)
+                .is_some()
+            {}
+            comments
+                .peek()
+                .is_none_or(|span| *offset < span.start.offset)
+        })
+        .map(|(_, ch)| ch)
+        .collect();
+
+    text.trim().chars().count()
+}
+
+/// the most a query may have of each metric; unset limits aren't checked
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Limits {
+    pub max_characters: Option<usize>,
+    pub max_terms: Option<usize>,
+    pub max_wildcards: Option<usize>,
+    pub max_short_wildcards: Option<usize>,
+    pub max_depth: Option<usize>,
+    pub max_near_operators: Option<usize>,
+    pub max_field_operators: Option<usize>,
+}
+
+impl Limits {
+    /// set the limits that `other` sets, keeping the rest
+    pub fn merge(&mut self, other: &Limits) {
+        let Limits {
+            max_characters,
+            max_terms,
+            max_wildcards,
+            max_short_wildcards,
+            max_depth,
+            max_near_operators,
+            max_field_operators,
+        } = other;
+
+        self.max_characters = max_characters.or(self.max_characters);
+        self.max_terms = max_terms.or(self.max_terms);
+        self.max_wildcards = max_wildcards.or(self.max_wildcards);
+        self.max_short_wildcards = max_short_wildcards.or(self.max_short_wildcards);
+        self.max_depth = max_depth.or(self.max_depth);
+        self.max_near_operators = max_near_operators.or(self.max_near_operators);
+        self.max_field_operators = max_field_operators.or(self.max_field_operators);
+    }
+
+    /// each limit by the name of its metric, in the order of [`QueryMetrics::entries`]
+    pub fn entries(&self) -> [(&'static str, Option<usize>); 7] {
+        [
+            ("characters", self.max_characters),
+            ("terms", self.max_terms),
+            ("wildcards", self.max_wildcards),
+            ("short_wildcards", self.max_short_wildcards),
+            ("max_depth", self.max_depth),
+            ("near_operators", self.max_near_operators),
+            ("field_operators", self.max_field_operators),
+        ]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Limits::default()
+    }
+
+    /// a warning for each metric of `query` above its limit
+    pub fn check(&self, metrics: &QueryMetrics, query: &Query) -> Vec<LintWarning> {
+        let checks = [
+            (self.max_characters, metrics.characters, "characters"),
+            (self.max_terms, metrics.terms, "terms"),
+            (self.max_wildcards, metrics.wildcards, "wildcards"),
+            (
+                self.max_short_wildcards,
+                metrics.short_wildcards,
+                "short wildcards",
+            ),
+            (self.max_depth, metrics.max_depth, "levels of nesting"),
+            (
+                self.max_near_operators,
+                metrics.near_operators,
+                "NEAR operators",
+            ),
+            (
+                self.max_field_operators,
+                metrics.field_operators,
+                "field operators",
+            ),
+        ];
+
+        checks
+            .into_iter()
+            .filter_map(|(limit, value, metric)| {
+                let limit = limit?;
+                (value > limit).then(|| LintWarning::LimitExceeded {
+                    span: query.span.clone(),
+                    metric: metric.to_string(),
+                    value,
+                    limit,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    fn metrics(source: &str) -> QueryMetrics {
+        let (tokens, _) = Lexer::new(source).tokenize_with_errors();
+        QueryMetrics::new(&Parser::new(tokens).parse().query, source)
+    }
+
+    #[test]
+    fn test_metrics() {
+        let source = "<<<brands>>>\n(apple OR ap* OR appl*) AND\n(title:(juice OR \"cherry pie\") OR (tart NEAR/3 pie))\nAND rating:[1 TO 3] <<<end>>>\n";
+
+        assert_eq!(
+            metrics(source),
+            QueryMetrics {
+                characters: 100,
+                terms: 5,
+                wildcards: 2,
+                short_wildcards: 1,
+                max_depth: 2,
+                near_operators: 1,
+                field_operators: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_wildcard_stem() {
+        assert_eq!(WildcardStem::of("*ab"), WildcardStem::Leading);
+        assert_eq!(WildcardStem::of("a*"), WildcardStem::Single);
+        assert_eq!(WildcardStem::of("éa*"), WildcardStem::Short);
+        assert_eq!(WildcardStem::of("ab*c"), WildcardStem::Short);
+        assert_eq!(WildcardStem::of("abc*"), WildcardStem::Long);
+    }
+
+    #[test]
+    fn test_proximity_is_not_near() {
+        let metrics = metrics("\"apple juice\"~5 OR apple NEAR/2f juice");
+        assert_eq!(metrics.near_operators, 1);
+        assert_eq!(metrics.max_depth, 0);
+    }
+
+    #[test]
+    fn test_limits() {
+        let (tokens, _) = Lexer::new("apple OR juice OR cherry").tokenize_with_errors();
+        let query = Parser::new(tokens).parse().query;
+        let metrics = QueryMetrics::new(&query, "apple OR juice OR cherry");
+
+        let mut limits = Limits {
+            max_terms: Some(2),
+            max_characters: Some(100),
+            ..Limits::default()
+        };
+        let warnings = limits.check(&metrics, &query);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].to_string(),
+            "Query has 3 terms, above the limit of 2"
+        );
+
+        limits.merge(&Limits {
+            max_terms: Some(3),
+            ..Limits::default()
+        });
+        assert_eq!(limits.max_characters, Some(100));
+        assert!(limits.check(&metrics, &query).is_empty());
+    }
+}
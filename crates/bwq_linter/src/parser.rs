@@ -1,91 +1,213 @@
 use crate::ast::*;
-use crate::error::{LintError, LintResult, LintWarning, Span};
+use crate::error::{Edit, Fix, LintError, LintWarning, Position, Span};
 use crate::lexer::{Token, TokenType};
 
-/// result type with parsed query and any parser warnings
+/// result type with parsed query and any parser errors and warnings
 pub struct ParseResult {
     pub query: Query,
+    pub errors: Vec<LintError>,
     pub warnings: Vec<LintWarning>,
 }
 
 /// recursive descent parser for queries
+///
+/// the parser recovers from syntax errors: each error is recorded, the offending input is
+/// replaced by an `Expression::Error` node and parsing resumes at the next `)`, `AND`, `OR`
+/// or `NOT`, so a single pass reports every independent problem.
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
-    implicit_and_spans: Vec<Span>,
+    /// span of each implicit AND and the position its operator would go
+    implicit_ands: Vec<(Span, Position)>,
+    /// span of each field name written in a different case to the field's own, e.g. `authorverified`
+    miscased_fields: Vec<(Span, FieldType)>,
+    errors: Vec<LintError>,
+    comments: Vec<Comment>,
+}
+
+/// whether `expr` is a word that reads like a boolean operator, such as `and` or `Or`
+pub(crate) fn is_operator_word(expr: &Expression) -> bool {
+    matches!(
+        expr,
+        Expression::Term { term: Term::Word { value }, .. }
+            if value.eq_ignore_ascii_case("and") || value.eq_ignore_ascii_case("or")
+    )
+}
+
+/// the leftmost operand of a chain of boolean operators
+fn first_term(expr: &Expression) -> &Expression {
+    match expr {
+        Expression::BooleanOp {
+            left,
+            right: Some(_),
+            ..
+        } => first_term(left),
+        _ => expr,
+    }
+}
+
+/// the rightmost operand of a chain of boolean operators
+fn last_term(expr: &Expression) -> &Expression {
+    match expr {
+        Expression::BooleanOp {
+            right: Some(right), ..
+        } => last_term(right),
+        _ => expr,
+    }
 }
 
 impl Parser {
-    pub fn new(tokens: Vec<Token>) -> Result<Self, LintError> {
-        // filter out all comment-related tokens including content between comment markers
+    pub fn new(tokens: Vec<Token>) -> Self {
+        // pull comments out of the token stream so the grammar never has to deal with them
         let mut filtered_tokens: Vec<Token> = Vec::new();
-        let mut inside_comment = false;
+        let mut errors = Vec::new();
+        let mut comments = Vec::new();
+        let mut open_comment: Option<Comment> = None;
         let mut comment_start_span: Option<Span> = None;
 
         for token in tokens {
             match &token.token_type {
                 TokenType::CommentStart => {
-                    inside_comment = true;
                     comment_start_span = Some(token.span.clone());
+                    open_comment = Some(Comment {
+                        text: String::new(),
+                        span: token.span.clone(),
+                    });
+                }
+                TokenType::CommentText(text) => {
+                    if let Some(comment) = open_comment.as_mut() {
+                        comment.text = text.clone();
+                        comment.span.end = token.span.end.clone();
+                    }
                 }
                 TokenType::CommentEnd => {
-                    inside_comment = false;
-                    comment_start_span = None;
+                    if let Some(mut comment) = open_comment.take() {
+                        comment.span.end = token.span.end.clone();
+                        comments.push(comment);
+                    }
                 }
-                TokenType::Eof if inside_comment => {
-                    return Err(LintError::ValidationError {
-                        span: comment_start_span.unwrap(),
-                        message: "Please add a >>> mark to close this commented text.".to_string(),
+                TokenType::Eof if open_comment.is_some() => {
+                    let comment = open_comment.take().unwrap();
+                    let start = comment_start_span.take().unwrap();
+
+                    // the comment most likely ends with its first line, but it may have been
+                    // meant to run further, so the fix needs review
+                    let first_line = comment.text.split('\n').next().unwrap_or_default();
+                    let length = first_line.trim_end().chars().count();
+                    let close_at = Position::new(
+                        start.end.line,
+                        start.end.column + length,
+                        start.end.offset + length,
+                    );
+
+                    errors.push(LintError::UnclosedComment {
+                        span: start,
+                        fix: Some(Box::new(Fix::unsafe_edit(
+                            "Close the comment at the end of the line",
+                            vec![Edit::insertion(close_at, ">>>")],
+                        ))),
                     });
+                    comments.push(comment);
+                    filtered_tokens.push(token);
                 }
-                _ if inside_comment => {}
+                _ if open_comment.is_some() => {}
                 _ => {
                     filtered_tokens.push(token);
                 }
             }
         }
 
-        Ok(Self {
+        Self {
             tokens: filtered_tokens,
             current: 0,
-            implicit_and_spans: Vec::new(),
-        })
+            implicit_ands: Vec::new(),
+            miscased_fields: Vec::new(),
+            errors,
+            comments,
+        }
     }
 
     /// parse the tokens into a queryAST
-    pub fn parse(&mut self) -> LintResult<ParseResult> {
-        let expression = self.parse_expression()?;
-        let span = expression.span().clone();
+    pub fn parse(&mut self) -> ParseResult {
+        let mut expression = self.parse_expression();
 
-        // ensure we've consumed all tokens except EOF
-        if !self.is_at_end() && !matches!(self.peek().token_type, TokenType::Eof) {
-            return Err(LintError::UnexpectedToken {
-                span: self.peek().span.clone(),
-                token: self.peek().token_type.to_string(),
-            });
+        // anything left over is a stray token (e.g. an unmatched ')'); report it and keep
+        // parsing whatever follows so later problems are still found
+        while !self.is_at_end() {
+            let token = self.advance().clone();
+            // a sync point such as ')' has already been reported by the term that stopped at it
+            if self.errors.last().map(LintError::span) != Some(&token.span) {
+                self.errors.push(LintError::UnexpectedToken {
+                    span: token.span,
+                    token: token.token_type.to_string(),
+                });
+            }
+
+            if self.is_at_end() {
+                break;
+            }
+
+            let operator = if self.match_token(&TokenType::Or) {
+                BooleanOperator::Or
+            } else if self.match_token(&TokenType::Not) {
+                BooleanOperator::Not
+            } else {
+                self.match_token(&TokenType::And);
+                BooleanOperator::And
+            };
+            let right = self.parse_expression();
+
+            let span = Span::new(expression.span().start.clone(), right.span().end.clone());
+            expression = Expression::BooleanOp {
+                operator,
+                left: Box::new(expression),
+                right: Some(Box::new(right)),
+                span,
+            };
         }
 
+        let span = expression.span().clone();
+
         let mut warnings = Vec::new();
-        for span in &self.implicit_and_spans {
+        for (span, operator_position) in &self.implicit_ands {
             warnings.push(LintWarning::PotentialTypo {
                 span: span.clone(),
                 suggestion: "Consider using explicit 'AND' operator for clarity".to_string(),
+                fix: Some(Box::new(Fix::safe(
+                    "Insert explicit AND",
+                    vec![Edit::insertion(operator_position.clone(), "AND ")],
+                ))),
+            });
+        }
+        for (span, field) in &self.miscased_fields {
+            warnings.push(LintWarning::FieldNameCase {
+                span: span.clone(),
+                expected: field.as_str().to_string(),
+                fix: Some(Box::new(Fix::safe(
+                    format!("Replace with '{}'", field.as_str()),
+                    vec![Edit::replacement(span.clone(), field.as_str())],
+                ))),
             });
         }
 
-        Ok(ParseResult {
-            query: Query { expression, span },
+        ParseResult {
+            query: Query {
+                expression,
+                span,
+                comments: std::mem::take(&mut self.comments),
+            },
+            errors: std::mem::take(&mut self.errors),
             warnings,
-        })
+        }
     }
 
-    fn parse_expression(&mut self) -> LintResult<Expression> {
-        let mut left = self.parse_and_expression()?;
+    fn parse_expression(&mut self) -> Expression {
+        let mut left = self.parse_and_expression();
 
         while self.match_token(&TokenType::Or) {
             let operator = BooleanOperator::Or;
             let _operator_span = self.previous().span.clone();
-            let right = self.parse_and_expression()?;
+            let right = self.parse_and_expression();
 
             let span = Span::new(left.span().start.clone(), right.span().end.clone());
             left = Expression::BooleanOp {
@@ -96,17 +218,17 @@ impl Parser {
             };
         }
 
-        Ok(left)
+        left
     }
 
-    fn parse_and_expression(&mut self) -> LintResult<Expression> {
-        let mut left = self.parse_not_expression()?;
+    fn parse_and_expression(&mut self) -> Expression {
+        let mut left = self.parse_not_expression();
 
         loop {
             if self.match_token(&TokenType::And) {
                 let operator = BooleanOperator::And;
                 let _operator_span = self.previous().span.clone();
-                let right = self.parse_not_expression()?;
+                let right = self.parse_not_expression();
 
                 let span = Span::new(left.span().start.clone(), right.span().end.clone());
                 left = Expression::BooleanOp {
@@ -117,7 +239,12 @@ impl Parser {
                 };
             } else if self.is_implicit_and_candidate() {
                 // warn on implicit AND (space-separated terms)
-                let right = self.parse_not_expression()?;
+                let right = self.parse_not_expression();
+
+                // a lowercase `and`/`or` between terms is reported as E004 instead
+                let next_to_operator_word =
+                    is_operator_word(last_term(&left)) || is_operator_word(first_term(&right));
+                let operator_position = right.span().start.clone();
 
                 let span = Span::new(left.span().start.clone(), right.span().end.clone());
                 left = Expression::BooleanOp {
@@ -127,20 +254,22 @@ impl Parser {
                     span: span.clone(),
                 };
 
-                self.implicit_and_spans.push(span);
+                if !next_to_operator_word {
+                    self.implicit_ands.push((span, operator_position));
+                }
             } else {
                 break;
             }
         }
 
-        Ok(left)
+        left
     }
 
-    fn parse_not_expression(&mut self) -> LintResult<Expression> {
+    fn parse_not_expression(&mut self) -> Expression {
         let mut left = if self.match_token(&TokenType::Not) {
             // handle leading NOT operator
             let operator_span = self.previous().span.clone();
-            let right = self.parse_proximity_expression()?;
+            let right = self.parse_proximity_expression();
 
             let span = Span::new(operator_span.start.clone(), right.span().end.clone());
             Expression::BooleanOp {
@@ -150,13 +279,13 @@ impl Parser {
                 span,
             }
         } else {
-            self.parse_proximity_expression()?
+            self.parse_proximity_expression()
         };
 
         while self.match_token(&TokenType::Not) {
             let operator = BooleanOperator::Not;
             let _operator_span = self.previous().span.clone();
-            let right = self.parse_proximity_expression()?;
+            let right = self.parse_proximity_expression();
 
             let span = Span::new(left.span().start.clone(), right.span().end.clone());
             left = Expression::BooleanOp {
@@ -167,49 +296,43 @@ impl Parser {
             };
         }
 
-        Ok(left)
+        left
     }
 
-    fn parse_proximity_expression(&mut self) -> LintResult<Expression> {
-        let left = self.parse_primary()?;
+    fn parse_proximity_expression(&mut self) -> Expression {
+        let left = self.parse_primary();
 
         // handle proximity operators
         if self.match_token(&TokenType::Tilde) {
             let tilde_span = self.previous().span.clone();
-            let distance;
+            let mut distance = None;
+            let mut error_message = None;
 
             if left.span().end.offset != tilde_span.start.offset {
-                return Err(LintError::ValidationError {
-                    span: tilde_span,
-                    message: "The ~ operator must be immediately attached to the preceding term (e.g., apple~5, not apple ~5).".to_string(),
-                });
+                error_message = Some(
+                    "The ~ operator must be immediately attached to the preceding term (e.g., apple~5, not apple ~5).",
+                );
             }
 
-            // require distance number immediately after tilde (no spaces)
+            // require distance number immediately after tilde (no spaces); a detached number is
+            // still consumed so it isn't reported again as a stray term
             if let TokenType::Number(num_str) = &self.peek().token_type {
                 let number_token = self.peek();
                 if tilde_span.end.offset == number_token.span.start.offset {
                     distance = num_str.parse::<u32>().ok();
-                    self.advance();
                     if distance.is_none() {
-                        return Err(LintError::ValidationError {
-                            span: tilde_span,
-                            message:
-                                "Invalid proximity distance. Distance must be a positive number."
-                                    .to_string(),
-                        });
+                        error_message.get_or_insert(
+                            "Invalid proximity distance. Distance must be a positive number.",
+                        );
                     }
                 } else {
-                    return Err(LintError::ValidationError {
-                        span: tilde_span,
-                        message: "The ~ operator requires a distance number immediately after it (e.g., ~5 for proximity within 5 words).".to_string(),
-                    });
+                    error_message.get_or_insert("The ~ operator requires a distance number immediately after it (e.g., ~5 for proximity within 5 words).");
                 }
+                self.advance();
             } else {
-                return Err(LintError::ValidationError {
-                    span: tilde_span,
-                    message: "The ~ operator requires a distance number (e.g., ~5 for proximity within 5 words).".to_string(),
-                });
+                error_message.get_or_insert(
+                    "The ~ operator requires a distance number (e.g., ~5 for proximity within 5 words).",
+                );
             }
 
             // tilde is valid after quoted phrases, grouped expressions, or single terms
@@ -217,23 +340,27 @@ impl Parser {
                 matches!(&left, Expression::Term { .. } | Expression::Group { .. });
 
             if !is_valid_tilde_context {
-                return Err(LintError::ValidationError {
+                error_message.get_or_insert("The ~ operator should be used after a search term, quoted phrase, or grouped expression. If this should be part of a search term, it must be quoted (or escaped using the \\ character).");
+            }
+
+            if let Some(message) = error_message {
+                self.errors.push(LintError::InvalidTildeUsage {
                     span: tilde_span,
-                    message: "The ~ operator should be used after a search term, quoted phrase, or grouped expression. If this should be part of a search term, it must be quoted (or escaped using the \\ character).".to_string(),
+                    message: message.to_string(),
                 });
+                return left;
             }
 
+            // the distance number was consumed above, so the span ends with it
             let terms = vec![left];
-            let end_span = tilde_span.end.clone();
+            let end_span = self.previous().span.end.clone();
             let span = Span::new(terms[0].span().start.clone(), end_span);
 
-            return Ok(Expression::Proximity {
-                operator: ProximityOperator::Proximity {
-                    distance: Some(distance.unwrap()),
-                },
+            return Expression::Proximity {
+                operator: ProximityOperator::Proximity { distance },
                 terms,
                 span,
-            });
+            };
         }
 
         // handle NEAR/x and NEAR/xf
@@ -241,34 +368,34 @@ impl Parser {
             let distance = *distance;
             self.advance();
             let _operator_span = self.previous().span.clone();
-            let right = self.parse_primary()?;
+            let right = self.parse_primary();
 
             let span = Span::new(left.span().start.clone(), right.span().end.clone());
-            return Ok(Expression::Proximity {
+            return Expression::Proximity {
                 operator: ProximityOperator::Near { distance },
                 terms: vec![left, right],
                 span,
-            });
+            };
         }
 
         if let TokenType::NearForward(distance) = &self.peek().token_type {
             let distance = *distance;
             self.advance();
             let _operator_span = self.previous().span.clone();
-            let right = self.parse_primary()?;
+            let right = self.parse_primary();
 
             let span = Span::new(left.span().start.clone(), right.span().end.clone());
-            return Ok(Expression::Proximity {
+            return Expression::Proximity {
                 operator: ProximityOperator::NearForward { distance },
                 terms: vec![left, right],
                 span,
-            });
+            };
         }
 
-        Ok(left)
+        left
     }
 
-    fn parse_primary(&mut self) -> LintResult<Expression> {
+    fn parse_primary(&mut self) -> Expression {
         // Dispatch to specialized parsing methods based on token type
         match &self.peek().token_type {
             TokenType::LeftParen => self.parse_grouped_expression(),
@@ -288,54 +415,81 @@ impl Parser {
         }
     }
 
-    fn parse_grouped_expression(&mut self) -> LintResult<Expression> {
+    fn parse_grouped_expression(&mut self) -> Expression {
         let start_span = self.advance().span.clone(); // consume '('
-        let expr = self.parse_expression()?;
+        let expr = self.parse_expression();
 
         if !self.match_token(&TokenType::RightParen) {
-            return Err(LintError::ExpectedToken {
-                span: self.peek().span.clone(),
-                expected: ")".to_string(),
-                found: self.peek().token_type.to_string(),
-            });
+            // close the group where it stopped so its contents are still validated. groups
+            // left open at the same place share the innermost group's error
+            let span = self.peek().span.clone();
+            let reported = matches!(
+                self.errors.last(),
+                Some(LintError::ExpectedToken { span: last, expected, .. })
+                    if *last == span && expected == ")"
+            );
+            if !reported {
+                self.errors.push(LintError::ExpectedToken {
+                    span,
+                    expected: ")".to_string(),
+                    found: self.peek().token_type.to_string(),
+                });
+            }
         }
 
         let end_span = self.previous().span.clone();
         let span = Span::new(start_span.start, end_span.end);
 
-        Ok(Expression::Group {
+        Expression::Group {
             expression: Box::new(expr),
             span,
-        })
+        }
     }
 
-    fn parse_case_sensitive_term(&mut self) -> LintResult<Expression> {
+    fn parse_case_sensitive_term(&mut self) -> Expression {
         let start_span = self.advance().span.clone(); // consume '{'
 
         let mut content_tokens = Vec::new();
         let mut found_closing_brace = false;
+        // nested braces are kept in the value for validation to report
+        let mut depth = 0;
 
         while !self.is_at_end() {
-            if matches!(self.peek().token_type, TokenType::RightBrace) {
-                found_closing_brace = true;
-                break;
+            match self.peek().token_type {
+                TokenType::RightBrace if depth == 0 => {
+                    found_closing_brace = true;
+                    break;
+                }
+                TokenType::LeftBrace => depth += 1,
+                TokenType::RightBrace => depth -= 1,
+                _ => {}
             }
             content_tokens.push(self.advance().clone());
         }
 
         if !found_closing_brace {
-            return Err(LintError::ExpectedToken {
+            self.errors.push(LintError::ExpectedToken {
                 span: self.peek().span.clone(),
                 expected: "}".to_string(),
                 found: self.peek().token_type.to_string(),
             });
+            let end_span = self.previous().span.clone();
+            return Expression::Error {
+                span: Span::new(start_span.start, end_span.end),
+            };
         }
 
         let end_span = self.advance().span.clone();
         let span = Span::new(start_span.start, end_span.end);
 
         let mut value = String::new();
+        let mut previous_end = start_span.end.offset;
         for token in content_tokens.iter() {
+            // whitespace isn't tokenized, so it's put back from the gaps between tokens
+            if token.span.start.offset > previous_end && !value.is_empty() {
+                value.push(' ');
+            }
+            previous_end = token.span.end.offset;
             match &token.token_type {
                 TokenType::Word(word) => {
                     value.push_str(word);
@@ -361,18 +515,15 @@ impl Parser {
             value = " ".to_string();
         }
 
-        Ok(Expression::Term {
+        Expression::Term {
             term: Term::CaseSensitive { value },
             span,
-        })
+        }
     }
 
-    fn parse_field_operation(&mut self) -> LintResult<Expression> {
+    fn parse_field_operation(&mut self) -> Expression {
         let TokenType::Word(word) = &self.peek().token_type else {
-            return Err(LintError::UnexpectedToken {
-                span: self.peek().span.clone(),
-                token: self.peek().token_type.to_string(),
-            });
+            return self.unexpected_token();
         };
 
         let word = word.clone();
@@ -381,7 +532,7 @@ impl Parser {
         self.advance(); // consume field name
         self.advance(); // consume colon
 
-        let value = Box::new(self.parse_primary()?);
+        let value = Box::new(self.parse_primary());
 
         // Handle special case where field value is a range
         let value = if let Expression::Range {
@@ -405,16 +556,19 @@ impl Parser {
             value
         };
 
-        let span = Span::new(word_span.start, value.span().end.clone());
+        let span = Span::new(word_span.start.clone(), value.span().end.clone());
 
         if let Some(field_type) = FieldType::parse(&word) {
-            Ok(Expression::Field {
+            if word != field_type.as_str() {
+                self.miscased_fields.push((word_span, field_type.clone()));
+            }
+            Expression::Field {
                 field: field_type,
                 value,
                 span,
-            })
+            }
         } else {
-            Ok(Expression::Term {
+            Expression::Term {
                 term: Term::Word {
                     value: format!(
                         "{}:{}",
@@ -433,11 +587,11 @@ impl Parser {
                     ),
                 },
                 span,
-            })
+            }
         }
     }
 
-    fn parse_range(&mut self) -> LintResult<Expression> {
+    fn parse_range(&mut self) -> Expression {
         let start_span = self.previous().span.clone();
 
         let start_value = match &self.peek().token_type {
@@ -446,21 +600,11 @@ impl Parser {
                 self.advance();
                 val
             }
-            _ => {
-                return Err(LintError::ExpectedToken {
-                    span: self.peek().span.clone(),
-                    expected: "number or word".to_string(),
-                    found: self.peek().token_type.to_string(),
-                });
-            }
+            _ => return self.recover_range(start_span, "number or word"),
         };
 
         if !self.match_token(&TokenType::To) {
-            return Err(LintError::ExpectedToken {
-                span: self.peek().span.clone(),
-                expected: "TO".to_string(),
-                found: self.peek().token_type.to_string(),
-            });
+            return self.recover_range(start_span, "TO");
         }
 
         let end_value = match &self.peek().token_type {
@@ -469,37 +613,47 @@ impl Parser {
                 self.advance();
                 val
             }
-            _ => {
-                return Err(LintError::ExpectedToken {
-                    span: self.peek().span.clone(),
-                    expected: "number or word".to_string(),
-                    found: self.peek().token_type.to_string(),
-                });
-            }
+            _ => return self.recover_range(start_span, "number or word"),
         };
 
         if !self.match_token(&TokenType::RightBracket) {
-            return Err(LintError::ExpectedToken {
-                span: self.peek().span.clone(),
-                expected: "]".to_string(),
-                found: self.peek().token_type.to_string(),
-            });
+            return self.recover_range(start_span, "]");
         }
 
         let end_span = self.previous().span.clone();
         let span = Span::new(start_span.start, end_span.end);
 
-        Ok(Expression::Range {
+        Expression::Range {
             field: None,
             start: start_value,
             end: end_value,
             span,
-        })
+        }
+    }
+
+    /// report a malformed range and skip to its closing ']' (or the next sync point)
+    fn recover_range(&mut self, start_span: Span, expected: &str) -> Expression {
+        self.errors.push(LintError::ExpectedToken {
+            span: self.peek().span.clone(),
+            expected: expected.to_string(),
+            found: self.peek().token_type.to_string(),
+        });
+
+        while !self.is_sync_point() {
+            if matches!(self.advance().token_type, TokenType::RightBracket) {
+                break;
+            }
+        }
+
+        let end_span = self.previous().span.clone();
+        Expression::Error {
+            span: Span::new(start_span.start, end_span.end),
+        }
     }
 
     // parse_comment function removed - comments are now filtered out during parser construction
 
-    fn parse_term(&mut self) -> LintResult<Expression> {
+    fn parse_term(&mut self) -> Expression {
         let token = self.peek().clone();
 
         match &token.token_type {
@@ -519,52 +673,73 @@ impl Parser {
                     }
                 };
 
-                Ok(Expression::Term {
+                Expression::Term {
                     term,
                     span: token.span,
-                })
+                }
             }
             TokenType::QuotedString(string) => {
                 self.advance();
-                Ok(Expression::Term {
+                Expression::Term {
                     term: Term::Phrase {
                         value: string.clone(),
                     },
                     span: token.span,
-                })
+                }
             }
             TokenType::Number(number) => {
                 self.advance();
-                Ok(Expression::Term {
+                Expression::Term {
                     term: Term::Word {
                         value: number.clone(),
                     },
                     span: token.span,
-                })
+                }
             }
             TokenType::Hashtag(hashtag) => {
                 self.advance();
-                Ok(Expression::Term {
+                Expression::Term {
                     term: Term::Hashtag {
                         value: hashtag.clone(),
                     },
                     span: token.span,
-                })
+                }
             }
             TokenType::Mention(mention) => {
                 self.advance();
-                Ok(Expression::Term {
+                Expression::Term {
                     term: Term::Mention {
                         value: mention.clone(),
                     },
                     span: token.span,
-                })
+                }
             }
-            _ => Err(LintError::UnexpectedToken {
-                span: token.span,
-                token: token.token_type.to_string(),
-            }),
+            _ => self.unexpected_token(),
+        }
+    }
+
+    /// report the current token as unexpected and stand an error node in for it; the token is
+    /// skipped unless it is a point the enclosing expression can resume from
+    fn unexpected_token(&mut self) -> Expression {
+        let token = self.peek().clone();
+        self.errors.push(LintError::UnexpectedToken {
+            span: token.span.clone(),
+            token: token.token_type.to_string(),
+        });
+
+        if !self.is_sync_point() {
+            self.advance();
         }
+
+        Expression::Error { span: token.span }
+    }
+
+    fn is_sync_point(&self) -> bool {
+        self.is_at_end()
+            || matches!(
+                self.peek().token_type,
+                TokenType::RightParen | TokenType::Or | TokenType::And | TokenType::Not
+            )
     }
 
     fn match_token(&mut self, token_type: &TokenType) -> bool {
@@ -650,6 +825,21 @@ impl Expression {
             Expression::Field { span, .. } => span,
             Expression::Range { span, .. } => span,
             Expression::Term { span, .. } => span,
+            Expression::Error { span } => span,
+        }
+    }
+
+    /// whether the parser had to recover from a syntax error anywhere in this expression
+    pub fn contains_error(&self) -> bool {
+        match self {
+            Expression::BooleanOp { left, right, .. } => {
+                left.contains_error() || right.as_ref().is_some_and(|right| right.contains_error())
+            }
+            Expression::Group { expression, .. } => expression.contains_error(),
+            Expression::Proximity { terms, .. } => terms.iter().any(Expression::contains_error),
+            Expression::Field { value, .. } => value.contains_error(),
+            Expression::Range { .. } | Expression::Term { .. } => false,
+            Expression::Error { .. } => true,
         }
     }
 }
@@ -663,8 +853,8 @@ mod tests {
     fn test_basic_parsing() {
         let mut lexer = Lexer::new("apple AND juice");
         let tokens = lexer.tokenize().unwrap();
-        let mut parser = Parser::new(tokens).unwrap();
-        let result = parser.parse().unwrap();
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse();
 
         match result.query.expression {
             Expression::BooleanOp { operator, .. } => {
@@ -678,8 +868,8 @@ mod tests {
     fn test_quoted_phrase() {
         let mut lexer = Lexer::new("\"apple juice\"");
         let tokens = lexer.tokenize().unwrap();
-        let mut parser = Parser::new(tokens).unwrap();
-        let result = parser.parse().unwrap();
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse();
 
         match result.query.expression {
             Expression::Term {
@@ -696,8 +886,8 @@ mod tests {
     fn test_field_operation() {
         let mut lexer = Lexer::new("title:\"apple juice\"");
         let tokens = lexer.tokenize().unwrap();
-        let mut parser = Parser::new(tokens).unwrap();
-        let result = parser.parse().unwrap();
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse();
 
         match result.query.expression {
             Expression::Field { field, .. } => {
@@ -711,8 +901,8 @@ mod tests {
     fn test_implicit_and() {
         let mut lexer = Lexer::new("apple banana");
         let tokens = lexer.tokenize().unwrap();
-        let mut parser = Parser::new(tokens).unwrap();
-        let result = parser.parse().unwrap();
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse();
 
         match result.query.expression {
             Expression::BooleanOp { operator, .. } => {
@@ -729,8 +919,8 @@ mod tests {
         // valid field operations are parsed as fields
         let mut lexer = Lexer::new("url:example.com");
         let tokens = lexer.tokenize().unwrap();
-        let mut parser = Parser::new(tokens).unwrap();
-        let result = parser.parse().unwrap();
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse();
 
         match result.query.expression {
             Expression::Field { field, .. } => {
@@ -742,8 +932,8 @@ mod tests {
         // non-field colons get combined into a single term
         let mut lexer = Lexer::new("test:test");
         let tokens = lexer.tokenize().unwrap();
-        let mut parser = Parser::new(tokens).unwrap();
-        let result = parser.parse().unwrap();
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse();
 
         match result.query.expression {
             Expression::Term {
@@ -758,8 +948,8 @@ mod tests {
         // quoted value after non-field colon
         let mut lexer = Lexer::new("protocol:\"https\"");
         let tokens = lexer.tokenize().unwrap();
-        let mut parser = Parser::new(tokens).unwrap();
-        let result = parser.parse().unwrap();
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse();
 
         match result.query.expression {
             Expression::Term {
@@ -771,4 +961,74 @@ mod tests {
             _ => panic!("Expected Term with combined colon and quoted value"),
         }
     }
+
+    #[test]
+    fn test_error_recovery() {
+        let mut lexer = Lexer::new("(apple AND ) OR (juice NOT [1 TO ]) OR rating:5");
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse();
+
+        let codes: Vec<_> = result.errors.iter().map(|e| e.code()).collect();
+        assert_eq!(codes, vec!["E010", "E011"]);
+
+        // the rest of the query is still parsed around the error nodes
+        let Expression::BooleanOp { left, right, .. } = result.query.expression else {
+            panic!("Expected BooleanOp");
+        };
+        assert!(matches!(
+            right.as_deref(),
+            Some(Expression::Field {
+                field: FieldType::Rating,
+                ..
+            })
+        ));
+        let Expression::BooleanOp { left, .. } = *left else {
+            panic!("Expected BooleanOp");
+        };
+        let Expression::Group { expression, .. } = *left else {
+            panic!("Expected Group");
+        };
+        assert!(matches!(
+            *expression,
+            Expression::BooleanOp { right: Some(ref r), .. } if matches!(**r, Expression::Error { .. })
+        ));
+    }
+
+    #[test]
+    fn test_case_sensitive_values() {
+        let value = |query: &str| {
+            let tokens = Lexer::new(query).tokenize().unwrap();
+            let result = Parser::new(tokens).parse();
+            assert!(result.errors.is_empty(), "{query}");
+            match result.query.expression {
+                Expression::Term {
+                    term: Term::CaseSensitive { value },
+                    ..
+                } => value,
+                other => panic!("Expected a case-sensitive term, got {other:?}"),
+            }
+        };
+
+        assert_eq!(value("{BBC  News}"), "BBC News");
+        assert_eq!(value("{BBC-News}"), "BBC-News");
+        assert_eq!(value("{ {BBC} }"), "{BBC}");
+    }
+
+    #[test]
+    fn test_stray_closing_paren() {
+        let mut lexer = Lexer::new("apple) OR juice");
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse();
+
+        assert_eq!(result.errors.len(), 1);
+        assert!(matches!(
+            result.query.expression,
+            Expression::BooleanOp {
+                operator: BooleanOperator::Or,
+                ..
+            }
+        ));
+    }
 }
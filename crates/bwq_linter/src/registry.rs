@@ -0,0 +1,719 @@
+use crate::config::Severity;
+
+/// the kind of problem a rule looks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Syntax,
+    Field,
+    Operator,
+    Performance,
+}
+
+impl Category {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Category::Syntax => "syntax",
+            Category::Field => "field",
+            Category::Operator => "operator",
+            Category::Performance => "performance",
+        }
+    }
+}
+
+/// everything known about one diagnostic code
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RuleInfo {
+    pub code: &'static str,
+    /// kebab-case name, usable anywhere a code is in `bwq.toml`
+    pub name: &'static str,
+    pub category: Category,
+    pub severity: Severity,
+    pub summary: &'static str,
+    /// what causes the diagnostic and how to resolve it
+    pub explanation: &'static str,
+    /// a query that raises the diagnostic, empty when there's no meaningful example
+    pub bad: &'static str,
+    /// the same query written so it doesn't
+    pub good: &'static str,
+}
+
+impl RuleInfo {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "code": self.code,
+            "name": self.name,
+            "category": self.category.as_str(),
+            "severity": self.severity.as_str(),
+            "summary": self.summary,
+            "explanation": self.explanation,
+            "bad": self.bad,
+            "good": self.good,
+        })
+    }
+}
+
+/// every diagnostic code, in order. codes are never reused, so a code that is no longer
+/// raised keeps its entry
+pub const RULES: &[RuleInfo] = &[
+    RuleInfo {
+        code: "E001",
+        name: "invalid-character",
+        category: Category::Syntax,
+        severity: Severity::Error,
+        summary: "Invalid character",
+        explanation: "The query contains a character that isn't part of the query syntax. Quote the term if the character should be searched for.",
+        bad: "apple AND juice!",
+        good: "apple AND \"juice!\"",
+    },
+    RuleInfo {
+        code: "E002",
+        name: "syntax-error",
+        category: Category::Syntax,
+        severity: Severity::Error,
+        summary: "Syntax error",
+        explanation: "The query can't be parsed.",
+        bad: "",
+        good: "",
+    },
+    RuleInfo {
+        code: "E003",
+        name: "unclosed-comment",
+        category: Category::Syntax,
+        severity: Severity::Error,
+        summary: "Unclosed comment",
+        explanation: "Every `<<<` comment must be closed with `>>>`, otherwise the rest of the query is treated as a comment.",
+        bad: "apple <<<check this",
+        good: "apple <<<check this>>>",
+    },
+    RuleInfo {
+        code: "E004",
+        name: "boolean-case",
+        category: Category::Operator,
+        severity: Severity::Error,
+        summary: "Lowercase boolean operator",
+        explanation: "Boolean operators must be written in capitals. A lowercase `and`/`or` is searched for as a word.",
+        bad: "apple and juice",
+        good: "apple AND juice",
+    },
+    RuleInfo {
+        code: "E005",
+        name: "unbalanced-parentheses",
+        category: Category::Syntax,
+        severity: Severity::Error,
+        summary: "Unbalanced parentheses",
+        explanation: "Every `(` must have a matching `)`.",
+        bad: "(apple OR juice",
+        good: "(apple OR juice)",
+    },
+    RuleInfo {
+        code: "E006",
+        name: "leading-wildcard",
+        category: Category::Performance,
+        severity: Severity::Error,
+        summary: "Leading wildcard",
+        explanation: "Wildcards can only be used within or at the end of a word, not at the beginning.",
+        bad: "*apple",
+        good: "apple*",
+    },
+    RuleInfo {
+        code: "E007",
+        name: "invalid-proximity-operator",
+        category: Category::Operator,
+        severity: Severity::Error,
+        summary: "Invalid proximity operator",
+        explanation: "Proximity operators are written `NEAR/x`, `NEAR/xf` or `~x`, where x is a number of words.",
+        bad: "apple NEAR/x juice",
+        good: "apple NEAR/3 juice",
+    },
+    RuleInfo {
+        code: "E008",
+        name: "invalid-field-operator",
+        category: Category::Field,
+        severity: Severity::Error,
+        summary: "Invalid field operator",
+        explanation: "Field operators are written `field:value`.",
+        bad: "title:",
+        good: "title:apple",
+    },
+    RuleInfo {
+        code: "E009",
+        name: "invalid-range-syntax",
+        category: Category::Field,
+        severity: Severity::Error,
+        summary: "Invalid range syntax",
+        explanation: "Ranges are written `[start TO end]`.",
+        bad: "rating:[1 5]",
+        good: "rating:[1 TO 5]",
+    },
+    RuleInfo {
+        code: "E010",
+        name: "unexpected-token",
+        category: Category::Syntax,
+        severity: Severity::Error,
+        summary: "Unexpected token",
+        explanation: "The query contains a token where none was expected, such as a closing bracket without an opening one.",
+        bad: "apple)",
+        good: "(apple)",
+    },
+    RuleInfo {
+        code: "E011",
+        name: "expected-token",
+        category: Category::Syntax,
+        severity: Severity::Error,
+        summary: "Missing token",
+        explanation: "The query is missing a token, such as a closing bracket.",
+        bad: "(apple OR juice",
+        good: "(apple OR juice)",
+    },
+    RuleInfo {
+        code: "E012",
+        name: "invalid-rating",
+        category: Category::Field,
+        severity: Severity::Error,
+        summary: "Rating out of range",
+        explanation: "`rating:` takes whole numbers from 0 to 5, on its own or as a range.",
+        bad: "rating:[3 TO 6]",
+        good: "rating:[3 TO 5]",
+    },
+    RuleInfo {
+        code: "E013",
+        name: "ambiguous-proximity",
+        category: Category::Operator,
+        severity: Severity::Error,
+        summary: "Ambiguous proximity operator",
+        explanation: "Proximity operators can't be combined with AND, or with OR and other NEAR operators, without parentheses.",
+        bad: "apple NEAR/3 juice AND cherry",
+        good: "(apple NEAR/3 juice) AND cherry",
+    },
+    RuleInfo {
+        code: "E014",
+        name: "invalid-range",
+        category: Category::Field,
+        severity: Severity::Error,
+        summary: "Invalid range",
+        explanation: "The range bounds are invalid, e.g. the start is greater than the end.",
+        bad: "authorFollowers:[1000 TO 100]",
+        good: "authorFollowers:[100 TO 1000]",
+    },
+    RuleInfo {
+        code: "E015",
+        name: "mixed-and-or",
+        category: Category::Operator,
+        severity: Severity::Error,
+        summary: "Mixed AND and OR",
+        explanation: "AND and OR can't be mixed in the same sub-query. Use parentheses to disambiguate.",
+        bad: "vanilla AND icecream OR cake",
+        good: "vanilla AND (icecream OR cake)",
+    },
+    RuleInfo {
+        code: "E016",
+        name: "pure-negative",
+        category: Category::Operator,
+        severity: Severity::Error,
+        summary: "Pure negative query",
+        explanation: "A query must include at least one positive term; it can't only exclude terms.",
+        bad: "NOT apple",
+        good: "juice NOT apple",
+    },
+    RuleInfo {
+        code: "E017",
+        name: "field-operator-spacing",
+        category: Category::Field,
+        severity: Severity::Error,
+        summary: "Space before field operator",
+        explanation: "Field operators can't have a space between the field name and the colon. Quote the colon if it should be searched for.",
+        bad: "title :apple",
+        good: "title:apple",
+    },
+    RuleInfo {
+        code: "E018",
+        name: "missing-operand",
+        category: Category::Operator,
+        severity: Severity::Error,
+        summary: "Missing operand",
+        explanation: "AND, OR and NEAR need a term on both sides.",
+        bad: "apple AND",
+        good: "apple AND juice",
+    },
+    RuleInfo {
+        code: "E019",
+        name: "invalid-tilde",
+        category: Category::Operator,
+        severity: Severity::Error,
+        summary: "Invalid tilde usage",
+        explanation: "`~` must directly follow a term, phrase or group and be directly followed by a distance.",
+        bad: "\"apple juice\"~",
+        good: "\"apple juice\"~5",
+    },
+    RuleInfo {
+        code: "E020",
+        name: "broad-wildcard",
+        category: Category::Performance,
+        severity: Severity::Error,
+        summary: "Single-letter wildcard",
+        explanation: "A wildcard after a single letter matches too many words to be searched. Add more letters before the `*`.",
+        bad: "a*",
+        good: "app*",
+    },
+    RuleInfo {
+        code: "E021",
+        name: "empty-term",
+        category: Category::Syntax,
+        severity: Severity::Error,
+        summary: "Empty term",
+        explanation: "Phrases, hashtags and mentions must contain something to search for.",
+        bad: "apple AND \"\"",
+        good: "apple AND \"apple juice\"",
+    },
+    RuleInfo {
+        code: "E022",
+        name: "unterminated-quote",
+        category: Category::Syntax,
+        severity: Severity::Error,
+        summary: "Unterminated quote",
+        explanation: "Every `\"` must have a closing `\"`, otherwise the rest of the query is treated as part of the phrase.",
+        bad: "\"apple juice",
+        good: "\"apple juice\"",
+    },
+    RuleInfo {
+        code: "E023",
+        name: "invalid-case-sensitive",
+        category: Category::Operator,
+        severity: Severity::Error,
+        summary: "Invalid case-sensitive term",
+        explanation: "Case-sensitive matching with `{}` works on plain words of up to 20 characters. Braces can't hold wildcards or operators, or be nested; put each term in its own braces instead.",
+        bad: "{BBC OR ITV}",
+        good: "{BBC} OR {ITV}",
+    },
+    RuleInfo {
+        code: "E024",
+        name: "invalid-coordinate",
+        category: Category::Field,
+        severity: Severity::Error,
+        summary: "Coordinate out of range",
+        explanation: "`latitude:` values must be between -90 and 90, and `longitude:` values between -180 and 180.",
+        bad: "latitude:[40 TO 100]",
+        good: "latitude:[40 TO 50]",
+    },
+    RuleInfo {
+        code: "E025",
+        name: "invalid-boolean-value",
+        category: Category::Field,
+        severity: Severity::Error,
+        summary: "Invalid boolean field value",
+        explanation: "Fields such as `authorVerified:`, `redditSpoiler:` and `sensitiveContent:` only take a lowercase `true` or `false`.",
+        bad: "authorVerified:yes",
+        good: "authorVerified:true",
+    },
+    RuleInfo {
+        code: "E026",
+        name: "invalid-verified-type",
+        category: Category::Field,
+        severity: Severity::Error,
+        summary: "Invalid verified type",
+        explanation: "`authorVerifiedType:` takes `blue`, `business` or `government`.",
+        bad: "authorVerifiedType:gold",
+        good: "authorVerifiedType:business",
+    },
+    RuleInfo {
+        code: "E027",
+        name: "invalid-minute-of-day",
+        category: Category::Field,
+        severity: Severity::Error,
+        summary: "Minute of day out of range",
+        explanation: "`minuteOfDay:` ranges count minutes from midnight, from 0 to 1439.",
+        bad: "minuteOfDay:[0 TO 1440]",
+        good: "minuteOfDay:[0 TO 1439]",
+    },
+    RuleInfo {
+        code: "E028",
+        name: "invalid-domain",
+        category: Category::Field,
+        severity: Severity::Error,
+        summary: "Invalid domain",
+        explanation: "`site:`, `url:`, `links:` and `topLevelDomain:` take bare hostnames: no scheme, no port or stray dots and, for `site:`, no path. Use `url:` to match a path, and `site:` for a full domain rather than `topLevelDomain:`.",
+        bad: "site:https://bbc.co.uk/",
+        good: "site:bbc.co.uk",
+    },
+    RuleInfo {
+        code: "E029",
+        name: "invalid-hostname",
+        category: Category::Field,
+        severity: Severity::Error,
+        summary: "Invalid hostname",
+        explanation: "Each dot-separated part of a hostname uses only letters, digits and hyphens, and doesn't start or end with a hyphen, so a value such as `bbc_news.com` can never match.",
+        bad: "site:bbc_news.com",
+        good: "site:bbcnews.com",
+    },
+    RuleInfo {
+        code: "E030",
+        name: "incomplete-domain",
+        category: Category::Field,
+        severity: Severity::Error,
+        summary: "Domain without a dot",
+        explanation: "`site:` takes a full domain including its ending, such as `bbc.com`. A bare name such as `bbc` matches no site.",
+        bad: "site:bbc",
+        good: "site:bbc.com",
+    },
+    RuleInfo {
+        code: "E031",
+        name: "site-www-prefix",
+        category: Category::Field,
+        severity: Severity::Error,
+        summary: "Site domain with www",
+        explanation: "`site:` matches a domain and all of its subdomains, so `www.` only narrows it to one of them. Leave it out to match the whole site.",
+        bad: "site:www.bbc.co.uk",
+        good: "site:bbc.co.uk",
+    },
+    RuleInfo {
+        code: "W001",
+        name: "implicit-and",
+        category: Category::Operator,
+        severity: Severity::Warning,
+        summary: "Implicit AND",
+        explanation: "Terms separated only by whitespace are joined with AND. Write the AND out to make the intent clear.",
+        bad: "apple juice",
+        good: "apple AND juice",
+    },
+    RuleInfo {
+        code: "W002",
+        name: "deprecated-operator",
+        category: Category::Operator,
+        severity: Severity::Warning,
+        summary: "Deprecated operator",
+        explanation: "The operator is deprecated.",
+        bad: "",
+        good: "",
+    },
+    RuleInfo {
+        code: "W003",
+        name: "performance",
+        category: Category::Performance,
+        severity: Severity::Warning,
+        summary: "Performance",
+        explanation: "The query may be slow to run, e.g. a term with many replacement characters.",
+        bad: "c????r",
+        good: "customi?e",
+    },
+    RuleInfo {
+        code: "W004",
+        name: "invalid-suppression",
+        category: Category::Syntax,
+        severity: Severity::Warning,
+        summary: "Invalid suppression",
+        explanation: "A `<<<bwq: ignore>>>` directive is malformed or no longer suppresses anything.",
+        bad: "apple AND juice <<<bwq: ignore[E015]>>>",
+        good: "apple AND juice OR cherry <<<bwq: ignore[E015]>>>",
+    },
+    RuleInfo {
+        code: "W005",
+        name: "single-term-proximity",
+        category: Category::Operator,
+        severity: Severity::Warning,
+        summary: "Single-term proximity",
+        explanation: "`~` after a single term does fuzzy matching, not proximity. Use a quoted phrase to search for words near each other.",
+        bad: "apple~5",
+        good: "\"apple juice\"~5",
+    },
+    RuleInfo {
+        code: "W006",
+        name: "invalid-language",
+        category: Category::Field,
+        severity: Severity::Warning,
+        summary: "Invalid language code",
+        explanation: "`language:` takes lowercase two-letter ISO 639-1 codes, such as `en` or `es`, rather than names or unassigned codes.",
+        bad: "language:english",
+        good: "language:en",
+    },
+    RuleInfo {
+        code: "W007",
+        name: "field-name-case",
+        category: Category::Field,
+        severity: Severity::Warning,
+        summary: "Field name case",
+        explanation: "Field names are case-sensitive, so a field written in the wrong case may not be applied.",
+        bad: "authorverified:true",
+        good: "authorVerified:true",
+    },
+    RuleInfo {
+        code: "W008",
+        name: "query-limit",
+        category: Category::Performance,
+        severity: Severity::Warning,
+        summary: "Query limit exceeded",
+        explanation: "The query is larger or more complex than a limit set in the `[limits]` table of `bwq.toml`, e.g. `max-characters` or `max-near-operators`. No limits are set by default; `bwq stats` shows a query's figures.",
+        bad: "",
+        good: "",
+    },
+    RuleInfo {
+        code: "W009",
+        name: "short-wildcard-budget",
+        category: Category::Performance,
+        severity: Severity::Warning,
+        summary: "Short wildcard budget",
+        explanation: "Brandwatch allows at most 150 short wildcards, those with a two-character stem like `ab*`, in a query. This warns once a query has 120 of them, counted across the whole query; lengthen the stems or list the words they should match.",
+        bad: "",
+        good: "",
+    },
+    RuleInfo {
+        code: "W010",
+        name: "unsupported-wildcard",
+        category: Category::Performance,
+        severity: Severity::Warning,
+        summary: "Unsupported wildcard",
+        explanation: "Wildcards only work in plain words. Inside a quoted phrase or attached to a hashtag or mention, `*` isn't expanded. Use a proximity operator to combine a wildcard with other words.",
+        bad: "\"apple juic*\"",
+        good: "apple NEAR/1f juic*",
+    },
+    RuleInfo {
+        code: "W011",
+        name: "ineffective-case-sensitive",
+        category: Category::Operator,
+        severity: Severity::Warning,
+        summary: "Ineffective case-sensitive term",
+        explanation: "`{}` only changes what is matched when the braces contain uppercase letters and aren't inside a quoted phrase.",
+        bad: "{apple}",
+        good: "{Apple}",
+    },
+    RuleInfo {
+        code: "W012",
+        name: "invalid-location",
+        category: Category::Field,
+        severity: Severity::Warning,
+        summary: "Invalid location code",
+        explanation: "`continent:`, `country:`, `region:` and `city:` take lowercase codes from Brandwatch's location list: ISO 3166 alpha-3 countries, then the region and city separated by `.`. Codes containing spaces must be quoted.",
+        bad: "country:uk",
+        good: "country:gbr",
+    },
+    RuleInfo {
+        code: "W013",
+        name: "unexpected-author-gender",
+        category: Category::Field,
+        severity: Severity::Warning,
+        summary: "Unexpected author gender",
+        explanation: "Brandwatch classifies authors as `F`, `M`, `X` or `U`, so other `authorGender:` values don't match anything.",
+        bad: "authorGender:female",
+        good: "authorGender:F",
+    },
+    RuleInfo {
+        code: "W014",
+        name: "unexpected-engagement-type",
+        category: Category::Field,
+        severity: Severity::Warning,
+        summary: "Unexpected engagement type",
+        explanation: "The value isn't one of the common `engagementType:` values, which may be a typo.",
+        bad: "engagementType:FAVORITE",
+        good: "engagementType:COMMENT",
+    },
+];
+
+/// a check in the validation engine, selectable by name like a code in `bwq.toml`, and the
+/// codes it raises
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Check {
+    pub name: &'static str,
+    pub codes: &'static [&'static str],
+}
+
+/// every check, by the name it is registered under
+pub const CHECKS: &[Check] = &[
+    Check {
+        name: "rating-field",
+        codes: &["E012"],
+    },
+    Check {
+        name: "coordinate-field",
+        codes: &["E024"],
+    },
+    Check {
+        name: "language-field",
+        codes: &["W006"],
+    },
+    Check {
+        name: "location-field",
+        codes: &["W012"],
+    },
+    Check {
+        name: "author-gender-field",
+        codes: &["W013"],
+    },
+    Check {
+        name: "boolean-field",
+        codes: &["E025"],
+    },
+    Check {
+        name: "engagement-type-field",
+        codes: &["W014"],
+    },
+    Check {
+        name: "verified-type-field",
+        codes: &["E026"],
+    },
+    Check {
+        name: "minute-of-day-field",
+        codes: &["E027"],
+    },
+    Check {
+        name: "range-field",
+        codes: &["E014"],
+    },
+    Check {
+        name: "site-field",
+        codes: &["E028", "E029", "E030", "E031"],
+    },
+    Check {
+        name: "mixed-and-or",
+        codes: &["E015"],
+    },
+    Check {
+        name: "boolean-case",
+        codes: &["E004"],
+    },
+    Check {
+        name: "mixed-near",
+        codes: &["E013"],
+    },
+    Check {
+        name: "pure-negative",
+        codes: &["E016"],
+    },
+    Check {
+        name: "binary-operator",
+        codes: &["E018"],
+    },
+    Check {
+        name: "tilde-usage",
+        codes: &["W005"],
+    },
+    Check {
+        name: "case-sensitive",
+        codes: &["E021", "E023", "W011"],
+    },
+    Check {
+        name: "wildcard-performance",
+        codes: &["E006", "E020", "W003"],
+    },
+    Check {
+        name: "short-term",
+        codes: &["E021", "W003"],
+    },
+    Check {
+        name: "short-wildcard",
+        codes: &["W009", "W010"],
+    },
+    Check {
+        name: "range-performance",
+        codes: &["E014", "W003"],
+    },
+];
+
+/// the rule for a code (`E015`) or name (`mixed-and-or`)
+pub fn rule(code_or_name: &str) -> Option<&'static RuleInfo> {
+    RULES
+        .iter()
+        .find(|rule| rule.code == code_or_name || rule.name == code_or_name)
+}
+
+/// the rules a selector names: the rule for a code or name, or every rule raised by a check
+pub fn rules(selector: &str) -> Vec<&'static RuleInfo> {
+    if let Some(rule) = rule(selector) {
+        return vec![rule];
+    }
+
+    check(selector)
+        .map(|check| check.codes.iter().filter_map(|code| rule(code)).collect())
+        .unwrap_or_default()
+}
+
+/// the check registered under `name`
+pub fn check(name: &str) -> Option<&'static Check> {
+    CHECKS.iter().find(|check| check.name == name)
+}
+
+/// the name of the rule for `code`
+pub fn rule_name(code: &str) -> Option<&'static str> {
+    RULES
+        .iter()
+        .find(|rule| rule.code == code)
+        .map(|rule| rule.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BrandwatchLinter;
+    use crate::validation::ValidationEngine;
+    use pretty_assertions::assert_eq;
+
+    /// codes the parser doesn't currently produce, either kept for stability or caught earlier
+    /// by a more specific diagnostic
+    const RESERVED: &[&str] = &["E002", "E005", "E007", "E008", "E009", "E018", "W002"];
+
+    fn codes(query: &str) -> Vec<&'static str> {
+        let report = BrandwatchLinter::new().lint(query);
+        report
+            .errors
+            .iter()
+            .map(|error| error.code())
+            .chain(report.warnings.iter().map(|warning| warning.code()))
+            .collect()
+    }
+
+    #[test]
+    fn test_codes_and_names_are_unique() {
+        assert!(RULES.windows(2).all(|pair| pair[0].code < pair[1].code));
+
+        let mut names: Vec<_> = RULES.iter().map(|rule| rule.name).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), RULES.len());
+    }
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(rule("E015").unwrap().name, "mixed-and-or");
+        assert_eq!(rule("mixed-and-or").unwrap().code, "E015");
+        assert_eq!(rule_name("W005"), Some("single-term-proximity"));
+        assert_eq!(rule("E999"), None);
+
+        let codes = |selector| {
+            rules(selector)
+                .iter()
+                .map(|rule| rule.code)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(codes("language-field"), ["W006"]);
+        assert_eq!(codes("short-wildcard"), ["W009", "W010"]);
+        assert!(codes("unknown").is_empty());
+    }
+
+    #[test]
+    fn test_checks_are_registered() {
+        let engine = ValidationEngine::new();
+        let mut names = engine.rule_names();
+        names.sort();
+        let mut checks: Vec<_> = CHECKS.iter().map(|check| check.name).collect();
+        checks.sort();
+        assert_eq!(names, checks);
+
+        for check in CHECKS {
+            assert!(check.codes.iter().all(|code| rule(code).is_some()));
+            // a check named like a code must raise only that code
+            if let Some(rule) = RULES.iter().find(|rule| rule.name == check.name) {
+                assert_eq!(check.codes, [rule.code], "{}", check.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_examples() {
+        // some rules, like W008 once configured, can't be shown in a short example
+        let examples = RULES
+            .iter()
+            .filter(|rule| !RESERVED.contains(&rule.code) && !rule.bad.is_empty());
+        for rule in examples {
+            assert!(codes(rule.bad).contains(&rule.code), "{}", rule.code);
+            assert!(!codes(rule.good).contains(&rule.code), "{}", rule.code);
+        }
+    }
+}
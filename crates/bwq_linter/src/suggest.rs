@@ -0,0 +1,53 @@
+/// the number of single-character insertions, deletions and substitutions between `a` and `b`
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// the candidates within `max_distance` edits of `value`, closest first
+pub(crate) fn closest<'a>(
+    value: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+    max_distance: usize,
+) -> Vec<&'a str> {
+    let mut matches: Vec<_> = candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(value, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    matches.sort_by_key(|(distance, _)| *distance);
+    matches
+        .into_iter()
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("en", "en"), 0);
+        assert_eq!(edit_distance("eng", "en"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_closest() {
+        assert_eq!(closest("fe", ["fr", "de", "es", "it"], 1), vec!["fr", "de"]);
+        assert!(closest("xyz", ["en"], 1).is_empty());
+    }
+}
@@ -0,0 +1,279 @@
+use crate::ast::Comment;
+use crate::config::is_code_prefix;
+use crate::error::{LintReport, LintWarning, Span};
+
+/// the lines a suppression applies to
+#[derive(Debug, Clone, PartialEq)]
+pub enum SuppressionScope {
+    Line(usize),
+    File,
+}
+
+/// a `<<<bwq: ignore[...]>>>` or `<<<bwq: ignore-file[...]>>>` directive
+///
+/// `ignore` on a line of its own applies to the next line; after other text it applies to
+/// the line it is on. without a code list every diagnostic in scope is suppressed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suppression {
+    pub scope: SuppressionScope,
+    /// codes or code prefixes; `None` for all codes
+    pub codes: Option<Vec<String>>,
+    pub span: Span,
+    used: Vec<bool>,
+}
+
+impl Suppression {
+    fn covers(&self, line: usize) -> bool {
+        match self.scope {
+            SuppressionScope::Line(target) => target == line,
+            SuppressionScope::File => true,
+        }
+    }
+
+    /// mark and report whether this suppresses a diagnostic with `code` starting on `line`
+    fn suppresses(&mut self, code: &str, line: usize) -> bool {
+        if !self.covers(line) {
+            return false;
+        }
+
+        match &self.codes {
+            None => {
+                self.used[0] = true;
+                true
+            }
+            Some(codes) => {
+                let mut matched = false;
+                for (i, prefix) in codes.iter().enumerate() {
+                    if code.starts_with(prefix.as_str()) {
+                        self.used[i] = true;
+                        matched = true;
+                    }
+                }
+                matched
+            }
+        }
+    }
+}
+
+/// read the suppression directives out of a query's comments. `source` is the text the
+/// comments were parsed from; directives that can't be understood are returned as warnings
+pub fn parse_suppressions(
+    comments: &[Comment],
+    source: &str,
+) -> (Vec<Suppression>, Vec<LintWarning>) {
+    let chars: Vec<char> = source.chars().collect();
+    let mut suppressions = Vec::new();
+    let mut warnings = Vec::new();
+
+    for comment in comments {
+        let Some(directive) = comment.text.trim().strip_prefix("bwq:") else {
+            continue;
+        };
+        let directive = directive.trim();
+
+        let (file_scope, rest) = if let Some(rest) = directive.strip_prefix("ignore-file") {
+            (true, rest)
+        } else if let Some(rest) = directive.strip_prefix("ignore") {
+            (false, rest)
+        } else {
+            warnings.push(invalid_directive(comment, directive));
+            continue;
+        };
+
+        let codes = match rest.trim() {
+            "" => None,
+            list => match list
+                .strip_prefix('[')
+                .and_then(|list| list.strip_suffix(']'))
+            {
+                Some(list) => {
+                    let codes = list
+                        .split(',')
+                        .map(|code| code.trim().to_string())
+                        .filter(|code| !code.is_empty())
+                        .collect::<Vec<_>>();
+                    if codes.is_empty() {
+                        warnings.push(LintWarning::SuppressionWarning {
+                            span: comment.span.clone(),
+                            message: format!(
+                                "'bwq: {directive}' lists no codes. Name a code, or drop the brackets to ignore everything"
+                            ),
+                        });
+                        continue;
+                    }
+                    Some(codes)
+                }
+                None => {
+                    warnings.push(invalid_directive(comment, directive));
+                    continue;
+                }
+            },
+        };
+
+        if let Some(unknown) = codes.iter().flatten().find(|code| !is_code_prefix(code)) {
+            warnings.push(LintWarning::SuppressionWarning {
+                span: comment.span.clone(),
+                message: format!("'{unknown}' is not a diagnostic code"),
+            });
+            continue;
+        }
+
+        let scope = if file_scope {
+            SuppressionScope::File
+        } else if comment.is_standalone(&chars) {
+            SuppressionScope::Line(comment.span.end.line + 1)
+        } else {
+            SuppressionScope::Line(comment.span.start.line)
+        };
+
+        let used = vec![false; codes.as_ref().map_or(1, Vec::len)];
+        suppressions.push(Suppression {
+            scope,
+            codes,
+            span: comment.span.clone(),
+            used,
+        });
+    }
+
+    (suppressions, warnings)
+}
+
+fn invalid_directive(comment: &Comment, directive: &str) -> LintWarning {
+    LintWarning::SuppressionWarning {
+        span: comment.span.clone(),
+        message: format!(
+            "Unrecognized directive 'bwq: {directive}'. Expected 'bwq: ignore[CODE]' or 'bwq: ignore-file[CODE]'"
+        ),
+    }
+}
+
+/// remove suppressed diagnostics from `report`, returning warnings for the suppressions that
+/// didn't match anything
+pub fn apply_suppressions(
+    report: &mut LintReport,
+    mut suppressions: Vec<Suppression>,
+) -> Vec<LintWarning> {
+    let mut is_suppressed = |code: &str, span: &Span| {
+        // every suppression has to see the diagnostic so each of them is marked as used
+        let mut suppressed = false;
+        for suppression in suppressions.iter_mut() {
+            suppressed |= suppression.suppresses(code, span.start.line);
+        }
+        suppressed
+    };
+
+    report
+        .errors
+        .retain(|error| !is_suppressed(error.code(), error.span()));
+    report
+        .warnings
+        .retain(|warning| !is_suppressed(warning.code(), warning.span()));
+
+    let mut unused = Vec::new();
+    for suppression in &suppressions {
+        match &suppression.codes {
+            None if !suppression.used[0] => unused.push(LintWarning::SuppressionWarning {
+                span: suppression.span.clone(),
+                message: "Unused suppression: no diagnostics to ignore".to_string(),
+            }),
+            Some(codes) => {
+                for (code, used) in codes.iter().zip(&suppression.used) {
+                    if !used {
+                        unused.push(LintWarning::SuppressionWarning {
+                            span: suppression.span.clone(),
+                            message: format!("Unused suppression: no {code} diagnostic to ignore"),
+                        });
+                    }
+                }
+            }
+            None => {}
+        }
+    }
+
+    unused
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+    use pretty_assertions::assert_eq;
+
+    fn suppressions(source: &str) -> (Vec<Suppression>, Vec<LintWarning>) {
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let query = Parser::new(tokens).parse().query;
+        parse_suppressions(&query.comments, source)
+    }
+
+    #[test]
+    fn test_parse_directives() {
+        let source = "<<<bwq: ignore-file[E012, W0]>>>
+<<<bwq: ignore[W001]>>>
+apple juice <<<bwq: ignore>>>
+<<<just a note>>>";
+        let (suppressions, warnings) = suppressions(source);
+
+        assert!(warnings.is_empty());
+        assert_eq!(
+            suppressions
+                .iter()
+                .map(|s| (s.scope.clone(), s.codes.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    SuppressionScope::File,
+                    Some(vec!["E012".to_string(), "W0".to_string()])
+                ),
+                (SuppressionScope::Line(3), Some(vec!["W001".to_string()])),
+                (SuppressionScope::Line(3), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_directives() {
+        let (suppressions, warnings) = suppressions(
+            "apple <<<bwq: ignor[W001]>>> <<<bwq: ignore W001>>> <<<bwq: ignore[mixed]>>> <<<bwq: ignore[ , ]>>>",
+        );
+
+        assert!(suppressions.is_empty());
+        assert_eq!(
+            warnings.iter().map(|w| w.to_string()).collect::<Vec<_>>(),
+            vec![
+                "Unrecognized directive 'bwq: ignor[W001]'. Expected 'bwq: ignore[CODE]' or 'bwq: ignore-file[CODE]'",
+                "Unrecognized directive 'bwq: ignore W001'. Expected 'bwq: ignore[CODE]' or 'bwq: ignore-file[CODE]'",
+                "'mixed' is not a diagnostic code",
+                "'bwq: ignore[ , ]' lists no codes. Name a code, or drop the brackets to ignore everything",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unused_codes_are_reported() {
+        let report = crate::lint_query("<<<bwq: ignore[W001, E012]>>>\napple juice");
+
+        assert!(report.errors.is_empty());
+        assert_eq!(
+            report
+                .warnings
+                .iter()
+                .map(|w| (w.code(), w.to_string()))
+                .collect::<Vec<_>>(),
+            vec![(
+                "W004",
+                "Unused suppression: no E012 diagnostic to ignore".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_file_suppression() {
+        let report = crate::lint_query("<<<bwq: ignore-file>>> rating:6 OR (apple juice)");
+        assert!(report.is_clean());
+
+        let report = crate::lint_query("rating:6 OR (apple juice) <<<bwq: ignore-file[E012]>>>");
+        assert!(report.errors.is_empty());
+        assert_eq!(report.warnings[0].code(), "W001");
+    }
+}
@@ -1,41 +1,63 @@
 use super::rules::*;
 use super::{ValidationContext, ValidationRule};
 use crate::ast::*;
+use crate::config::RuleSettings;
 use crate::error::{LintError, LintReport, LintWarning};
+use crate::locations::Locations;
 
 /// per-expression validation engine
 pub struct ValidationEngine {
     rules: Vec<Box<dyn ValidationRule>>,
+    settings: RuleSettings,
 }
 
 impl ValidationEngine {
     pub fn new() -> Self {
+        Self::with_settings(RuleSettings::default())
+    }
+
+    pub fn with_settings(settings: RuleSettings) -> Self {
         Self {
             rules: vec![
                 // field validation rules
                 Box::new(RatingFieldRule),
                 Box::new(CoordinateFieldRule),
                 Box::new(LanguageFieldRule),
+                Box::new(LocationFieldRule::new(
+                    settings
+                        .locations
+                        .clone()
+                        .unwrap_or_else(Locations::builtin),
+                )),
                 Box::new(AuthorGenderFieldRule),
                 Box::new(BooleanFieldRule),
                 Box::new(EngagementTypeFieldRule),
                 Box::new(VerifiedTypeFieldRule),
                 Box::new(MinuteOfDayFieldRule),
                 Box::new(RangeFieldRule),
+                Box::new(SiteFieldRule),
                 // operator validation rules
                 Box::new(MixedAndOrRule),
+                Box::new(BooleanCaseRule),
                 Box::new(MixedNearRule),
                 Box::new(PureNegativeRule),
                 Box::new(BinaryOperatorRule),
                 Box::new(TildeUsageRule),
+                Box::new(CaseSensitiveRule),
                 // performance validation rules
                 Box::new(WildcardPerformanceRule),
                 Box::new(ShortTermRule),
+                Box::new(ShortWildcardRule),
                 Box::new(RangePerformanceRule),
             ],
+            settings,
         }
     }
 
+    pub fn rule_names(&self) -> Vec<&'static str> {
+        self.rules.iter().map(|rule| rule.name()).collect()
+    }
+
     pub fn validate(&self, query: &Query) -> LintReport {
         let mut all_errors = Vec::new();
         let mut all_warnings = Vec::new();
@@ -59,7 +81,9 @@ impl ValidationEngine {
         // apply all relevant rules to this expression
         for rule in &self.rules {
             if rule.can_validate(expr) {
-                let result = rule.validate(expr, ctx);
+                let result = self
+                    .settings
+                    .apply(rule.validate(expr, ctx), Some(rule.name()));
                 errors.extend(result.errors);
                 warnings.extend(result.warnings);
             }
@@ -84,19 +108,23 @@ impl ValidationEngine {
             Expression::Group { expression, .. } => {
                 let mut group_ctx = ctx.clone();
                 group_ctx.inside_group = true;
+                group_ctx.parent_operator = None;
                 self.walk_expression(expression, &group_ctx, errors, warnings);
             }
             Expression::Proximity { terms, .. } => {
+                let mut proximity_ctx = ctx.clone();
+                proximity_ctx.parent_operator = None;
                 for term in terms {
-                    self.walk_expression(term, ctx, errors, warnings);
+                    self.walk_expression(term, &proximity_ctx, errors, warnings);
                 }
             }
             Expression::Field { field, value, .. } => {
                 let mut field_ctx = ctx.clone();
                 field_ctx.field_context = Some(field.clone());
+                field_ctx.parent_operator = None;
                 self.walk_expression(value, &field_ctx, errors, warnings);
             }
-            Expression::Range { .. } | Expression::Term { .. } => {
+            Expression::Range { .. } | Expression::Term { .. } | Expression::Error { .. } => {
                 // terminal nodes - no recursion needed
             }
         }
@@ -9,6 +9,7 @@ pub use engine::ValidationEngine;
 #[derive(Debug, Clone, Default)]
 pub struct ValidationContext {
     pub inside_group: bool,
+    /// the operator joining this expression to its siblings; unset inside groups and fields
     pub parent_operator: Option<BooleanOperator>,
     pub field_context: Option<FieldType>,
 }
@@ -1,5 +1,10 @@
+use std::sync::Arc;
+
 use crate::ast::*;
-use crate::error::{LintError, LintWarning};
+use crate::error::{Applicability, Edit, Fix, LintError, LintWarning, Position, Span};
+use crate::languages::{LANGUAGES, language_code, language_name};
+use crate::locations::Locations;
+use crate::suggest::closest;
 use crate::validation::{ValidationContext, ValidationResult, ValidationRule};
 
 pub struct RatingFieldRule;
@@ -20,15 +25,13 @@ impl ValidationRule for RatingFieldRule {
                     term: Term::Word { value: rating },
                     ..
                 } = value.as_ref()
+                    && let Ok(rating_num) = rating.parse::<i32>()
+                    && !(0..=5).contains(&rating_num)
                 {
-                    if let Ok(rating_num) = rating.parse::<i32>() {
-                        if !(0..=5).contains(&rating_num) {
-                            return ValidationResult::with_error(LintError::FieldValidationError {
-                                span: span.clone(),
-                                message: "Rating must be between 0 and 5".to_string(),
-                            });
-                        }
-                    }
+                    return ValidationResult::with_error(LintError::InvalidRating {
+                        span: span.clone(),
+                        message: "Rating must be between 0 and 5".to_string(),
+                    });
                 }
                 ValidationResult::new()
             }
@@ -38,13 +41,13 @@ impl ValidationRule for RatingFieldRule {
                 end,
                 span,
             } => {
-                if let (Ok(start_num), Ok(end_num)) = (start.parse::<i32>(), end.parse::<i32>()) {
-                    if !(0..=5).contains(&start_num) || !(0..=5).contains(&end_num) {
-                        return ValidationResult::with_error(LintError::FieldValidationError {
-                            span: span.clone(),
-                            message: "Rating values must be between 0 and 5".to_string(),
-                        });
-                    }
+                if let (Ok(start_num), Ok(end_num)) = (start.parse::<i32>(), end.parse::<i32>())
+                    && (!(0..=5).contains(&start_num) || !(0..=5).contains(&end_num))
+                {
+                    return ValidationResult::with_error(LintError::InvalidRating {
+                        span: span.clone(),
+                        message: "Rating values must be between 0 and 5".to_string(),
+                    });
                 }
                 ValidationResult::new()
             }
@@ -80,33 +83,22 @@ impl ValidationRule for CoordinateFieldRule {
                     term: Term::Word { value: coord },
                     ..
                 } = value.as_ref()
+                    && let Ok(coord_num) = coord.parse::<f64>()
                 {
-                    if let Ok(coord_num) = coord.parse::<f64>() {
-                        match field {
-                            FieldType::Latitude => {
-                                if !(-90.0..=90.0).contains(&coord_num) {
-                                    return ValidationResult::with_error(
-                                        LintError::FieldValidationError {
-                                            span: span.clone(),
-                                            message: "Latitude must be between -90 and 90"
-                                                .to_string(),
-                                        },
-                                    );
-                                }
-                            }
-                            FieldType::Longitude => {
-                                if !(-180.0..=180.0).contains(&coord_num) {
-                                    return ValidationResult::with_error(
-                                        LintError::FieldValidationError {
-                                            span: span.clone(),
-                                            message: "Longitude must be between -180 and 180"
-                                                .to_string(),
-                                        },
-                                    );
-                                }
-                            }
-                            _ => {}
+                    match field {
+                        FieldType::Latitude if !(-90.0..=90.0).contains(&coord_num) => {
+                            return ValidationResult::with_error(LintError::InvalidCoordinate {
+                                span: span.clone(),
+                                message: "Latitude must be between -90 and 90".to_string(),
+                            });
                         }
+                        FieldType::Longitude if !(-180.0..=180.0).contains(&coord_num) => {
+                            return ValidationResult::with_error(LintError::InvalidCoordinate {
+                                span: span.clone(),
+                                message: "Longitude must be between -180 and 180".to_string(),
+                            });
+                        }
+                        _ => {}
                     }
                 }
                 ValidationResult::new()
@@ -119,31 +111,24 @@ impl ValidationRule for CoordinateFieldRule {
             } => {
                 if let (Ok(start_num), Ok(end_num)) = (start.parse::<f64>(), end.parse::<f64>()) {
                     match field {
-                        FieldType::Latitude => {
-                            if !(-90.0..=90.0).contains(&start_num)
-                                || !(-90.0..=90.0).contains(&end_num)
-                            {
-                                return ValidationResult::with_error(
-                                    LintError::FieldValidationError {
-                                        span: span.clone(),
-                                        message: "Latitude values must be between -90 and 90"
-                                            .to_string(),
-                                    },
-                                );
-                            }
+                        FieldType::Latitude
+                            if (!(-90.0..=90.0).contains(&start_num)
+                                || !(-90.0..=90.0).contains(&end_num)) =>
+                        {
+                            return ValidationResult::with_error(LintError::InvalidCoordinate {
+                                span: span.clone(),
+                                message: "Latitude values must be between -90 and 90".to_string(),
+                            });
                         }
-                        FieldType::Longitude => {
-                            if !(-180.0..=180.0).contains(&start_num)
-                                || !(-180.0..=180.0).contains(&end_num)
-                            {
-                                return ValidationResult::with_error(
-                                    LintError::FieldValidationError {
-                                        span: span.clone(),
-                                        message: "Longitude values must be between -180 and 180"
-                                            .to_string(),
-                                    },
-                                );
-                            }
+                        FieldType::Longitude
+                            if (!(-180.0..=180.0).contains(&start_num)
+                                || !(-180.0..=180.0).contains(&end_num)) =>
+                        {
+                            return ValidationResult::with_error(LintError::InvalidCoordinate {
+                                span: span.clone(),
+                                message: "Longitude values must be between -180 and 180"
+                                    .to_string(),
+                            });
                         }
                         _ => {}
                     }
@@ -175,40 +160,365 @@ impl ValidationRule for LanguageFieldRule {
         "language-field"
     }
 
-    fn validate(&self, expr: &Expression, _ctx: &ValidationContext) -> ValidationResult {
-        if let Expression::Field {
-            field: FieldType::Language,
-            value,
+    fn validate(&self, expr: &Expression, ctx: &ValidationContext) -> ValidationResult {
+        // each value is checked on its own, so `language:(en OR xx)` is covered too
+        let Expression::Term {
+            term: Term::Word { value: code },
             span,
         } = expr
-        {
-            if let Expression::Term {
-                term: Term::Word { value: lang_code },
-                ..
-            } = value.as_ref()
-            {
-                if lang_code.len() != 2 || !lang_code.chars().all(|c| c.is_ascii_lowercase()) {
-                    return ValidationResult::with_warning(LintWarning::PotentialTypo {
-                        span: span.clone(),
-                        suggestion: "Language codes should be 2-character ISO 639-1 codes (e.g., 'en', 'es')".to_string(),
-                    });
-                }
-            }
+        else {
+            return ValidationResult::new();
+        };
+        if ctx.field_context != Some(FieldType::Language) || language_name(code).is_some() {
+            return ValidationResult::new();
         }
-        ValidationResult::new()
+
+        let lowercase = code.to_lowercase();
+        let replace = |code: &str| {
+            Box::new(Fix::safe(
+                format!("Replace with '{code}'"),
+                vec![Edit::replacement(span.clone(), code.to_string())],
+            ))
+        };
+
+        let (message, fix) = if let Some(name) = language_name(&lowercase) {
+            (
+                format!("Language codes must be lowercase: '{lowercase}' ({name})"),
+                Some(replace(&lowercase)),
+            )
+        } else if let Some(language) = language_code(code) {
+            (
+                format!("'{code}' is a language name, not a code. Use '{language}'"),
+                Some(replace(language)),
+            )
+        } else {
+            let suggestions = closest(&lowercase, LANGUAGES.iter().map(|(code, _)| *code), 1);
+            let message = if suggestions.is_empty() {
+                format!(
+                    "Unknown language code '{code}'. Language codes are 2-letter ISO 639-1 codes (e.g., 'en', 'es')"
+                )
+            } else {
+                let suggestions: Vec<_> = suggestions
+                    .iter()
+                    .take(3)
+                    .map(|code| format!("'{code}' ({})", language_name(code).unwrap_or_default()))
+                    .collect();
+                format!(
+                    "Unknown language code '{code}'. Did you mean {}?",
+                    suggestions.join(", ")
+                )
+            };
+            (message, None)
+        };
+
+        ValidationResult::with_warning(LintWarning::InvalidLanguage {
+            span: span.clone(),
+            message,
+            fix,
+        })
     }
 
     fn can_validate(&self, expr: &Expression) -> bool {
         matches!(
             expr,
-            Expression::Field {
-                field: FieldType::Language,
+            Expression::Term {
+                term: Term::Word { .. },
                 ..
             }
         )
     }
 }
 
+/// what's wrong with a location code, and the code it should probably be
+struct LocationProblem {
+    message: String,
+    replacement: Option<String>,
+}
+
+impl LocationProblem {
+    fn new(message: String, replacement: Option<String>) -> Self {
+        Self {
+            message,
+            replacement,
+        }
+    }
+}
+
+/// checks `continent:`, `country:`, `region:` and `city:` values against the location dataset
+pub struct LocationFieldRule {
+    locations: Arc<Locations>,
+}
+
+impl LocationFieldRule {
+    pub fn new(locations: Arc<Locations>) -> Self {
+        Self { locations }
+    }
+
+    fn check(&self, field: &FieldType, code: &str) -> Result<(), LocationProblem> {
+        match field {
+            FieldType::Continent => self.check_continent(code),
+            FieldType::Country => self.check_country(code),
+            FieldType::Region => {
+                let Some((country, region)) = code
+                    .split_once('.')
+                    .filter(|(_, region)| !region.contains('.'))
+                else {
+                    return Err(LocationProblem::new(
+                        format!(
+                            "region: values are a country code and a region separated by '.' (e.g., 'usa.fl'), not '{code}'"
+                        ),
+                        None,
+                    ));
+                };
+                self.check_country(country)
+                    .map_err(|problem| with_country(problem, region))?;
+                self.check_region(country, region)
+            }
+            FieldType::City => {
+                let mut parts = code.splitn(3, '.');
+                let (Some(country), Some(region), Some(city)) =
+                    (parts.next(), parts.next(), parts.next())
+                else {
+                    let listed = self.unique_city(code);
+                    let message = match listed {
+                        Some(listed) => format!(
+                            "city: values start with the country and region codes. Use '{listed}'"
+                        ),
+                        None => format!(
+                            "city: values are a country code, region and city separated by '.' (e.g., 'deu.berlin.berlin'), not '{code}'"
+                        ),
+                    };
+                    return Err(LocationProblem::new(message, listed.map(str::to_string)));
+                };
+                self.check_country(country)
+                    .map_err(|problem| with_country(problem, &format!("{region}.{city}")))?;
+                self.check_city(country, region, city)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_continent(&self, code: &str) -> Result<(), LocationProblem> {
+        let locations = &self.locations;
+        if locations.continents.is_empty() || locations.continents.contains(code) {
+            return Ok(());
+        }
+
+        let message = if let Some(country) = locations.country_code(code) {
+            format!("'{code}' is a country, not a continent. Use country:{country}")
+        } else {
+            let continents: Vec<_> = locations.continents.iter().map(String::as_str).collect();
+            match closest(code, continents.iter().copied(), 2).first() {
+                Some(continent) => {
+                    format!("Unknown continent '{code}'. Did you mean '{continent}'?")
+                }
+                None => format!(
+                    "Unknown continent '{code}'. Continents are '{}'",
+                    continents.join("', '")
+                ),
+            }
+        };
+        Err(LocationProblem::new(message, None))
+    }
+
+    fn check_country(&self, code: &str) -> Result<(), LocationProblem> {
+        let locations = &self.locations;
+        if locations.countries.is_empty() || locations.countries.contains_key(code) {
+            return Ok(());
+        }
+
+        if let Some(country) = locations.country_code(code) {
+            let name = locations.country_name(country).unwrap_or_default();
+            return Err(LocationProblem::new(
+                format!("'{code}' is not a country code. Use '{country}' ({name})"),
+                Some(country.to_string()),
+            ));
+        }
+
+        let suggestions: Vec<_> = closest(code, locations.countries.keys().map(String::as_str), 1)
+            .into_iter()
+            .take(3)
+            .map(|country| {
+                format!(
+                    "'{country}' ({})",
+                    locations.country_name(country).unwrap_or_default()
+                )
+            })
+            .collect();
+        let message = if suggestions.is_empty() {
+            format!(
+                "Unknown country code '{code}'. Country codes are 3-letter ISO 3166 codes (e.g., 'usa', 'gbr')"
+            )
+        } else {
+            format!(
+                "Unknown country code '{code}'. Did you mean {}?",
+                suggestions.join(", ")
+            )
+        };
+        Err(LocationProblem::new(message, None))
+    }
+
+    fn check_region(&self, country: &str, region: &str) -> Result<(), LocationProblem> {
+        let locations = &self.locations;
+        let code = format!("{country}.{region}");
+        let regions: Vec<_> = locations
+            .regions_of(country)
+            .map(|(code, _)| code)
+            .collect();
+        if regions.is_empty() || regions.contains(&code.as_str()) {
+            return Ok(());
+        }
+
+        if let Some(region_code) = locations.region_code(country, region) {
+            return Err(LocationProblem::new(
+                format!("'{code}' uses the region's name. Use '{region_code}'"),
+                Some(region_code.to_string()),
+            ));
+        }
+
+        let country_name = locations.country_name(country).unwrap_or(country);
+        let message = match closest(&code, regions.iter().copied(), 2).first() {
+            Some(suggestion) => {
+                format!("Unknown region '{code}' in {country_name}. Did you mean '{suggestion}'?")
+            }
+            None => format!("Unknown region '{code}' in {country_name}"),
+        };
+        Err(LocationProblem::new(message, None))
+    }
+
+    fn check_city(&self, country: &str, region: &str, city: &str) -> Result<(), LocationProblem> {
+        let locations = &self.locations;
+        let code = format!("{country}.{region}.{city}");
+        if locations.cities.contains(&code) {
+            return Ok(());
+        }
+
+        // the dataset is partial, so a city is only moved when its region is unknown and the
+        // name can't belong to any other listed city
+        let region_known = locations
+            .regions
+            .contains_key(&format!("{country}.{region}"));
+        if !region_known
+            && let Some(listed) = self.unique_city(city)
+            && listed.starts_with(&format!("{country}."))
+        {
+            let (listed_region, _) = listed.rsplit_once('.').unwrap_or_default();
+            return Err(LocationProblem::new(
+                format!(
+                    "'{city}' is listed in the region '{listed_region}', so its code is '{listed}'"
+                ),
+                Some(listed.to_string()),
+            ));
+        }
+
+        self.check_region(country, region)
+            .map_err(|problem| LocationProblem {
+                replacement: problem.replacement.map(|region| format!("{region}.{city}")),
+                ..problem
+            })
+    }
+
+    /// the code of the only listed city called `name`
+    fn unique_city<'a>(&'a self, name: &'a str) -> Option<&'a str> {
+        let mut cities = self.locations.cities_named(name);
+        cities.next().filter(|_| cities.next().is_none())
+    }
+
+    /// the location code `code` is the start of, when the rest of it followed a space
+    fn unquoted_code(&self, field: &FieldType, code: &str) -> Option<&str> {
+        let separators = match field {
+            FieldType::Continent => 0,
+            FieldType::Region => 1,
+            FieldType::City => 2,
+            _ => return None,
+        };
+        let prefix = format!("{code} ");
+        self.locations
+            .codes_starting_with(&prefix)
+            .find(|full| full.matches('.').count() == separators)
+    }
+}
+
+/// a problem with the country part of a region or city code, carried over to the whole code
+fn with_country(problem: LocationProblem, rest: &str) -> LocationProblem {
+    LocationProblem {
+        replacement: problem
+            .replacement
+            .map(|country| format!("{country}.{rest}")),
+        ..problem
+    }
+}
+
+impl ValidationRule for LocationFieldRule {
+    fn name(&self) -> &'static str {
+        "location-field"
+    }
+
+    fn validate(&self, expr: &Expression, ctx: &ValidationContext) -> ValidationResult {
+        let Expression::Term { term, span } = expr else {
+            return ValidationResult::new();
+        };
+        let Some(
+            field @ (FieldType::Continent
+            | FieldType::Country
+            | FieldType::Region
+            | FieldType::City),
+        ) = ctx.field_context.as_ref()
+        else {
+            return ValidationResult::new();
+        };
+        let (code, quoted) = match term {
+            Term::Word { value } => (value, false),
+            Term::Phrase { value } => (value, true),
+            _ => return ValidationResult::new(),
+        };
+
+        let replace = |code: &str| {
+            let content = if quoted || code.contains(' ') {
+                format!("\"{code}\"")
+            } else {
+                code.to_string()
+            };
+            Box::new(Fix::safe(
+                format!("Replace with '{code}'"),
+                vec![Edit::replacement(span.clone(), content)],
+            ))
+        };
+
+        let lowercase = code.to_lowercase();
+        if !quoted && let Some(full) = self.unquoted_code(field, &lowercase) {
+            return ValidationResult::with_warning(LintWarning::InvalidLocation {
+                span: span.clone(),
+                message: format!(
+                    "Location codes containing spaces must be quoted: {}:\"{full}\"",
+                    field.as_str()
+                ),
+                fix: None,
+            });
+        }
+
+        match self.check(field, &lowercase) {
+            Ok(()) if lowercase != *code => {
+                ValidationResult::with_warning(LintWarning::InvalidLocation {
+                    span: span.clone(),
+                    message: format!("Location codes must be lowercase: '{lowercase}'"),
+                    fix: Some(replace(&lowercase)),
+                })
+            }
+            Ok(()) => ValidationResult::new(),
+            Err(problem) => ValidationResult::with_warning(LintWarning::InvalidLocation {
+                span: span.clone(),
+                message: problem.message,
+                fix: problem.replacement.as_deref().map(replace),
+            }),
+        }
+    }
+
+    fn can_validate(&self, expr: &Expression) -> bool {
+        matches!(expr, Expression::Term { .. })
+    }
+}
+
 pub struct AuthorGenderFieldRule;
 
 impl ValidationRule for AuthorGenderFieldRule {
@@ -222,22 +532,19 @@ impl ValidationRule for AuthorGenderFieldRule {
             value,
             span,
         } = expr
-        {
-            if let Expression::Term {
+            && let Expression::Term {
                 term: Term::Word { value: gender },
                 ..
             } = value.as_ref()
-            {
-                if !matches!(
-                    gender.as_str(),
-                    "F" | "M" | "f" | "m" | "X" | "x" | "U" | "u"
-                ) {
-                    return ValidationResult::with_warning(LintWarning::PotentialTypo {
-                        span: span.clone(),
-                        suggestion: "Common gender values are 'F', 'M', 'X', or 'U'".to_string(),
-                    });
-                }
-            }
+            && !matches!(
+                gender.as_str(),
+                "F" | "M" | "f" | "m" | "X" | "x" | "U" | "u"
+            )
+        {
+            return ValidationResult::with_warning(LintWarning::UnexpectedAuthorGender {
+                span: span.clone(),
+                message: "Common gender values are 'F', 'M', 'X', or 'U'".to_string(),
+            });
         }
         ValidationResult::new()
     }
@@ -270,20 +577,27 @@ impl ValidationRule for BooleanFieldRule {
                     | FieldType::SensitiveContent
             );
 
-            if is_boolean_field {
-                if let Expression::Term {
+            if is_boolean_field
+                && let Expression::Term {
                     term: Term::Word { value: bool_val },
-                    ..
+                    span: value_span,
                 } = value.as_ref()
-                {
-                    if !matches!(bool_val.as_str(), "true" | "false") {
-                        let field_name = field.as_str();
-                        return ValidationResult::with_error(LintError::FieldValidationError {
-                            span: span.clone(),
-                            message: format!("{field_name} must be 'true' or 'false'"),
-                        });
-                    }
-                }
+                && !matches!(bool_val.as_str(), "true" | "false")
+            {
+                let field_name = field.as_str();
+                let lowercase = bool_val.to_ascii_lowercase();
+                let fix = matches!(lowercase.as_str(), "true" | "false").then(|| {
+                    Box::new(Fix::safe(
+                        format!("Replace with '{lowercase}'"),
+                        vec![Edit::replacement(value_span.clone(), lowercase.clone())],
+                    ))
+                });
+
+                return ValidationResult::with_error(LintError::InvalidBooleanValue {
+                    span: span.clone(),
+                    message: format!("{field_name} must be 'true' or 'false'"),
+                    fix,
+                });
             }
         }
         ValidationResult::new()
@@ -317,23 +631,23 @@ impl ValidationRule for EngagementTypeFieldRule {
             value,
             span,
         } = expr
-        {
-            if let Expression::Term {
+            && let Expression::Term {
                 term: Term::Word {
                     value: engagement_type,
                 },
                 ..
             } = value.as_ref()
-            {
-                let common_types = [
-                    "COMMENT", "REPLY", "RETWEET", "QUOTE", "LIKE", "SHARE", "MENTION",
-                ];
-                if !common_types.contains(&engagement_type.as_str()) {
-                    return ValidationResult::with_warning(LintWarning::PotentialTypo {
-                        span: span.clone(),
-                        suggestion: "Common engagement types are 'COMMENT', 'REPLY', 'RETWEET', 'QUOTE', 'LIKE'".to_string(),
-                    });
-                }
+        {
+            let common_types = [
+                "COMMENT", "REPLY", "RETWEET", "QUOTE", "LIKE", "SHARE", "MENTION",
+            ];
+            if !common_types.contains(&engagement_type.as_str()) {
+                return ValidationResult::with_warning(LintWarning::UnexpectedEngagementType {
+                    span: span.clone(),
+                    message:
+                        "Common engagement types are 'COMMENT', 'REPLY', 'RETWEET', 'QUOTE', 'LIKE'"
+                            .to_string(),
+                });
             }
         }
         ValidationResult::new()
@@ -363,22 +677,19 @@ impl ValidationRule for VerifiedTypeFieldRule {
             value,
             span,
         } = expr
-        {
-            if let Expression::Term {
+            && let Expression::Term {
                 term: Term::Word {
                     value: verified_type,
                 },
                 ..
             } = value.as_ref()
-            {
-                if !matches!(verified_type.as_str(), "blue" | "business" | "government") {
-                    return ValidationResult::with_error(LintError::FieldValidationError {
-                        span: span.clone(),
-                        message: "authorVerifiedType must be 'blue', 'business', or 'government'"
-                            .to_string(),
-                    });
-                }
-            }
+            && !matches!(verified_type.as_str(), "blue" | "business" | "government")
+        {
+            return ValidationResult::with_error(LintError::InvalidVerifiedType {
+                span: span.clone(),
+                message: "authorVerifiedType must be 'blue', 'business', or 'government'"
+                    .to_string(),
+            });
         }
         ValidationResult::new()
     }
@@ -408,15 +719,13 @@ impl ValidationRule for MinuteOfDayFieldRule {
             end,
             span,
         } = expr
+            && let (Ok(start_num), Ok(end_num)) = (start.parse::<i32>(), end.parse::<i32>())
+            && (!(0..=1439).contains(&start_num) || !(0..=1439).contains(&end_num))
         {
-            if let (Ok(start_num), Ok(end_num)) = (start.parse::<i32>(), end.parse::<i32>()) {
-                if !(0..=1439).contains(&start_num) || !(0..=1439).contains(&end_num) {
-                    return ValidationResult::with_error(LintError::FieldValidationError {
-                        span: span.clone(),
-                        message: "minuteOfDay values must be between 0 and 1439".to_string(),
-                    });
-                }
-            }
+            return ValidationResult::with_error(LintError::InvalidMinuteOfDay {
+                span: span.clone(),
+                message: "minuteOfDay values must be between 0 and 1439".to_string(),
+            });
         }
         ValidationResult::new()
     }
@@ -443,15 +752,20 @@ impl ValidationRule for RangeFieldRule {
         if let Expression::Range {
             start, end, span, ..
         } = expr
+            && let (Ok(start_num), Ok(end_num)) = (start.parse::<f64>(), end.parse::<f64>())
+            && start_num > end_num
         {
-            if let (Ok(start_num), Ok(end_num)) = (start.parse::<f64>(), end.parse::<f64>()) {
-                if start_num > end_num {
-                    return ValidationResult::with_error(LintError::RangeValidationError {
-                        span: span.clone(),
-                        message: "Range start value cannot be greater than end value".to_string(),
-                    });
-                }
-            }
+            return ValidationResult::with_error(LintError::RangeValidationError {
+                span: span.clone(),
+                message: "Range start value cannot be greater than end value".to_string(),
+                fix: Some(Box::new(Fix::safe(
+                    "Swap the range bounds",
+                    vec![Edit::replacement(
+                        span.clone(),
+                        format!("[{end} TO {start}]"),
+                    )],
+                ))),
+            });
         }
         ValidationResult::new()
     }
@@ -460,3 +774,334 @@ impl ValidationRule for RangeFieldRule {
         matches!(expr, Expression::Range { .. })
     }
 }
+
+/// checks the domains given to `site:`, `url:`, `links:` and `topLevelDomain:`
+pub struct SiteFieldRule;
+
+impl ValidationRule for SiteFieldRule {
+    fn name(&self) -> &'static str {
+        "site-field"
+    }
+
+    fn validate(&self, expr: &Expression, _ctx: &ValidationContext) -> ValidationResult {
+        let Expression::Field { field, value, span } = expr else {
+            return ValidationResult::new();
+        };
+
+        let mut values = Vec::new();
+        collect_domain_values(value, &mut values);
+
+        // a lone value is reported with its field, as in `site:www.bbc.co.uk`
+        let lone_value = matches!(value.as_ref(), Expression::Term { .. });
+        let mut result = ValidationResult::new();
+        for value in &values {
+            let report_span = if lone_value { span } else { value.span };
+            let errors = match field {
+                FieldType::TopLevelDomain => check_top_level_domain(value, report_span),
+                _ => check_domain(field, span, value, report_span)
+                    .into_iter()
+                    .collect(),
+            };
+            result.errors.extend(errors);
+        }
+        result
+    }
+
+    fn can_validate(&self, expr: &Expression) -> bool {
+        matches!(
+            expr,
+            Expression::Field {
+                field: FieldType::Site
+                    | FieldType::Url
+                    | FieldType::Links
+                    | FieldType::TopLevelDomain,
+                ..
+            }
+        )
+    }
+}
+
+/// a word or phrase given to a domain field, e.g. both values in `site:(bbc.co.uk OR "bbc.com")`
+struct DomainValue<'a> {
+    text: &'a str,
+    span: &'a Span,
+    quoted: bool,
+}
+
+impl DomainValue<'_> {
+    /// the span of the chars `start..end` of the value's text
+    fn span(&self, start: usize, end: usize) -> Span {
+        let skip = usize::from(self.quoted);
+        let at = |chars: usize| {
+            let start = &self.span.start;
+            Position::new(
+                start.line,
+                start.column + skip + chars,
+                start.offset + skip + chars,
+            )
+        };
+        Span::new(at(start), at(end))
+    }
+}
+
+fn collect_domain_values<'a>(expr: &'a Expression, values: &mut Vec<DomainValue<'a>>) {
+    match expr {
+        Expression::Term {
+            term: Term::Word { value },
+            span,
+        } => values.push(DomainValue {
+            text: value,
+            span,
+            quoted: false,
+        }),
+        Expression::Term {
+            term: Term::Phrase { value },
+            span,
+        } => values.push(DomainValue {
+            text: value,
+            span,
+            quoted: true,
+        }),
+        Expression::Group { expression, .. } => collect_domain_values(expression, values),
+        Expression::BooleanOp { left, right, .. } => {
+            collect_domain_values(left, values);
+            if let Some(right) = right {
+                collect_domain_values(right, values);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// the kinds of problem a `site:`, `url:` or `links:` value can have, each with its own code
+#[derive(Clone, Copy)]
+enum DomainProblem {
+    Invalid,
+    WwwPrefix,
+    Hostname,
+    Incomplete,
+}
+
+/// `site:`, `url:` and `links:` take a hostname, and only `url:` takes a path after it. a value
+/// is reported once, for its first problem, and only fixed when all of its problems can be
+fn check_domain(
+    field: &FieldType,
+    field_span: &Span,
+    value: &DomainValue,
+    report_span: &Span,
+) -> Option<LintError> {
+    let name = field.as_str();
+    let text = value.text;
+    let lowercase = text.to_ascii_lowercase();
+
+    // each problem found, with its message and the fix for it if there is one
+    let mut problems: Vec<(DomainProblem, String, Option<Fix>)> = Vec::new();
+    // the prefixes are ASCII, so this counts both bytes and chars
+    let mut host_start = 0;
+
+    if let Some(scheme) = ["https://", "http://"]
+        .into_iter()
+        .find(|scheme| lowercase.starts_with(scheme))
+    {
+        let scheme = &text[..scheme.len()];
+        problems.push((
+            DomainProblem::Invalid,
+            format!("{name}: should not include '{scheme}'"),
+            Some(Fix::safe(
+                format!("Remove '{scheme}'"),
+                vec![Edit::deletion(value.span(0, scheme.len()))],
+            )),
+        ));
+        host_start = scheme.len();
+    }
+
+    if *field == FieldType::Site
+        && lowercase[host_start..].starts_with("www.")
+        && lowercase.len() > host_start + 4
+    {
+        problems.push((
+            DomainProblem::WwwPrefix,
+            "site: should not include 'www.'".to_string(),
+            Some(Fix::safe(
+                "Remove 'www.'",
+                vec![Edit::deletion(value.span(host_start, host_start + 4))],
+            )),
+        ));
+        host_start += 4;
+    }
+
+    let rest = &text[host_start..];
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+
+    if *field == FieldType::Site && !path.is_empty() {
+        let start = &field_span.start;
+        let field_name = Span::new(
+            start.clone(),
+            Position::new(start.line, start.column + 4, start.offset + 4),
+        );
+        problems.push((
+            DomainProblem::Invalid,
+            format!("site: only takes a domain; use url: to match the pages under '{rest}'"),
+            Some(Fix::unsafe_edit(
+                "Replace 'site:' with 'url:'",
+                vec![Edit::replacement(field_name, "url")],
+            )),
+        ));
+    } else if *field == FieldType::Site && rest.ends_with('/') {
+        let end = text.chars().count();
+        problems.push((
+            DomainProblem::Invalid,
+            "site: should not end with '/'".to_string(),
+            Some(Fix::safe(
+                "Remove the '/'",
+                vec![Edit::deletion(value.span(end - 1, end))],
+            )),
+        ));
+    }
+
+    let normalized = normalize_host(host);
+    if normalized.is_empty() {
+        problems.push((
+            DomainProblem::Invalid,
+            format!("{name}: needs a hostname such as 'bbc.com', not '{text}'"),
+            None,
+        ));
+    } else if let Some(label) = normalized.split('.').find(|label| !is_host_label(label)) {
+        problems.push((
+            DomainProblem::Hostname,
+            format!(
+                "{name}: has an invalid hostname '{host}'. '{label}' may only use letters, digits and hyphens, and can't start or end with a hyphen"
+            ),
+            None,
+        ));
+    } else if *field == FieldType::Site && !normalized.contains('.') {
+        problems.push((
+            DomainProblem::Incomplete,
+            format!("site: takes a full domain such as 'bbc.com', not '{host}'"),
+            None,
+        ));
+    } else if normalized != host {
+        // dropping a port or a stray dot is a guess at what was meant
+        let host_end = host_start + host.chars().count();
+        problems.push((
+            DomainProblem::Invalid,
+            format!("{name}: has a malformed hostname '{host}'. Did you mean '{normalized}'?"),
+            Some(Fix::unsafe_edit(
+                format!("Replace with '{normalized}'"),
+                vec![Edit::replacement(
+                    value.span(host_start, host_end),
+                    normalized.clone(),
+                )],
+            )),
+        ));
+    }
+
+    let (problem, _, _) = problems.first()?;
+    let span = report_span.clone();
+    let message = problems
+        .iter()
+        .map(|(_, message, _)| message.as_str())
+        .collect::<Vec<_>>()
+        .join("; ");
+    let fix = combine_fixes(problems.iter().map(|(_, _, fix)| fix.as_ref()));
+
+    Some(match problem {
+        DomainProblem::Invalid => LintError::InvalidDomain { span, message, fix },
+        DomainProblem::WwwPrefix => LintError::SiteWwwPrefix { span, message, fix },
+        DomainProblem::Hostname => LintError::InvalidHostname { span, message },
+        DomainProblem::Incomplete => LintError::IncompleteDomain { span, message },
+    })
+}
+
+/// one fix making every edit in `fixes`, or `None` when one of them is missing
+fn combine_fixes<'a>(fixes: impl Iterator<Item = Option<&'a Fix>>) -> Option<Box<Fix>> {
+    let fixes: Vec<&Fix> = fixes.collect::<Option<_>>()?;
+    if let [fix] = fixes.as_slice() {
+        return Some(Box::new((*fix).clone()));
+    }
+
+    let message = fixes
+        .iter()
+        .enumerate()
+        .map(|(i, fix)| {
+            if i == 0 {
+                fix.message.clone()
+            } else {
+                let mut chars = fix.message.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_lowercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" and ");
+    let edits = fixes.iter().flat_map(|fix| fix.edits.clone()).collect();
+    let fix = if fixes
+        .iter()
+        .all(|fix| fix.applicability == Applicability::Safe)
+    {
+        Fix::safe(message, edits)
+    } else {
+        Fix::unsafe_edit(message, edits)
+    };
+    Some(Box::new(fix))
+}
+
+/// one dot-separated part of a hostname, e.g. `bbc` in `bbc.co.uk`
+fn is_host_label(label: &str) -> bool {
+    !label.starts_with('-')
+        && !label.ends_with('-')
+        && label.chars().all(|ch| ch.is_alphanumeric() || ch == '-')
+}
+
+/// a hostname without a port, or empty labels from stray dots
+fn normalize_host(host: &str) -> String {
+    let host = host.split_once(':').map_or(host, |(host, _port)| host);
+    host.split('.')
+        .filter(|label| !label.is_empty())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// `topLevelDomain:` takes the last label of a domain, e.g. `uk`
+fn check_top_level_domain(value: &DomainValue, report_span: &Span) -> Vec<LintError> {
+    let text = value.text;
+    if !text.contains('.') {
+        return Vec::new();
+    }
+
+    let labels: Vec<&str> = text.split('.').filter(|label| !label.is_empty()).collect();
+    let Some(last) = labels.last() else {
+        return vec![LintError::InvalidDomain {
+            span: report_span.clone(),
+            message: "topLevelDomain: takes a domain ending such as 'uk', not dots".to_string(),
+            fix: None,
+        }];
+    };
+
+    let edit = vec![Edit::replacement(
+        value.span(0, text.chars().count()),
+        last.to_string(),
+    )];
+    let (message, fix) = if labels.len() == 1 {
+        (
+            format!("topLevelDomain: takes the domain ending without dots, e.g. '{last}'"),
+            Fix::safe(format!("Replace with '{last}'"), edit),
+        )
+    } else {
+        // `co.uk` isn't a top-level domain, and `uk` matches more than it
+        (
+            format!(
+                "topLevelDomain: only takes the last part of a domain, e.g. '{last}' rather than '{text}'. Use site: to match '{text}'"
+            ),
+            Fix::unsafe_edit(format!("Replace with '{last}'"), edit),
+        )
+    };
+
+    vec![LintError::InvalidDomain {
+        span: report_span.clone(),
+        message,
+        fix: Some(Box::new(fix)),
+    }]
+}
@@ -1,5 +1,6 @@
 use crate::ast::*;
-use crate::error::{LintError, LintWarning};
+use crate::error::{Edit, Fix, LintError, LintWarning, Position, Span};
+use crate::parser::is_operator_word;
 use crate::validation::{ValidationContext, ValidationResult, ValidationRule};
 
 pub struct MixedAndOrRule;
@@ -17,28 +18,34 @@ impl ValidationRule for MixedAndOrRule {
                 right,
                 span,
             } => {
+                // operators around a syntax error are a guess by the parser, so the syntax
+                // error is the only thing worth reporting
+                if left.contains_error()
+                    || right.as_ref().is_some_and(|right| right.contains_error())
+                {
+                    return ValidationResult::new();
+                }
                 if matches!(operator, BooleanOperator::And) {
-                    if let Some(right_expr) = right {
-                        if self.contains_or_at_top_level(right_expr)
-                            || self.contains_or_at_top_level(left)
-                        {
-                            return ValidationResult::with_error(LintError::OperatorMixingError {
+                    if let Some(right_expr) = right
+                        && (self.contains_or_at_top_level(right_expr)
+                            || self.contains_or_at_top_level(left))
+                    {
+                        return ValidationResult::with_error(LintError::OperatorMixingError {
                                 span: span.clone(),
                                 message: "The AND and OR operators cannot be mixed in the same sub-query. Please use parentheses to disambiguate - e.g. vanilla AND (icecream OR cake).".to_string(),
+                                fix: self.parenthesize(&BooleanOperator::Or, left, right_expr),
                             });
-                        }
                     }
-                } else if matches!(operator, BooleanOperator::Or) {
-                    if let Some(right_expr) = right {
-                        if self.contains_and_at_top_level(right_expr)
-                            || self.contains_and_at_top_level(left)
-                        {
-                            return ValidationResult::with_error(LintError::OperatorMixingError {
+                } else if matches!(operator, BooleanOperator::Or)
+                    && let Some(right_expr) = right
+                    && (self.contains_and_at_top_level(right_expr)
+                        || self.contains_and_at_top_level(left))
+                {
+                    return ValidationResult::with_error(LintError::OperatorMixingError {
                                 span: span.clone(),
                                 message: "The AND and OR operators cannot be mixed in the same sub-query. Please use parentheses to disambiguate - e.g. vanilla AND (icecream OR cake).".to_string(),
+                                fix: self.parenthesize(&BooleanOperator::And, left, right_expr),
                             });
-                        }
-                    }
                 }
                 ValidationResult::new()
             }
@@ -58,6 +65,35 @@ impl ValidationRule for MixedAndOrRule {
 }
 
 impl MixedAndOrRule {
+    /// group the operands joined by `inner`, keeping the grouping the parser gave them. that is
+    /// only one of the readings the author may have meant, so the fix is unsafe
+    fn parenthesize(
+        &self,
+        inner: &BooleanOperator,
+        left: &Expression,
+        right: &Expression,
+    ) -> Option<Box<Fix>> {
+        let edits: Vec<Edit> = [left, right]
+            .into_iter()
+            .filter(|operand| {
+                matches!(operand, Expression::BooleanOp { operator, .. } if operator == inner)
+            })
+            .flat_map(|operand| {
+                [
+                    Edit::insertion(operand.span().start.clone(), "("),
+                    Edit::insertion(operand.span().end.clone(), ")"),
+                ]
+            })
+            .collect();
+
+        (!edits.is_empty()).then(|| {
+            Box::new(Fix::unsafe_edit(
+                format!("Wrap the {} operands in parentheses", inner.as_str()),
+                edits,
+            ))
+        })
+    }
+
     fn contains_and_at_top_level(&self, expr: &Expression) -> bool {
         matches!(
             expr,
@@ -79,6 +115,108 @@ impl MixedAndOrRule {
     }
 }
 
+/// a lowercase `and`/`or` is searched for as a word, which is rarely what was meant
+pub struct BooleanCaseRule;
+
+impl ValidationRule for BooleanCaseRule {
+    fn name(&self) -> &'static str {
+        "boolean-case"
+    }
+
+    fn validate(&self, expr: &Expression, ctx: &ValidationContext) -> ValidationResult {
+        if ctx.field_context.is_some() {
+            return ValidationResult::new();
+        }
+
+        match expr {
+            // words joined to their neighbours by AND are reported with the whole chain, which
+            // knows whether they sit between two operands
+            Expression::BooleanOp {
+                operator: BooleanOperator::And,
+                ..
+            } if ctx.parent_operator != Some(BooleanOperator::And) => {
+                let mut operands = Vec::new();
+                collect_and_operands(expr, &mut operands);
+
+                let mut result = ValidationResult::new();
+                for (i, operand) in operands.iter().enumerate() {
+                    let between_operands = i > 0
+                        && i + 1 < operands.len()
+                        && !is_operator_word(operands[i - 1])
+                        && !is_operator_word(operands[i + 1]);
+                    if let Some(error) = self.check_word(operand, between_operands) {
+                        result.errors.push(error);
+                    }
+                }
+                result
+            }
+            Expression::Term { .. } if ctx.parent_operator != Some(BooleanOperator::And) => self
+                .check_word(expr, false)
+                .map_or_else(ValidationResult::new, ValidationResult::with_error),
+            _ => ValidationResult::new(),
+        }
+    }
+
+    fn can_validate(&self, expr: &Expression) -> bool {
+        matches!(
+            expr,
+            Expression::Term {
+                term: Term::Word { .. },
+                ..
+            } | Expression::BooleanOp {
+                operator: BooleanOperator::And,
+                ..
+            }
+        )
+    }
+}
+
+impl BooleanCaseRule {
+    /// an error if `expr` is a lowercase operator word. making it an operator changes what the
+    /// query matches, so the fix is unsafe and only offered between two operands, where the
+    /// result still parses
+    fn check_word(&self, expr: &Expression, between_operands: bool) -> Option<LintError> {
+        let Expression::Term {
+            term: Term::Word { value },
+            span,
+        } = expr
+        else {
+            return None;
+        };
+        let uppercase = value.to_ascii_uppercase();
+        if !is_operator_word(expr) || *value == uppercase {
+            return None;
+        }
+
+        Some(LintError::InvalidBooleanCase {
+            span: span.clone(),
+            operator: value.clone(),
+            fix: between_operands.then(|| {
+                Box::new(Fix::unsafe_edit(
+                    format!("Replace with '{uppercase}'"),
+                    vec![Edit::replacement(span.clone(), uppercase)],
+                ))
+            }),
+        })
+    }
+}
+
+/// flatten a chain of ANDs into its operands, in order
+fn collect_and_operands<'e>(expr: &'e Expression, operands: &mut Vec<&'e Expression>) {
+    match expr {
+        Expression::BooleanOp {
+            operator: BooleanOperator::And,
+            left,
+            right: Some(right),
+            ..
+        } => {
+            collect_and_operands(left, operands);
+            collect_and_operands(right, operands);
+        }
+        _ => operands.push(expr),
+    }
+}
+
 pub struct MixedNearRule;
 
 impl ValidationRule for MixedNearRule {
@@ -94,27 +232,25 @@ impl ValidationRule for MixedNearRule {
                 right,
                 span,
             } => {
-                if matches!(operator, BooleanOperator::And) {
-                    if let Some(right_expr) = right {
-                        if self.is_unparenthesized_near_and_mix(left, right_expr) {
-                            return ValidationResult::with_error(LintError::ProximityOperatorError {
+                if matches!(operator, BooleanOperator::And)
+                    && let Some(right_expr) = right
+                    && self.is_unparenthesized_near_and_mix(left, right_expr)
+                {
+                    return ValidationResult::with_error(LintError::ProximityOperatorError {
                                 span: span.clone(),
                                 message: "The AND operator cannot be used within the NEAR operator. Either remove this operator or disambiguate with parenthesis, e.g. (vanilla NEAR/5 ice-cream) AND cake.".to_string(),
                             });
-                        }
-                    }
                 }
-                if !ctx.inside_group && matches!(operator, BooleanOperator::Or) {
-                    if let Some(right_expr) = right {
-                        if self.contains_near_at_top_level(right_expr)
-                            || self.contains_near_at_top_level(left)
-                        {
-                            return ValidationResult::with_error(LintError::ProximityOperatorError {
+                if !ctx.inside_group
+                    && matches!(operator, BooleanOperator::Or)
+                    && let Some(right_expr) = right
+                    && (self.contains_near_at_top_level(right_expr)
+                        || self.contains_near_at_top_level(left))
+                {
+                    return ValidationResult::with_error(LintError::ProximityOperatorError {
                                 span: span.clone(),
                                 message: "Please use parentheses for disambiguation when using the OR or NEAR operators with another NEAR operator - e.g. (vanilla OR chocolate) NEAR/5 (ice-cream NEAR/5 cake).".to_string(),
                             });
-                        }
-                    }
                 }
                 ValidationResult::new()
             }
@@ -229,9 +365,10 @@ impl PureNegativeRule {
                 right,
                 ..
             } => {
-                // leading NOT with no right operand is pure negative
+                // leading NOT with no right operand is pure negative (unless the operand
+                // itself failed to parse, which is already reported)
                 if right.is_none() {
-                    return true;
+                    return !matches!(left.as_ref(), Expression::Error { .. });
                 }
                 // binary NOT: if left side is pure negative, then the whole operation
                 // is pure negative because the right side is being excluded, not included
@@ -293,7 +430,7 @@ impl ValidationRule for BinaryOperatorRule {
             }
 
             if right.is_none() && !matches!(operator, BooleanOperator::Not) {
-                return ValidationResult::with_error(LintError::ValidationError {
+                return ValidationResult::with_error(LintError::MissingOperand {
                     span: span.clone(),
                     message: format!("{} operator requires two operands", operator.as_str()),
                 });
@@ -320,32 +457,31 @@ impl ValidationRule for TildeUsageRule {
             terms,
             span,
         } = expr
+            && let Some(first_term) = terms.first()
         {
-            if let Some(first_term) = terms.first() {
-                match first_term {
-                    Expression::Term {
-                        term: Term::Word { .. },
-                        ..
-                    } => {
-                        return ValidationResult::with_warning(LintWarning::PotentialTypo {
+            match first_term {
+                Expression::Term {
+                    term: Term::Word { .. },
+                    ..
+                } => {
+                    return ValidationResult::with_warning(LintWarning::SingleTermProximity {
                             span: span.clone(),
-                            suggestion: "Single term tilde may produce unexpected fuzzy matching results. Consider using quoted phrases for proximity: \"term1 term2\"~5".to_string(),
+                            message: "Single term tilde may produce unexpected fuzzy matching results. Consider using quoted phrases for proximity: \"term1 term2\"~5".to_string(),
                         });
-                    }
-                    Expression::Term {
-                        term: Term::Phrase { value },
-                        ..
-                    } => {
-                        let words: Vec<&str> = value.split_whitespace().collect();
-                        if words.len() == 1 {
-                            return ValidationResult::with_warning(LintWarning::PotentialTypo {
+                }
+                Expression::Term {
+                    term: Term::Phrase { value },
+                    ..
+                } => {
+                    let words: Vec<&str> = value.split_whitespace().collect();
+                    if words.len() == 1 {
+                        return ValidationResult::with_warning(LintWarning::SingleTermProximity {
                                 span: span.clone(),
-                                suggestion: "Tilde operator on single quoted words has no effect. Use unquoted word or multi-word phrase.".to_string(),
+                                message: "Tilde operator on single quoted words has no effect. Use unquoted word or multi-word phrase.".to_string(),
                             });
-                        }
                     }
-                    _ => {}
                 }
+                _ => {}
             }
         }
         ValidationResult::new()
@@ -361,3 +497,129 @@ impl ValidationRule for TildeUsageRule {
         )
     }
 }
+
+/// the longest word Brandwatch matches case-sensitively
+pub const MAX_CASE_SENSITIVE_CHARS: usize = 20;
+
+pub struct CaseSensitiveRule;
+
+impl ValidationRule for CaseSensitiveRule {
+    fn name(&self) -> &'static str {
+        "case-sensitive"
+    }
+
+    fn validate(&self, expr: &Expression, _ctx: &ValidationContext) -> ValidationResult {
+        match expr {
+            Expression::Term {
+                term: Term::CaseSensitive { value },
+                span,
+            } => Self::validate_braces(value, span),
+            Expression::Term {
+                term: Term::Phrase { value },
+                span,
+            } if value.contains('{') && value.contains('}') => {
+                ValidationResult::with_warning(LintWarning::IneffectiveCaseSensitive {
+                    span: span.clone(),
+                    message: "Case-sensitive braces don't work inside quoted phrases; use {} around the whole phrase instead, e.g. {\"BBC News\"}".to_string(),
+                    fix: None,
+                })
+            }
+            _ => ValidationResult::new(),
+        }
+    }
+
+    fn can_validate(&self, expr: &Expression) -> bool {
+        matches!(
+            expr,
+            Expression::Term {
+                term: Term::CaseSensitive { .. } | Term::Phrase { .. },
+                ..
+            }
+        )
+    }
+}
+
+impl CaseSensitiveRule {
+    fn validate_braces(value: &str, span: &Span) -> ValidationResult {
+        if value.trim().is_empty() {
+            return ValidationResult::with_error(LintError::EmptyTerm {
+                span: span.clone(),
+                message: "Case-sensitive braces cannot be empty".to_string(),
+            });
+        }
+
+        let error = |message: String| LintError::CaseSensitiveError {
+            span: span.clone(),
+            message,
+        };
+        let words: Vec<&str> = value.split_whitespace().collect();
+        let mut result = ValidationResult::new();
+
+        if value.contains(['{', '}']) {
+            result.errors.push(error(
+                "Case-sensitive braces can't be nested; use a single pair, e.g. {BBC}".to_string(),
+            ));
+        }
+        if let Some(operator) = words.iter().find(|word| is_operator(word)) {
+            result.errors.push(error(format!(
+                "Operators can't be used inside case-sensitive braces, but found '{operator}'; put each term in its own braces, e.g. {{BBC}} OR {{ITV}}"
+            )));
+        }
+        if value.contains(['*', '?']) {
+            result.errors.push(error(
+                "Wildcards and replacement characters can't be used inside case-sensitive braces"
+                    .to_string(),
+            ));
+        }
+        if let Some(word) = words
+            .iter()
+            .map(|word| word.trim_matches('"'))
+            .find(|word| word.chars().count() > MAX_CASE_SENSITIVE_CHARS)
+        {
+            result.errors.push(error(format!(
+                "Case-sensitive matching only works for words of up to {MAX_CASE_SENSITIVE_CHARS} characters, but '{word}' has {}",
+                word.chars().count()
+            )));
+        }
+
+        if result.errors.is_empty() && !value.chars().any(char::is_uppercase) {
+            result.warnings.push(LintWarning::IneffectiveCaseSensitive {
+                span: span.clone(),
+                message: "Case-sensitive braces have no effect on a term without uppercase letters"
+                    .to_string(),
+                fix: Self::remove_braces(value, span),
+            });
+        }
+
+        result
+    }
+
+    /// delete the braces, unless that would split a sequence of words into separate terms
+    fn remove_braces(value: &str, span: &Span) -> Option<Box<Fix>> {
+        let quoted = value.len() > 1 && value.starts_with('"') && value.ends_with('"');
+        if value.contains(char::is_whitespace) && !quoted {
+            return None;
+        }
+
+        let (start, end) = (&span.start, &span.end);
+        Some(Box::new(Fix::safe(
+            "Remove the braces",
+            vec![
+                Edit::deletion(Span::new(
+                    start.clone(),
+                    Position::new(start.line, start.column + 1, start.offset + 1),
+                )),
+                Edit::deletion(Span::new(
+                    Position::new(end.line, end.column - 1, end.offset - 1),
+                    end.clone(),
+                )),
+            ],
+        )))
+    }
+}
+
+fn is_operator(word: &str) -> bool {
+    matches!(word, "AND" | "OR" | "NOT")
+        || word.starts_with("NEAR/")
+        || word.contains(['~', ':', '(', ')', '[', ']'])
+}
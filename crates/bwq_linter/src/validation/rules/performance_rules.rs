@@ -1,5 +1,6 @@
 use crate::ast::*;
-use crate::error::{LintError, LintWarning};
+use crate::error::{LintError, LintWarning, Span};
+use crate::metrics::WildcardStem;
 use crate::validation::{ValidationContext, ValidationResult, ValidationRule};
 
 pub struct WildcardPerformanceRule;
@@ -15,20 +16,14 @@ impl ValidationRule for WildcardPerformanceRule {
                 Term::Wildcard { value } => {
                     let mut result = ValidationResult::new();
 
-                    if value.starts_with('*') {
-                        result
+                    match WildcardStem::of(value) {
+                        WildcardStem::Leading => result
                             .errors
-                            .push(LintError::InvalidWildcardPlacement { span: span.clone() });
-                    }
-
-                    let parts: Vec<&str> = value.split('*').collect();
-                    if let Some(first_part) = parts.first() {
-                        if !first_part.is_empty() && first_part.len() == 1 && value.ends_with('*') {
-                            result.errors.push(LintError::ValidationError {
-                                            span: span.clone(),
-                                            message: "This wildcard matches too many unique terms. Please make it more specific.".to_string(),
-                                        });
-                        }
+                            .push(LintError::InvalidWildcardPlacement { span: span.clone() }),
+                        WildcardStem::Single if value.ends_with('*') => result
+                            .errors
+                            .push(LintError::BroadWildcard { span: span.clone() }),
+                        _ => {}
                     }
 
                     result
@@ -79,7 +74,7 @@ impl ValidationRule for ShortTermRule {
 
                         // Check for empty terms
                         if value.trim().is_empty() {
-                            result.errors.push(LintError::ValidationError {
+                            result.errors.push(LintError::EmptyTerm {
                                 span: span.clone(),
                                 message: "Word cannot be empty".to_string(),
                             });
@@ -89,7 +84,7 @@ impl ValidationRule for ShortTermRule {
                     }
                     Term::Phrase { value } => {
                         if value.trim().is_empty() {
-                            ValidationResult::with_error(LintError::ValidationError {
+                            ValidationResult::with_error(LintError::EmptyTerm {
                                 span: span.clone(),
                                 message: "Quoted phrase cannot be empty".to_string(),
                             })
@@ -99,7 +94,7 @@ impl ValidationRule for ShortTermRule {
                     }
                     Term::Hashtag { value } => {
                         if value.trim().is_empty() {
-                            ValidationResult::with_error(LintError::ValidationError {
+                            ValidationResult::with_error(LintError::EmptyTerm {
                                 span: span.clone(),
                                 message: "Hashtag cannot be empty".to_string(),
                             })
@@ -114,7 +109,7 @@ impl ValidationRule for ShortTermRule {
                     }
                     Term::Mention { value } => {
                         if value.trim().is_empty() {
-                            ValidationResult::with_error(LintError::ValidationError {
+                            ValidationResult::with_error(LintError::EmptyTerm {
                                 span: span.clone(),
                                 message: "Mention cannot be empty".to_string(),
                             })
@@ -140,6 +135,102 @@ impl ValidationRule for ShortTermRule {
     }
 }
 
+/// Brandwatch's cap on the number of short wildcards in a query
+pub const SHORT_WILDCARD_LIMIT: usize = 150;
+/// the number of short wildcards at which the cap is reported as near
+pub const SHORT_WILDCARD_WARNING: usize = 120;
+
+/// counts short wildcards across the whole query, and flags wildcards that Brandwatch won't
+/// expand
+pub struct ShortWildcardRule;
+
+impl ShortWildcardRule {
+    /// a warning at the first short wildcard past the warning threshold or, once there are too
+    /// many, past the limit
+    pub fn check_query(&self, query: &Query) -> ValidationResult {
+        let mut spans = Vec::new();
+        collect_short_wildcards(&query.expression, &mut spans);
+
+        let threshold = if spans.len() > SHORT_WILDCARD_LIMIT {
+            SHORT_WILDCARD_LIMIT
+        } else {
+            SHORT_WILDCARD_WARNING - 1
+        };
+        match spans.get(threshold) {
+            Some(span) => ValidationResult::with_warning(LintWarning::ShortWildcardBudget {
+                span: span.clone(),
+                count: spans.len(),
+                limit: SHORT_WILDCARD_LIMIT,
+            }),
+            None => ValidationResult::new(),
+        }
+    }
+}
+
+impl ValidationRule for ShortWildcardRule {
+    fn name(&self) -> &'static str {
+        "short-wildcard"
+    }
+
+    fn validate(&self, expr: &Expression, _ctx: &ValidationContext) -> ValidationResult {
+        let Expression::Term { term, span } = expr else {
+            return ValidationResult::new();
+        };
+
+        let message = match term {
+            Term::Phrase { value } if value.contains('*') => {
+                "Wildcards aren't expanded inside quoted phrases. Use NEAR/x to combine a wildcard with other words"
+            }
+            // a leading wildcard is reported by the short-term rule
+            Term::Hashtag { value } if value.contains('*') && !value.starts_with('*') => {
+                "Wildcards aren't expanded in hashtags"
+            }
+            Term::Mention { value } if value.contains('*') && !value.starts_with('*') => {
+                "Wildcards aren't expanded in mentions"
+            }
+            _ => return ValidationResult::new(),
+        };
+
+        ValidationResult::with_warning(LintWarning::UnsupportedWildcard {
+            span: span.clone(),
+            message: message.to_string(),
+        })
+    }
+
+    fn can_validate(&self, expr: &Expression) -> bool {
+        matches!(
+            expr,
+            Expression::Term {
+                term: Term::Phrase { .. } | Term::Hashtag { .. } | Term::Mention { .. },
+                ..
+            }
+        )
+    }
+}
+
+fn collect_short_wildcards(expr: &Expression, spans: &mut Vec<Span>) {
+    match expr {
+        Expression::BooleanOp { left, right, .. } => {
+            collect_short_wildcards(left, spans);
+            if let Some(right) = right {
+                collect_short_wildcards(right, spans);
+            }
+        }
+        Expression::Group { expression, .. } => collect_short_wildcards(expression, spans),
+        Expression::Proximity { terms, .. } => {
+            for term in terms {
+                collect_short_wildcards(term, spans);
+            }
+        }
+        Expression::Field { value, .. } => collect_short_wildcards(value, spans),
+        Expression::Term {
+            term: Term::Wildcard { value },
+            span,
+        } if WildcardStem::of(value) == WildcardStem::Short => spans.push(span.clone()),
+        Expression::Range { .. } | Expression::Term { .. } | Expression::Error { .. } => {}
+    }
+}
+
 pub struct RangePerformanceRule;
 
 impl ValidationRule for RangePerformanceRule {
@@ -162,6 +253,7 @@ impl ValidationRule for RangePerformanceRule {
                     result.errors.push(LintError::RangeValidationError {
                         span: span.clone(),
                         message: "Follower counts cannot be negative".to_string(),
+                        fix: None,
                     });
                 }
 
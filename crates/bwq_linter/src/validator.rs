@@ -1,18 +1,30 @@
 use crate::ast::*;
+use crate::config::RuleSettings;
 use crate::error::{LintError, LintReport};
-use crate::validation::{ValidationEngine, rules::PureNegativeRule};
+use crate::validation::{
+    ValidationEngine, ValidationResult, ValidationRule,
+    rules::{PureNegativeRule, ShortWildcardRule},
+};
 
 /// plugin-based query-level validator
 pub struct Validator {
     engine: ValidationEngine,
     pure_negative_rule: PureNegativeRule,
+    short_wildcard_rule: ShortWildcardRule,
+    settings: RuleSettings,
 }
 
 impl Validator {
     pub fn new() -> Self {
+        Self::with_settings(RuleSettings::default())
+    }
+
+    pub fn with_settings(settings: RuleSettings) -> Self {
         Self {
-            engine: ValidationEngine::new(),
+            engine: ValidationEngine::with_settings(settings.clone()),
             pure_negative_rule: PureNegativeRule,
+            short_wildcard_rule: ShortWildcardRule,
+            settings,
         }
     }
 
@@ -23,12 +35,25 @@ impl Validator {
             .pure_negative_rule
             .is_pure_negative_query(&query.expression)
         {
-            report.errors.push(LintError::PureNegativeQueryError {
+            let result = ValidationResult::with_error(LintError::PureNegativeQueryError {
                 span: query.span.clone(),
                 message: "Queries must contain at least one non-excluded term".to_string(),
             });
+            let result = self
+                .settings
+                .apply(result, Some(self.pure_negative_rule.name()));
+            report.errors.extend(result.errors);
+            report.warnings.extend(result.warnings);
         }
 
+        // short wildcards are capped per query, so they're counted across the whole tree
+        let result = self.settings.apply(
+            self.short_wildcard_rule.check_query(query),
+            Some(self.short_wildcard_rule.name()),
+        );
+        report.errors.extend(result.errors);
+        report.warnings.extend(result.warnings);
+
         report
     }
 }
@@ -49,8 +74,8 @@ mod tests {
     fn test_wildcard_validation() {
         let mut lexer = Lexer::new("valid*");
         let tokens = lexer.tokenize().unwrap();
-        let mut parser = Parser::new(tokens).unwrap();
-        let result = parser.parse().unwrap();
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse();
 
         let mut validator = Validator::new();
         let report = validator.validate(&result.query);
@@ -62,8 +87,8 @@ mod tests {
     fn test_rating_validation() {
         let mut lexer = Lexer::new("rating:6");
         let tokens = lexer.tokenize().unwrap();
-        let mut parser = Parser::new(tokens).unwrap();
-        let result = parser.parse().unwrap();
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse();
 
         let mut validator = Validator::new();
         let report = validator.validate(&result.query);
@@ -75,8 +100,8 @@ mod tests {
     fn test_valid_query() {
         let mut lexer = Lexer::new("apple AND juice");
         let tokens = lexer.tokenize().unwrap();
-        let mut parser = Parser::new(tokens).unwrap();
-        let result = parser.parse().unwrap();
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse();
 
         let mut validator = Validator::new();
         let report = validator.validate(&result.query);
@@ -88,8 +113,8 @@ mod tests {
     fn test_mixed_and_or_validation() {
         let mut lexer = Lexer::new("apple AND banana OR juice");
         let tokens = lexer.tokenize().unwrap();
-        let mut parser = Parser::new(tokens).unwrap();
-        let result = parser.parse().unwrap();
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse();
 
         let mut validator = Validator::new();
         let report = validator.validate(&result.query);
@@ -102,8 +127,8 @@ mod tests {
     fn test_pure_negative_query() {
         let mut lexer = Lexer::new("NOT bitter");
         let tokens = lexer.tokenize().unwrap();
-        let mut parser = Parser::new(tokens).unwrap();
-        let result = parser.parse().unwrap();
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse();
 
         let mut validator = Validator::new();
         let report = validator.validate(&result.query);
@@ -116,36 +141,36 @@ mod tests {
     fn test_coordinate_validation() {
         let mut lexer = Lexer::new("latitude:100");
         let tokens = lexer.tokenize().unwrap();
-        let mut parser = Parser::new(tokens).unwrap();
-        let result = parser.parse().unwrap();
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse();
 
         let mut validator = Validator::new();
         let report = validator.validate(&result.query);
 
         assert!(report.has_errors());
-        assert!(report.errors.iter().any(|e| e.code() == "E012"));
+        assert!(report.errors.iter().any(|e| e.code() == "E024"));
     }
 
     #[test]
     fn test_boolean_field_validation() {
         let mut lexer = Lexer::new("authorVerified:yes");
         let tokens = lexer.tokenize().unwrap();
-        let mut parser = Parser::new(tokens).unwrap();
-        let result = parser.parse().unwrap();
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse();
 
         let mut validator = Validator::new();
         let report = validator.validate(&result.query);
 
         assert!(report.has_errors());
-        assert!(report.errors.iter().any(|e| e.code() == "E012"));
+        assert!(report.errors.iter().any(|e| e.code() == "E025"));
     }
 
     #[test]
     fn test_wildcard_placement_validation() {
         let mut lexer = Lexer::new("*invalid");
         let tokens = lexer.tokenize().unwrap();
-        let mut parser = Parser::new(tokens).unwrap();
-        let result = parser.parse().unwrap();
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse();
 
         let mut validator = Validator::new();
         let report = validator.validate(&result.query);
@@ -158,8 +183,8 @@ mod tests {
     fn test_performance_warnings() {
         let mut lexer = Lexer::new("#*test");
         let tokens = lexer.tokenize().unwrap();
-        let mut parser = Parser::new(tokens).unwrap();
-        let result = parser.parse().unwrap();
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse();
         let mut validator = Validator::new();
         let report = validator.validate(&result.query);
         assert!(!report.warnings.is_empty());
@@ -168,10 +193,48 @@ mod tests {
         // Test that wildcards with characters after are valid
         let mut lexer = Lexer::new("t*est");
         let tokens = lexer.tokenize().unwrap();
-        let mut parser = Parser::new(tokens).unwrap();
-        let result = parser.parse().unwrap();
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse();
         let mut validator = Validator::new();
         let report = validator.validate(&result.query);
         assert!(report.is_clean());
     }
+
+    #[test]
+    fn test_short_wildcard_budget() {
+        let codes = |count: usize| {
+            let stems: Vec<String> = (0..count)
+                .map(|i| {
+                    format!(
+                        "{}{}*",
+                        (b'a' + (i / 26) as u8) as char,
+                        (b'a' + (i % 26) as u8) as char
+                    )
+                })
+                .collect();
+            let tokens = Lexer::new(&stems.join(" OR ")).tokenize().unwrap();
+            let result = Parser::new(tokens).parse();
+            let report = Validator::new().validate(&result.query);
+            report
+                .warnings
+                .iter()
+                .filter(|w| w.code() == "W009")
+                .map(|w| w.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert!(codes(119).is_empty());
+        assert_eq!(
+            codes(120),
+            vec![
+                "Query has 120 short wildcards (two-character stems like 'ab*'), close to Brandwatch's limit of 150"
+            ]
+        );
+        assert_eq!(
+            codes(151),
+            vec![
+                "Query has 151 short wildcards (two-character stems like 'ab*'), more than Brandwatch's limit of 150"
+            ]
+        );
+    }
 }
@@ -24,84 +24,66 @@ impl QueryTest {
     }
 
     pub fn assert_valid(&mut self, query: &str) -> &LintReport {
-        match self.linter.lint(query) {
-            Ok(report) => {
-                assert!(
-                    !report.has_errors(),
-                    "Expected query to be valid but found errors: {} - {:?}",
-                    query,
-                    report.errors
-                );
-                // Store the report and return a reference to it
-                self.last_report = Some(report);
-                self.last_report.as_ref().unwrap()
-            }
-            Err(error) => {
-                panic!("Expected query to be valid but got parse error: {query} - {error}");
-            }
-        }
+        let report = self.linter.lint(query);
+        assert!(
+            !report.has_errors(),
+            "Expected query to be valid but found errors: {} - {:?}",
+            query,
+            report.errors
+        );
+        // Store the report and return a reference to it
+        self.last_report = Some(report);
+        self.last_report.as_ref().unwrap()
     }
 
     /// Assert query is valid but has warnings
     pub fn assert_valid_with_warning(&mut self, query: &str) -> &LintReport {
-        match self.linter.lint(query) {
-            Ok(report) => {
-                assert!(
-                    !report.has_errors(),
-                    "Expected query to be valid but found errors: {} - {:?}",
-                    query,
-                    report.errors
-                );
-                assert!(
-                    report.has_warnings(),
-                    "Expected query to have warnings: {query}"
-                );
-                // Store the report and return a reference to it
-                self.last_report = Some(report);
-                self.last_report.as_ref().unwrap()
-            }
-            Err(error) => {
-                panic!("Expected query to be valid but got parse error: {query} - {error}");
-            }
-        }
+        let report = self.linter.lint(query);
+        assert!(
+            !report.has_errors(),
+            "Expected query to be valid but found errors: {} - {:?}",
+            query,
+            report.errors
+        );
+        assert!(
+            report.has_warnings(),
+            "Expected query to have warnings: {query}"
+        );
+        // Store the report and return a reference to it
+        self.last_report = Some(report);
+        self.last_report.as_ref().unwrap()
     }
 
     /// Assert query has error with specific code
     pub fn assert_error_code(&mut self, query: &str, expected_code: &str) {
-        match self.linter.lint(query) {
-            Ok(report) => {
-                assert!(
-                    report.has_errors(),
-                    "Expected query to have errors: {query}"
-                );
-                assert!(
-                    report.errors.iter().any(|e| e.code() == expected_code),
-                    "Expected error with code '{}' for query: {}, but got errors: {:?}",
-                    expected_code,
-                    query,
-                    report.errors.iter().map(|e| e.code()).collect::<Vec<_>>()
-                );
-            }
-            Err(error) => {
-                // For parse/lex errors, check the error directly
-                assert_eq!(
-                    error.code(),
-                    expected_code,
-                    "Expected error code '{}' for query: {}, but got: {}",
-                    expected_code,
-                    query,
-                    error.code()
-                );
-            }
-        }
+        let report = self.linter.lint(query);
+        assert!(
+            report.has_errors(),
+            "Expected query to have errors: {query}"
+        );
+        assert!(
+            report.errors.iter().any(|e| e.code() == expected_code),
+            "Expected error with code '{}' for query: {}, but got errors: {:?}",
+            expected_code,
+            query,
+            report.errors.iter().map(|e| e.code()).collect::<Vec<_>>()
+        );
+    }
+
+    /// Assert query reports exactly these error codes, in order
+    pub fn assert_error_codes(&mut self, query: &str, expected_codes: &[&str]) {
+        let report = self.linter.lint(query);
+        assert_eq!(
+            report.errors.iter().map(|e| e.code()).collect::<Vec<_>>(),
+            expected_codes,
+            "Unexpected errors for query: {query} - {:?}",
+            report.errors
+        );
     }
 
     /// Assert query has warning with specific code
     pub fn assert_warning_code(&mut self, query: &str, expected_code: &str) {
-        let report = self
-            .linter
-            .lint(query)
-            .expect("Query should parse successfully");
+        let report = self.linter.lint(query);
         assert!(
             !report.warnings.is_empty(),
             "Expected query to have warnings: {query}"
@@ -117,10 +99,7 @@ impl QueryTest {
 
     /// Assert query has no warnings
     pub fn assert_no_warnings(&mut self, query: &str) {
-        let report = self
-            .linter
-            .lint(query)
-            .expect("Query should parse successfully");
+        let report = self.linter.lint(query);
         assert!(
             report.warnings.is_empty(),
             "Expected no warnings for query: {}, but got: {:?}",
@@ -259,8 +238,11 @@ fn test_quoted_phrase_syntax(query: &str, expected: TestExpectation) {
 #[test_case("#*test", TestExpectation::ValidWithWarning("W003"); "wildcard after hashtag prefix performance warning")]
 #[test_case("@*test", TestExpectation::ValidWithWarning("W003"); "wildcard after @ prefix performance warning")]
 #[test_case("*invalid", TestExpectation::ErrorCode("E006"); "invalid wildcard at beginning")]
-#[test_case("a*", TestExpectation::ErrorCode("E003"); "short wildcard matches too many unique terms")]
+#[test_case("a*", TestExpectation::ErrorCode("E020"); "short wildcard matches too many unique terms")]
 #[test_case("t*est", TestExpectation::ValidNoWarnings; "wildcard in middle with characters after")]
+#[test_case("\"apple juic*\"", TestExpectation::ValidWithWarning("W010"); "wildcard inside quoted phrase")]
+#[test_case("#apple*", TestExpectation::ValidWithWarning("W010"); "wildcard attached to hashtag")]
+#[test_case("@brand*", TestExpectation::ValidWithWarning("W010"); "wildcard attached to mention")]
 fn test_wildcard_syntax(query: &str, expected: TestExpectation) {
     let mut test = QueryTest::new();
     expected.assert(&mut test, query);
@@ -281,14 +263,14 @@ fn test_near_proximity_operator_syntax(query: &str, expected: TestExpectation) {
 #[test_case("((tech OR technology) AND innovation)~7", TestExpectation::ValidNoWarnings; "nested boolean group with tilde")]
 #[test_case("\"apple juice\"~5 AND test", TestExpectation::ValidNoWarnings; "quoted tilde with boolean AND")]
 #[test_case("(apple AND juice)~2 AND test", TestExpectation::ValidNoWarnings; "grouped terms with tilde and AND")]
-#[test_case("apple~5", TestExpectation::ValidWithWarning("W001"); "single term with tilde warning")]
-#[test_case("\"apple\"~5", TestExpectation::ValidWithWarning("W001"); "single quoted word with tilde warning")]
+#[test_case("apple~5", TestExpectation::ValidWithWarning("W005"); "single term with tilde warning")]
+#[test_case("\"apple\"~5", TestExpectation::ValidWithWarning("W005"); "single quoted word with tilde warning")]
 #[test_case("apple~5 juice", TestExpectation::ValidWithWarning("W001"); "tilde with implicit AND warning")]
-#[test_case("\"apple juice\"~", TestExpectation::ErrorCode("E003"); "tilde without distance number on phrase")]
-#[test_case("apple~", TestExpectation::ErrorCode("E003"); "tilde without distance number on term")]
-#[test_case("apple ~ juice", TestExpectation::ErrorCode("E003"); "tilde with spaces")]
-#[test_case("apple~ 5", TestExpectation::ErrorCode("E003"); "space between tilde and number")]
-#[test_case("apple ~5", TestExpectation::ErrorCode("E003"); "space before tilde")]
+#[test_case("\"apple juice\"~", TestExpectation::ErrorCode("E019"); "tilde without distance number on phrase")]
+#[test_case("apple~", TestExpectation::ErrorCode("E019"); "tilde without distance number on term")]
+#[test_case("apple ~ juice", TestExpectation::ErrorCode("E019"); "tilde with spaces")]
+#[test_case("apple~ 5", TestExpectation::ErrorCode("E019"); "space between tilde and number")]
+#[test_case("apple ~5", TestExpectation::ErrorCode("E019"); "space before tilde")]
 #[test_case("apple~5t", TestExpectation::ErrorCode("E001"); "invalid characters after number")]
 fn test_tilde_proximity_syntax(query: &str, expected: TestExpectation) {
     let mut test = QueryTest::new();
@@ -307,6 +289,20 @@ fn test_case_sensitive_matching() {
     test.assert_valid_no_warnings("{Brand Watch}");
 }
 
+#[test_case("{}", TestExpectation::ErrorCode("E021"); "empty braces")]
+#[test_case("{ }", TestExpectation::ErrorCode("E021"); "braces with only whitespace")]
+#[test_case("{{BBC}}", TestExpectation::ErrorCode("E023"); "nested braces")]
+#[test_case("{BBC OR ITV}", TestExpectation::ErrorCode("E023"); "operator inside braces")]
+#[test_case("{BB*}", TestExpectation::ErrorCode("E023"); "wildcard inside braces")]
+#[test_case("{SupercalifragilisticX}", TestExpectation::ErrorCode("E023"); "word longer than 20 characters")]
+#[test_case("{apple}", TestExpectation::ValidWithWarning("W011"); "no uppercase letters")]
+#[test_case("\"{BBC} news\"", TestExpectation::ValidWithWarning("W011"); "braces inside quoted phrase")]
+#[test_case("{\"BBC News\"}", TestExpectation::ValidNoWarnings; "quoted phrase inside braces")]
+fn test_case_sensitive_validation(query: &str, expected: TestExpectation) {
+    let mut test = QueryTest::new();
+    expected.assert(&mut test, query);
+}
+
 #[test]
 fn test_comments() {
     let mut test = QueryTest::new();
@@ -336,6 +332,53 @@ fn test_invalid_query_patterns(query: &str, expected: TestExpectation) {
     expected.assert(&mut test, query);
 }
 
+#[test]
+fn test_domain_values_are_reported_once() {
+    let mut test = QueryTest::new();
+
+    test.assert_error_codes("site:www.", &["E030"]);
+    test.assert_error_codes("site:https://www.bbc.co.uk/", &["E028"]);
+    test.assert_error_codes("site:(www.bbc.com OR bbc)", &["E031", "E030"]);
+}
+
+#[test]
+fn test_error_recovery_reports_every_problem() {
+    let mut test = QueryTest::new();
+
+    // syntax errors in separate alternatives are all reported
+    test.assert_error_codes(
+        "(apple AND) OR (juice OR ]) OR rating:[1 TO]",
+        &["E010", "E010", "E011"],
+    );
+
+    // an unmatched closing paren doesn't hide what follows it
+    test.assert_error_codes("apple) AND rating:6", &["E010", "E012"]);
+    test.assert_error_codes(") apple", &["E010"]);
+    test.assert_error_codes("author:)", &["E010"]);
+
+    // groups left open together are reported once, where they stop
+    test.assert_error_codes("(((", &["E010", "E011"]);
+    test.assert_error_codes("((apple AND juice", &["E011"]);
+    test.assert_error_codes("((apple) AND (juice", &["E011"]);
+
+    // validation still runs over the parts that parsed
+    test.assert_error_codes(
+        "apple AND juice OR orange \"unterminated",
+        &["E022", "E015"],
+    );
+
+    // operators next to a syntax error aren't reported as mixed
+    test.assert_error_codes("a AND OR b", &["E010"]);
+    test.assert_error_codes(") apple AND OR banana (", &["E010", "E010", "E010", "E011"]);
+
+    // lexer errors don't stop tokenizing
+    test.assert_error_codes("apple~5t AND juice ! rating:7", &["E001", "E001", "E012"]);
+
+    // unclosed groups and comments are reported once
+    test.assert_error_codes("(apple OR juice", &["E011"]);
+    test.assert_error_codes("apple <<<unclosed comment", &["E003"]);
+}
+
 #[test]
 fn test_basic_field_operators() {
     let mut test = QueryTest::new();
@@ -374,8 +417,26 @@ fn test_unicode_and_special_characters(query: &str, expected: TestExpectation) {
 #[test_case("https:w/ww.youtube.com/", TestExpectation::ValidNoWarnings; "slash in middle")]
 #[test_case("https:/www.youtube.com/", TestExpectation::ValidNoWarnings; "double slash missing one")]
 #[test_case("https://www.youtube.com/", TestExpectation::ValidNoWarnings; "full URL format")]
-#[test_case("site:reddit.com/r/programming", TestExpectation::ValidNoWarnings; "site operator with path")]
+#[test_case("site:reddit.com/r/programming", TestExpectation::ErrorCode("E028"); "site operator with path")]
 #[test_case("url:example.com/path/to/page", TestExpectation::ValidNoWarnings; "url with path")]
+#[test_case("site:https://twitter.com", TestExpectation::ErrorCode("E028"); "site with scheme")]
+#[test_case("site:twitter.com/", TestExpectation::ErrorCode("E028"); "site with trailing slash")]
+#[test_case("site:(bbc.co.uk OR \"www.bbc.com\")", TestExpectation::ErrorCode("E031"); "www in grouped site values")]
+#[test_case("url:\"http://msn.com/news\"", TestExpectation::ErrorCode("E028"); "url with scheme")]
+#[test_case("url:msn.com/news", TestExpectation::ValidNoWarnings; "url with path and no scheme")]
+#[test_case("links:msn..com", TestExpectation::ErrorCode("E028"); "links with malformed hostname")]
+#[test_case("links:www.msn.com", TestExpectation::ValidNoWarnings; "www is only flagged for site")]
+#[test_case("site:bbc_news.com", TestExpectation::ErrorCode("E029"); "site with underscore in hostname")]
+#[test_case("site:-bbc.com", TestExpectation::ErrorCode("E029"); "site with leading hyphen")]
+#[test_case("site:(bbc.co.uk OR bbc-.com)", TestExpectation::ErrorCode("E029"); "grouped site with trailing hyphen")]
+#[test_case("links:msn+news.com", TestExpectation::ErrorCode("E029"); "links with invalid hostname")]
+#[test_case("site:bbc", TestExpectation::ErrorCode("E030"); "site without a dot")]
+#[test_case("site:.", TestExpectation::ErrorCode("E028"); "site with only a dot")]
+#[test_case("site:\"...\"", TestExpectation::ErrorCode("E028"); "site with only dots")]
+#[test_case("site:bbc-news.co.uk", TestExpectation::ValidNoWarnings; "site with hyphen in hostname")]
+#[test_case("url:localhost/status", TestExpectation::ValidNoWarnings; "url without a dot")]
+#[test_case("topLevelDomain:co.uk", TestExpectation::ErrorCode("E028"); "top-level domain with dots")]
+#[test_case("topLevelDomain:uk", TestExpectation::ValidNoWarnings; "top-level domain")]
 fn test_url_like_strings(query: &str, expected: TestExpectation) {
     let mut test = QueryTest::new();
     expected.assert(&mut test, query);
@@ -399,8 +460,8 @@ fn test_rating_field_validation(query: &str, expected: TestExpectation) {
 #[test_case("latitude:[40 TO 42]", TestExpectation::ValidNoWarnings; "valid latitude range")]
 #[test_case("longitude:[-73 TO -69]", TestExpectation::ValidNoWarnings; "valid longitude range")]
 #[test_case("continent:europe", TestExpectation::ValidNoWarnings; "valid continent")]
-#[test_case("latitude:[100 TO 110]", TestExpectation::ErrorCode("E012"); "latitude out of range")]
-#[test_case("longitude:[-200 TO -150]", TestExpectation::ErrorCode("E012"); "longitude out of range")]
+#[test_case("latitude:[100 TO 110]", TestExpectation::ErrorCode("E024"); "latitude out of range")]
+#[test_case("longitude:[-200 TO -150]", TestExpectation::ErrorCode("E024"); "longitude out of range")]
 fn test_coordinate_field_validation(query: &str, expected: TestExpectation) {
     let mut test = QueryTest::new();
     expected.assert(&mut test, query);
@@ -408,8 +469,9 @@ fn test_coordinate_field_validation(query: &str, expected: TestExpectation) {
 
 #[test_case("authorVerified:true", TestExpectation::ValidNoWarnings; "valid boolean true")]
 #[test_case("authorVerified:false", TestExpectation::ValidNoWarnings; "valid boolean false")]
-#[test_case("authorVerified:yes", TestExpectation::ErrorCode("E012"); "invalid boolean yes")]
-#[test_case("authorVerified:1", TestExpectation::ErrorCode("E012"); "invalid boolean number")]
+#[test_case("authorverified:true", TestExpectation::ValidWithWarning("W007"); "lowercase field name warning")]
+#[test_case("authorVerified:yes", TestExpectation::ErrorCode("E025"); "invalid boolean yes")]
+#[test_case("authorVerified:1", TestExpectation::ErrorCode("E025"); "invalid boolean number")]
 fn test_boolean_field_validation(query: &str, expected: TestExpectation) {
     let mut test = QueryTest::new();
     expected.assert(&mut test, query);
@@ -417,8 +479,14 @@ fn test_boolean_field_validation(query: &str, expected: TestExpectation) {
 
 #[test_case("language:en", TestExpectation::ValidNoWarnings; "valid 2-char language code")]
 #[test_case("language:fr", TestExpectation::ValidNoWarnings; "valid french language code")]
-#[test_case("language:ENG", TestExpectation::ValidWithWarning("W001"); "uppercase language code warning")]
-#[test_case("language:english", TestExpectation::ValidWithWarning("W001"); "full language name warning")]
+#[test_case("language:ENG", TestExpectation::ValidWithWarning("W006"); "uppercase language code warning")]
+#[test_case("language:english", TestExpectation::ValidWithWarning("W006"); "full language name warning")]
+#[test_case("language:EN", TestExpectation::ValidWithWarning("W006"); "uppercase 2-char language code warning")]
+#[test_case("language:xx", TestExpectation::ValidWithWarning("W006"); "unknown language code warning")]
+#[test_case("language:zz", TestExpectation::ValidWithWarning("W006"); "unassigned language code warning")]
+#[test_case("language:(fr OR xx)", TestExpectation::ValidWithWarning("W006"); "unknown language code in a group warning")]
+#[test_case("language:(fr OR en)", TestExpectation::ValidNoWarnings; "grouped language codes")]
+#[test_case("language:iw", TestExpectation::ValidNoWarnings; "legacy hebrew language code")]
 #[test_case("languag:e", TestExpectation::ValidNoWarnings; "invalid field operator is valid")]
 fn test_language_field_validation(query: &str, expected: TestExpectation) {
     let mut test = QueryTest::new();
@@ -438,7 +506,7 @@ fn test_engagement_type_field_validation(query: &str, expected: TestExpectation)
 #[test_case("authorVerifiedType:blue", TestExpectation::ValidNoWarnings; "valid verified type blue")]
 #[test_case("authorVerifiedType:business", TestExpectation::ValidNoWarnings; "valid verified type business")]
 #[test_case("authorVerifiedType:government", TestExpectation::ValidNoWarnings; "valid verified type government")]
-#[test_case("authorVerifiedType:gold", TestExpectation::ErrorCode("E012"); "invalid verified type gold")]
+#[test_case("authorVerifiedType:gold", TestExpectation::ErrorCode("E026"); "invalid verified type gold")]
 fn test_verified_type_field_validation(query: &str, expected: TestExpectation) {
     let mut test = QueryTest::new();
     expected.assert(&mut test, query);
@@ -446,8 +514,8 @@ fn test_verified_type_field_validation(query: &str, expected: TestExpectation) {
 
 #[test_case("minuteOfDay:[0 TO 1439]", TestExpectation::ValidNoWarnings; "valid minute of day full range")]
 #[test_case("minuteOfDay:[720 TO 780]", TestExpectation::ValidNoWarnings; "valid minute of day noon to 1pm")]
-#[test_case("minuteOfDay:[-1 TO 100]", TestExpectation::ErrorCode("E012"); "minute of day with negative")]
-#[test_case("minuteOfDay:[0 TO 1440]", TestExpectation::ErrorCode("E012"); "minute of day over max")]
+#[test_case("minuteOfDay:[-1 TO 100]", TestExpectation::ErrorCode("E027"); "minute of day with negative")]
+#[test_case("minuteOfDay:[0 TO 1440]", TestExpectation::ErrorCode("E027"); "minute of day over max")]
 fn test_minute_of_day_field_validation(query: &str, expected: TestExpectation) {
     let mut test = QueryTest::new();
     expected.assert(&mut test, query);
@@ -456,6 +524,26 @@ fn test_minute_of_day_field_validation(query: &str, expected: TestExpectation) {
 #[test_case("country:gbr", TestExpectation::ValidNoWarnings; "valid country code")]
 #[test_case("region:usa.fl", TestExpectation::ValidNoWarnings; "valid region code")]
 #[test_case("city:\"deu.berlin.berlin\"", TestExpectation::ValidNoWarnings; "valid city code")]
+#[test_case("country:(usa OR can)", TestExpectation::ValidNoWarnings; "valid grouped country codes")]
+#[test_case("country:uk", TestExpectation::ValidWithWarning("W012"); "country alias warning")]
+#[test_case("country:GBR", TestExpectation::ValidWithWarning("W012"); "uppercase country code warning")]
+#[test_case("country:xyz", TestExpectation::ValidWithWarning("W012"); "unknown country code warning")]
+#[test_case("continent:\"north america\"", TestExpectation::ValidNoWarnings; "quoted continent")]
+#[test_case("continent:north america", TestExpectation::ValidWithWarning("W012"); "unquoted multi-word continent warning")]
+#[test_case("continent:france", TestExpectation::ValidWithWarning("W012"); "country as continent warning")]
+#[test_case("region:usa.florida", TestExpectation::ValidWithWarning("W012"); "region name warning")]
+#[test_case("region:uk.scotland", TestExpectation::ValidWithWarning("W012"); "region with country alias warning")]
+#[test_case("region:fra.bretagne", TestExpectation::ValidNoWarnings; "region of country without listed regions")]
+#[test_case("region:usa", TestExpectation::ValidWithWarning("W012"); "region without region part warning")]
+#[test_case("city:\"gbr.england.newcastle upon tyne\"", TestExpectation::ValidNoWarnings; "quoted multi-word city")]
+#[test_case("city:gbr.england.newcastle upon tyne", TestExpectation::ValidWithWarning("W012"); "unquoted multi-word city warning")]
+#[test_case("city:gbr.eng.london", TestExpectation::ValidWithWarning("W012"); "city in unknown region warning")]
+#[test_case("city:gbr.scotland.london", TestExpectation::ValidNoWarnings; "unlisted city in a known region")]
+#[test_case("city:usa.me.portland", TestExpectation::ValidNoWarnings; "city sharing its name with another")]
+#[test_case("city:\"usa.ks.kansas city\"", TestExpectation::ValidNoWarnings; "quoted city sharing its name with another")]
+#[test_case("city:usa.nh.portland", TestExpectation::ValidNoWarnings; "unlisted city sharing a listed name")]
+#[test_case("city:usa.fl", TestExpectation::ValidWithWarning("W012"); "city without city part warning")]
+#[test_case("city:london", TestExpectation::ValidWithWarning("W012"); "bare city name warning")]
 fn test_location_field_validation(query: &str, expected: TestExpectation) {
     let mut test = QueryTest::new();
     expected.assert(&mut test, query);
@@ -506,9 +594,10 @@ fn test_implicit_and_behavior() {
     // Explicit AND should not generate warnings
     test.assert_valid_no_warnings("apple AND banana");
 
-    // Test case sensitivity - lowercase operators
-    test.assert_valid("apple and juice");
-    test.assert_warning_code("apple and juice", "W001");
+    // Lowercase operators are reported as E004 rather than as an implicit AND
+    test.assert_error_code("apple and juice", "E004");
+    test.assert_error_code("apple Or juice", "E004");
+    test.assert_valid_no_warnings("\"apple and juice\"");
 }
 
 #[test]
@@ -583,3 +672,23 @@ fn test_fixture_files(file_path: &str, expected: FileTestExpectation) {
     let mut test = QueryTest::new();
     expected.assert(&mut test, file_path);
 }
+
+#[test]
+fn test_suppression_comments() {
+    let mut linter = BrandwatchLinter::new();
+
+    let report = linter.lint("<<<bwq: ignore[E012]>>>\nrating:6 OR\nrating:7");
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].code(), "E012");
+    assert_eq!(report.errors[0].span().start.line, 3);
+
+    let report = linter.lint("rating:6 <<<bwq: ignore[E012]>>> OR rating:7");
+    assert!(report.is_clean());
+
+    let report = linter.lint("apple AND juice <<<bwq: ignore[W001]>>>");
+    assert!(!report.has_errors());
+    assert_eq!(
+        report.warnings.iter().map(|w| w.code()).collect::<Vec<_>>(),
+        vec!["W004"]
+    );
+}
@@ -0,0 +1,246 @@
+use lsp_types::{
+    CompletionItem, CompletionItemKind, Documentation, InsertTextFormat, MarkupContent, MarkupKind,
+    Position as LspPosition,
+};
+
+use crate::utils::{LineIndex, PositionEncoding};
+use bwq_linter::{
+    ast::FieldType,
+    docs::{Operator, OperatorDoc, field_doc, operator_doc},
+    languages::LANGUAGES,
+    lexer::{Lexer, TokenType},
+};
+
+/// completions for the cursor position: values after a field, operators after a complete
+/// operand and field names wherever a new term can start
+pub fn completions(
+    content: &str,
+    position: LspPosition,
+    encoding: PositionEncoding,
+) -> Vec<CompletionItem> {
+    let chars: Vec<char> = content.chars().collect();
+    let offset = LineIndex::new(content, encoding).offset(position);
+
+    // the word being typed is left for the client to filter on
+    let word_start = chars[..offset]
+        .iter()
+        .rposition(|&c| !is_word_char(c))
+        .map_or(0, |i| i + 1);
+
+    if word_start > 0 && chars[word_start - 1] == ':' {
+        let name_end = word_start - 1;
+        let name_start = chars[..name_end]
+            .iter()
+            .rposition(|&c| !c.is_alphanumeric())
+            .map_or(0, |i| i + 1);
+        let name: String = chars[name_start..name_end].iter().collect();
+
+        return FieldType::parse(&name)
+            .map(|field| value_completions(&field))
+            .unwrap_or_default();
+    }
+
+    let before: String = chars[..word_start].iter().collect();
+    match previous_token(&before) {
+        Previous::Text => Vec::new(),
+        Previous::Operand if before.ends_with(char::is_whitespace) => operator_completions()
+            .into_iter()
+            .chain(field_completions())
+            .collect(),
+        Previous::Operand => Vec::new(),
+        Previous::Start => field_completions(),
+    }
+}
+
+/// what comes before the word being typed
+enum Previous {
+    /// the start of the query, an operator or an opening bracket
+    Start,
+    /// a complete term, phrase or group
+    Operand,
+    /// an unclosed comment or phrase
+    Text,
+}
+
+fn previous_token(before: &str) -> Previous {
+    if before.chars().filter(|&c| c == '"').count() % 2 == 1 {
+        return Previous::Text;
+    }
+
+    let (tokens, _) = Lexer::new(before).tokenize_with_errors();
+    let mut in_comment = false;
+    let mut previous = Previous::Start;
+
+    for token in &tokens {
+        match token.token_type {
+            TokenType::CommentStart => in_comment = true,
+            TokenType::CommentEnd => in_comment = false,
+            TokenType::CommentText(_) | TokenType::Whitespace | TokenType::Eof => {}
+            TokenType::Word(_)
+            | TokenType::QuotedString(_)
+            | TokenType::Number(_)
+            | TokenType::Hashtag(_)
+            | TokenType::Mention(_)
+            | TokenType::RightParen
+            | TokenType::RightBracket
+            | TokenType::RightBrace
+            | TokenType::Asterisk
+            | TokenType::Question => previous = Previous::Operand,
+            _ => previous = Previous::Start,
+        }
+    }
+
+    if in_comment { Previous::Text } else { previous }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn field_completions() -> Vec<CompletionItem> {
+    FieldType::ALL
+        .iter()
+        .map(|field| {
+            let doc = field_doc(field);
+            CompletionItem {
+                label: doc.syntax.to_string(),
+                kind: Some(CompletionItemKind::FIELD),
+                detail: Some(doc.description.to_string()),
+                documentation: Some(documentation(&doc)),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+fn value_completions(field: &FieldType) -> Vec<CompletionItem> {
+    let mut items: Vec<_> = if *field == FieldType::Language {
+        LANGUAGES
+            .iter()
+            .map(|(code, name)| value_item(code, Some(name)))
+            .collect()
+    } else {
+        field_doc(field)
+            .values
+            .iter()
+            .map(|value| value_item(value, None))
+            .collect()
+    };
+
+    if field.accepts_range() {
+        items.push(CompletionItem {
+            label: "[start TO end]".to_string(),
+            kind: Some(CompletionItemKind::SNIPPET),
+            insert_text: Some("[${1} TO ${2}]".to_string()),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            ..Default::default()
+        });
+    }
+
+    items
+}
+
+fn value_item(value: &str, detail: Option<&str>) -> CompletionItem {
+    CompletionItem {
+        label: value.to_string(),
+        kind: Some(CompletionItemKind::ENUM_MEMBER),
+        detail: detail.map(str::to_string),
+        ..Default::default()
+    }
+}
+
+fn operator_completions() -> Vec<CompletionItem> {
+    let keywords = [Operator::And, Operator::Or, Operator::Not].map(|operator| {
+        let doc = operator_doc(operator);
+        CompletionItem {
+            label: doc.syntax.to_string(),
+            kind: Some(CompletionItemKind::KEYWORD),
+            documentation: Some(documentation(&doc)),
+            ..Default::default()
+        }
+    });
+
+    let near = [
+        (Operator::Near, "NEAR/${1:5} "),
+        (Operator::NearForward, "NEAR/${1:5}f "),
+    ]
+    .map(|(operator, snippet)| {
+        let doc = operator_doc(operator);
+        CompletionItem {
+            label: doc.syntax.to_string(),
+            kind: Some(CompletionItemKind::OPERATOR),
+            documentation: Some(documentation(&doc)),
+            insert_text: Some(snippet.to_string()),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            ..Default::default()
+        }
+    });
+
+    keywords.into_iter().chain(near).collect()
+}
+
+fn documentation(doc: &OperatorDoc) -> Documentation {
+    Documentation::MarkupContent(MarkupContent {
+        kind: MarkupKind::Markdown,
+        value: doc.to_markdown(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(content: &str) -> Vec<String> {
+        let position = LspPosition::new(0, content.encode_utf16().count() as u32);
+        completions(content, position, PositionEncoding::Utf16)
+            .into_iter()
+            .map(|item| item.label)
+            .collect()
+    }
+
+    #[test]
+    fn test_field_names_at_term_start() {
+        for content in ["", "apple AND ", "(auth", "apple OR (juice NEAR/3 "] {
+            let labels = labels(content);
+            assert!(labels.contains(&"authorVerifiedType:".to_string()));
+            assert!(!labels.contains(&"AND".to_string()), "{content}");
+        }
+    }
+
+    #[test]
+    fn test_operators_after_operand() {
+        for content in [
+            "apple ",
+            "\"apple juice\" ",
+            "(apple OR juice) ",
+            "rating:[1 TO 3] A",
+        ] {
+            let labels = labels(content);
+            assert!(labels.contains(&"AND".to_string()), "{content}");
+            assert!(labels.contains(&"NEAR/xf".to_string()), "{content}");
+            assert!(labels.contains(&"site:".to_string()), "{content}");
+        }
+
+        // nothing can directly follow a phrase
+        assert!(labels("\"apple juice\"").is_empty());
+    }
+
+    #[test]
+    fn test_field_values() {
+        assert_eq!(
+            labels("apple AND authorVerifiedType:"),
+            vec!["blue", "business", "government"]
+        );
+        assert_eq!(labels("engagementType:RE").len(), 4);
+        assert_eq!(labels("redditSpoiler:"), vec!["true", "false"]);
+        assert!(labels("language:").contains(&"en".to_string()));
+        assert_eq!(labels("authorFollowers:"), vec!["[start TO end]"]);
+        assert!(labels("unknown:").is_empty());
+    }
+
+    #[test]
+    fn test_no_completions_in_text() {
+        assert!(labels("apple <<<todo ").is_empty());
+        assert!(labels("\"apple ").is_empty());
+    }
+}
@@ -1,17 +1,32 @@
+use std::collections::HashMap;
+
 use anyhow::Result;
-use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Diagnostic, DiagnosticSeverity,
+    NumberOrString, Range, TextEdit, Uri, WorkspaceEdit,
+};
 
-use crate::utils::span_to_range;
+use crate::utils::{LineIndex, PositionEncoding};
 use bwq_linter::{
     BrandwatchLinter,
-    error::{LintError, LintWarning},
+    ast::{BooleanOperator, Expression, ProximityOperator, Term},
+    error::{Applicability, Edit, Fix, LintError, LintWarning},
+    lexer::{Lexer, Token, TokenType},
+    parser::Parser,
 };
 
-pub struct DiagnosticsHandler;
+pub struct DiagnosticsHandler {
+    encoding: PositionEncoding,
+}
 
 impl DiagnosticsHandler {
     pub fn new() -> Self {
-        Self
+        Self::with_encoding(PositionEncoding::default())
+    }
+
+    /// a handler whose ranges count characters in `encoding`
+    pub fn with_encoding(encoding: PositionEncoding) -> Self {
+        Self { encoding }
     }
 
     pub fn analyze_content(
@@ -22,76 +37,214 @@ impl DiagnosticsHandler {
         let mut diagnostics = Vec::new();
 
         let analysis = linter.analyze_and_skip_empty(content);
+        let index = LineIndex::new(content, self.encoding);
 
         for error in &analysis.errors {
-            diagnostics.push(self.error_to_diagnostic(error));
+            diagnostics.push(self.error_to_diagnostic(error, &index));
         }
 
         for warning in &analysis.warnings {
-            diagnostics.push(self.warning_to_diagnostic(warning));
+            diagnostics.push(self.warning_to_diagnostic(warning, &index));
         }
 
         Ok(diagnostics)
     }
 
-    fn error_to_diagnostic(&self, error: &LintError) -> Diagnostic {
+    /// quick fixes for the diagnostics that overlap `range`, followed by the refactors that
+    /// apply to it
+    pub fn code_actions(
+        &self,
+        uri: &Uri,
+        content: &str,
+        range: Range,
+        linter: &mut BrandwatchLinter,
+    ) -> Vec<CodeActionOrCommand> {
+        let analysis = linter.analyze_and_skip_empty(content);
+        let index = LineIndex::new(content, self.encoding);
+
+        let errors = analysis
+            .errors
+            .iter()
+            .filter_map(|error| Some((self.error_to_diagnostic(error, &index), error.fix()?)));
+        let warnings = analysis.warnings.iter().filter_map(|warning| {
+            Some((self.warning_to_diagnostic(warning, &index), warning.fix()?))
+        });
+
+        let mut actions: Vec<CodeActionOrCommand> = errors
+            .chain(warnings)
+            .filter(|(diagnostic, _)| {
+                diagnostic.range.start <= range.end && range.start <= diagnostic.range.end
+            })
+            .map(|(diagnostic, fix)| {
+                CodeActionOrCommand::CodeAction(
+                    self.fix_to_code_action(uri, diagnostic, fix, &index),
+                )
+            })
+            .collect();
+
+        actions.extend(self.refactors(content, range, &index).into_iter().map(
+            |(title, kind, edits)| {
+                CodeActionOrCommand::CodeAction(self.code_action(uri, title, kind, &edits, &index))
+            },
+        ));
+
+        actions
+    }
+
+    /// the refactors offered for `range`, as (title, kind, edits)
+    fn refactors(
+        &self,
+        content: &str,
+        range: Range,
+        index: &LineIndex,
+    ) -> Vec<(String, CodeActionKind, Vec<Edit>)> {
+        let (start, end) = (index.offset(range.start), index.offset(range.end));
+        let (tokens, _) = Lexer::new(content).tokenize_with_errors();
+        let query = Parser::new(tokens.clone()).parse().query;
+
+        let mut refactors = Vec::new();
+        if let Some(edits) = extract_group(content, &query.expression, &tokens, start, end) {
+            refactors.push((
+                "Extract to parenthesised group".to_string(),
+                CodeActionKind::REFACTOR_EXTRACT,
+                edits,
+            ));
+        }
+
+        let mut rewrites = Vec::new();
+        proximity_to_near(&query.expression, start, end, &mut rewrites);
+        for edit in rewrites {
+            refactors.push((
+                format!("Convert to '{}'", edit.content),
+                CodeActionKind::REFACTOR_REWRITE,
+                vec![edit],
+            ));
+        }
+
+        refactors
+    }
+
+    fn fix_to_code_action(
+        &self,
+        uri: &Uri,
+        diagnostic: Diagnostic,
+        fix: &Fix,
+        index: &LineIndex,
+    ) -> CodeAction {
+        CodeAction {
+            diagnostics: Some(vec![diagnostic]),
+            is_preferred: Some(fix.applicability == Applicability::Safe),
+            ..self.code_action(
+                uri,
+                fix.message.clone(),
+                CodeActionKind::QUICKFIX,
+                &fix.edits,
+                index,
+            )
+        }
+    }
+
+    fn code_action(
+        &self,
+        uri: &Uri,
+        title: String,
+        kind: CodeActionKind,
+        edits: &[Edit],
+        index: &LineIndex,
+    ) -> CodeAction {
+        let edits = edits
+            .iter()
+            .map(|edit| TextEdit {
+                range: index.range(&edit.span),
+                new_text: edit.content.clone(),
+            })
+            .collect();
+
+        CodeAction {
+            title,
+            kind: Some(kind),
+            edit: Some(WorkspaceEdit {
+                changes: Some(HashMap::from([(uri.clone(), edits)])),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn error_to_diagnostic(&self, error: &LintError, index: &LineIndex) -> Diagnostic {
         let (range, message) = match error {
-            LintError::LexerError { span, message } => (span_to_range(span), message.clone()),
-            LintError::ParserError { span, message } => (span_to_range(span), message.clone()),
-            LintError::ValidationError { span, message } => (span_to_range(span), message.clone()),
-            LintError::InvalidBooleanCase { span, operator } => (
-                span_to_range(span),
+            LintError::LexerError { span, message } => (index.range(span), message.clone()),
+            LintError::ParserError { span, message } => (index.range(span), message.clone()),
+            LintError::UnclosedComment { span, .. } => (index.range(span), error.to_string()),
+            LintError::InvalidBooleanCase { span, operator, .. } => (
+                index.range(span),
                 format!("Boolean operator '{operator}' must be capitalized"),
             ),
             LintError::UnbalancedParentheses { span } => {
-                (span_to_range(span), "Unbalanced parentheses".to_string())
+                (index.range(span), "Unbalanced parentheses".to_string())
             }
             LintError::InvalidWildcardPlacement { span } => (
-                span_to_range(span),
+                index.range(span),
                 "Invalid wildcard placement: wildcards cannot be at the beginning of a word"
                     .to_string(),
             ),
             LintError::InvalidProximityOperator { span, message } => (
-                span_to_range(span),
+                index.range(span),
                 format!("Invalid proximity operator syntax: {message}"),
             ),
             LintError::InvalidFieldOperator { span, message } => (
-                span_to_range(span),
+                index.range(span),
                 format!("Invalid field operator syntax: {message}"),
             ),
             LintError::InvalidRangeSyntax { span } => (
-                span_to_range(span),
+                index.range(span),
                 "Invalid range syntax: expected '[value TO value]'".to_string(),
             ),
             LintError::UnexpectedToken { span, token } => {
-                (span_to_range(span), format!("Unexpected token '{token}'"))
+                (index.range(span), format!("Unexpected token '{token}'"))
             }
             LintError::ExpectedToken {
                 span,
                 expected,
                 found,
             } => (
-                span_to_range(span),
+                index.range(span),
                 format!("Expected '{expected}' but found '{found}'"),
             ),
-            LintError::FieldValidationError { span, message } => {
-                (span_to_range(span), message.clone())
+            LintError::InvalidRating { span, message }
+            | LintError::InvalidCoordinate { span, message }
+            | LintError::InvalidBooleanValue { span, message, .. }
+            | LintError::InvalidVerifiedType { span, message }
+            | LintError::InvalidMinuteOfDay { span, message }
+            | LintError::InvalidDomain { span, message, .. }
+            | LintError::InvalidHostname { span, message }
+            | LintError::IncompleteDomain { span, message }
+            | LintError::SiteWwwPrefix { span, message, .. } => {
+                (index.range(span), message.clone())
             }
             LintError::ProximityOperatorError { span, message } => {
-                (span_to_range(span), message.clone())
+                (index.range(span), message.clone())
             }
-            LintError::RangeValidationError { span, message } => {
-                (span_to_range(span), message.clone())
+            LintError::RangeValidationError { span, message, .. } => {
+                (index.range(span), message.clone())
             }
-            LintError::OperatorMixingError { span, message } => {
-                (span_to_range(span), message.clone())
+            LintError::OperatorMixingError { span, message, .. } => {
+                (index.range(span), message.clone())
             }
             LintError::PureNegativeQueryError { span, message } => {
-                (span_to_range(span), message.clone())
+                (index.range(span), message.clone())
+            }
+            LintError::InvalidFieldOperatorSpacing { span, message }
+            | LintError::MissingOperand { span, message }
+            | LintError::InvalidTildeUsage { span, message }
+            | LintError::EmptyTerm { span, message }
+            | LintError::CaseSensitiveError { span, message } => {
+                (index.range(span), message.clone())
             }
-            LintError::InvalidFieldOperatorSpacing { span, message } => {
-                (span_to_range(span), message.clone())
+            LintError::BroadWildcard { span } | LintError::UnterminatedQuote { span } => {
+                (index.range(span), error.to_string())
             }
+            LintError::Promoted { warning } => (index.range(warning.span()), warning.to_string()),
         };
 
         Diagnostic {
@@ -107,9 +260,9 @@ impl DiagnosticsHandler {
         }
     }
 
-    fn warning_to_diagnostic(&self, warning: &LintWarning) -> Diagnostic {
+    fn warning_to_diagnostic(&self, warning: &LintWarning, index: &LineIndex) -> Diagnostic {
         Diagnostic {
-            range: span_to_range(warning.span()),
+            range: index.range(warning.span()),
             severity: Some(DiagnosticSeverity::WARNING),
             code: Some(NumberOrString::String(warning.code().to_string())),
             code_description: None,
@@ -122,6 +275,167 @@ impl DiagnosticsHandler {
     }
 }
 
+/// parentheses around the selected chars `start..end` when they are neighbouring operands of one
+/// boolean operator in `expr`, and not already the whole of a group
+fn extract_group(
+    content: &str,
+    expr: &Expression,
+    tokens: &[Token],
+    start: usize,
+    end: usize,
+) -> Option<Vec<Edit>> {
+    let chars: Vec<char> = content.chars().collect();
+    let (mut start, mut end) = (start, end.min(chars.len()));
+    while start < end && chars[start].is_whitespace() {
+        start += 1;
+    }
+    while end > start && chars[end - 1].is_whitespace() {
+        end -= 1;
+    }
+
+    let first = tokens.iter().position(|t| t.span.start.offset == start)?;
+    let last = tokens.iter().position(|t| t.span.end.offset == end)?;
+    if first > last {
+        return None;
+    }
+    let already_grouped = first > 0
+        && tokens[first - 1].token_type == TokenType::LeftParen
+        && tokens
+            .get(last + 1)
+            .is_some_and(|t| t.token_type == TokenType::RightParen);
+    if already_grouped {
+        return None;
+    }
+
+    if !selects_operands(expr, start, end) {
+        return None;
+    }
+
+    Some(vec![
+        Edit::insertion(tokens[first].span.start.clone(), "("),
+        Edit::insertion(tokens[last].span.end.clone(), ")"),
+    ])
+}
+
+/// whether the chars `start..end` are two or more neighbouring operands of one AND, OR or NOT
+/// chain in `expr`, so that grouping them keeps the query's meaning
+fn selects_operands(expr: &Expression, start: usize, end: usize) -> bool {
+    match expr {
+        Expression::BooleanOp {
+            operator,
+            right: Some(_),
+            ..
+        } => {
+            let mut operands = Vec::new();
+            chain_operands(expr, operator, &mut operands);
+            // `a NOT b NOT c` is read from the left, so only a run from its first operand can be
+            // grouped
+            let firsts = if *operator == BooleanOperator::Not {
+                0..1
+            } else {
+                0..operands.len()
+            };
+            let selected = firsts.into_iter().any(|i| {
+                operands[i].span().start.offset == start
+                    && operands[i + 1..]
+                        .iter()
+                        .any(|operand| operand.span().end.offset == end)
+            });
+            selected
+                || operands
+                    .iter()
+                    .any(|operand| selects_operands(operand, start, end))
+        }
+        Expression::BooleanOp { left, .. } => selects_operands(left, start, end),
+        Expression::Group { expression, .. } => selects_operands(expression, start, end),
+        Expression::Field { value, .. } => selects_operands(value, start, end),
+        Expression::Proximity { terms, .. } => {
+            terms.iter().any(|term| selects_operands(term, start, end))
+        }
+        Expression::Range { .. } | Expression::Term { .. } | Expression::Error { .. } => false,
+    }
+}
+
+/// the operands of the chain of `operator`s starting at `expr`, e.g. all three in `a AND b AND c`
+fn chain_operands<'a>(
+    expr: &'a Expression,
+    operator: &BooleanOperator,
+    operands: &mut Vec<&'a Expression>,
+) {
+    match expr {
+        Expression::BooleanOp {
+            operator: op,
+            left,
+            right: Some(right),
+            ..
+        } if op == operator => {
+            chain_operands(left, operator, operands);
+            if *operator == BooleanOperator::Not {
+                operands.push(right);
+            } else {
+                chain_operands(right, operator, operands);
+            }
+        }
+        _ => operands.push(expr),
+    }
+}
+
+/// rewrite each two-word `"a b"~5` overlapping the chars `start..end` as `a NEAR/5 b`
+fn proximity_to_near(expr: &Expression, start: usize, end: usize, rewrites: &mut Vec<Edit>) {
+    match expr {
+        Expression::BooleanOp { left, right, .. } => {
+            proximity_to_near(left, start, end, rewrites);
+            if let Some(right) = right {
+                proximity_to_near(right, start, end, rewrites);
+            }
+        }
+        Expression::Group { expression, .. } => {
+            proximity_to_near(expression, start, end, rewrites);
+        }
+        Expression::Field { value, .. } => proximity_to_near(value, start, end, rewrites),
+        Expression::Proximity {
+            operator:
+                ProximityOperator::Proximity {
+                    distance: Some(distance),
+                },
+            terms,
+            span,
+        } if span.start.offset <= end && start <= span.end.offset => {
+            let [
+                Expression::Term {
+                    term: Term::Phrase { value },
+                    ..
+                },
+            ] = terms.as_slice()
+            else {
+                return;
+            };
+            let words: Vec<&str> = value.split_whitespace().collect();
+            let [first, second] = words.as_slice() else {
+                return;
+            };
+
+            // only offer the rewrite when both words still read as plain words outside quotes
+            let near = format!("{first} NEAR/{distance} {second}");
+            let (tokens, errors) = Lexer::new(&near).tokenize_with_errors();
+            let parsed = Parser::new(tokens).parse();
+            let plain_words = matches!(
+                &parsed.query.expression,
+                Expression::Proximity { operator: ProximityOperator::Near { .. }, terms, .. }
+                    if terms.iter().all(|term| matches!(
+                        term,
+                        Expression::Term { term: Term::Word { .. }, .. }
+                    ))
+            );
+
+            if errors.is_empty() && parsed.errors.is_empty() && plain_words {
+                rewrites.push(Edit::replacement(span.clone(), near));
+            }
+        }
+        _ => {}
+    }
+}
+
 impl Default for DiagnosticsHandler {
     fn default() -> Self {
         Self::new()
@@ -158,6 +472,202 @@ mod tests {
         }
     }
 
+    fn uri() -> Uri {
+        "file:///queries/brand.bwq".parse().unwrap()
+    }
+
+    fn range(start: u32, end: u32) -> Range {
+        Range::new(
+            lsp_types::Position::new(0, start),
+            lsp_types::Position::new(0, end),
+        )
+    }
+
+    /// the actions offered for a single-line ASCII query
+    fn actions(content: &str, range: Range) -> Vec<CodeAction> {
+        let handler = DiagnosticsHandler::new();
+        handler
+            .code_actions(&uri(), content, range, &mut BrandwatchLinter::new())
+            .into_iter()
+            .map(|action| match action {
+                CodeActionOrCommand::CodeAction(action) => action,
+                CodeActionOrCommand::Command(_) => panic!("expected a code action"),
+            })
+            .collect()
+    }
+
+    /// `content` after applying the action titled `title`
+    fn apply(content: &str, range: Range, title: &str) -> String {
+        let actions = actions(content, range);
+        let action = actions
+            .iter()
+            .find(|action| action.title == title)
+            .unwrap_or_else(|| panic!("no '{title}' action in {actions:?}"));
+        let mut edits = action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri()].clone();
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.range.start.character));
+
+        let mut chars: Vec<char> = content.chars().collect();
+        for edit in edits {
+            let (start, end) = (edit.range.start.character, edit.range.end.character);
+            chars.splice(start as usize..end as usize, edit.new_text.chars());
+        }
+        chars.into_iter().collect()
+    }
+
+    #[test]
+    fn test_code_actions() {
+        let content = "apple and juice OR site:www.bbc.co.uk";
+        let actions = actions(content, range(7, 7));
+        let action = actions
+            .iter()
+            .find(|action| action.title == "Replace with 'AND'")
+            .unwrap();
+        assert_eq!(action.kind, Some(CodeActionKind::QUICKFIX));
+        // it changes what the query matches, so it isn't preferred
+        assert_eq!(action.is_preferred, Some(false));
+
+        let edits = &action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri()];
+        assert_eq!(edits[0].new_text, "AND");
+        assert_eq!(edits[0].range.start.character, 6);
+        assert_eq!(edits[0].range.end.character, 9);
+
+        assert_eq!(
+            apply(content, range(20, 20), "Remove 'www.'"),
+            "apple and juice OR site:bbc.co.uk"
+        );
+    }
+
+    #[test]
+    fn test_quick_fixes() {
+        assert_eq!(
+            apply(
+                "apple AND juice OR cherry",
+                range(0, 0),
+                "Wrap the AND operands in parentheses"
+            ),
+            "(apple AND juice) OR cherry"
+        );
+        assert_eq!(
+            apply("apple juice", range(0, 0), "Insert explicit AND"),
+            "apple AND juice"
+        );
+        assert_eq!(
+            apply(
+                "authorverified:true",
+                range(3, 3),
+                "Replace with 'authorVerified'"
+            ),
+            "authorVerified:true"
+        );
+        assert_eq!(
+            apply("rating:[5 TO 2]", range(8, 8), "Swap the range bounds"),
+            "rating:[2 TO 5]"
+        );
+        assert_eq!(
+            apply(
+                "apple <<<note",
+                range(7, 7),
+                "Close the comment at the end of the line"
+            ),
+            "apple <<<note>>>"
+        );
+    }
+
+    #[test]
+    fn test_extract_group() {
+        let content = "apple AND juice AND cherry";
+        let action = actions(content, range(10, 26))
+            .into_iter()
+            .find(|action| action.kind == Some(CodeActionKind::REFACTOR_EXTRACT))
+            .unwrap();
+        assert_eq!(action.title, "Extract to parenthesised group");
+        assert_eq!(
+            apply(content, range(9, 26), "Extract to parenthesised group"),
+            "apple AND (juice AND cherry)"
+        );
+        assert_eq!(
+            apply(
+                "apple NOT banana NOT cherry",
+                range(0, 16),
+                "Extract to parenthesised group"
+            ),
+            "(apple NOT banana) NOT cherry"
+        );
+
+        let extracts = |content: &str, range: Range| {
+            actions(content, range)
+                .iter()
+                .filter(|action| action.kind == Some(CodeActionKind::REFACTOR_EXTRACT))
+                .count()
+        };
+        // not whole operands, a single term, and already a group
+        assert_eq!(extracts(content, range(0, 9)), 0);
+        assert_eq!(extracts(content, range(3, 12)), 0);
+        assert_eq!(extracts(content, range(10, 15)), 0);
+        assert_eq!(extracts("apple AND (juice OR cherry)", range(11, 26)), 0);
+        // operands that belong to different operators would change what the query matches
+        assert_eq!(extracts("apple NOT banana AND cherry", range(10, 27)), 0);
+        assert_eq!(extracts("apple AND juice OR cherry", range(10, 25)), 0);
+        assert_eq!(extracts("apple NOT banana NOT cherry", range(10, 27)), 0);
+    }
+
+    #[test]
+    fn test_proximity_to_near() {
+        assert_eq!(
+            apply(
+                "\"apple juice\"~5 OR cherry",
+                range(2, 2),
+                "Convert to 'apple NEAR/5 juice'"
+            ),
+            "apple NEAR/5 juice OR cherry"
+        );
+
+        let rewrites = |content: &str| {
+            actions(content, range(2, 2))
+                .iter()
+                .filter(|action| action.kind == Some(CodeActionKind::REFACTOR_REWRITE))
+                .count()
+        };
+        assert_eq!(rewrites("\"apple juice\"~5"), 1);
+        assert_eq!(rewrites("\"apple juice cherry\"~5"), 0);
+        assert_eq!(rewrites("\"apple OR\"~5"), 0);
+        assert_eq!(rewrites("\"apple juice\""), 0);
+    }
+
+    #[test]
+    fn test_emoji_ranges() {
+        let mut linter = BrandwatchLinter::new();
+        let uri: Uri = "file:///queries/brand.bwq".parse().unwrap();
+        let content = "🍎🍎 OR (👍 and juice)";
+
+        // `and` starts after 9 chars: 12 UTF-16 code units or 18 UTF-8 bytes
+        for (encoding, start) in [
+            (PositionEncoding::Utf8, 18),
+            (PositionEncoding::Utf16, 12),
+            (PositionEncoding::Utf32, 9),
+        ] {
+            let handler = DiagnosticsHandler::with_encoding(encoding);
+            let diagnostics = handler.analyze_content(content, &mut linter).unwrap();
+            let diagnostic = diagnostics
+                .iter()
+                .find(|d| d.code == Some(NumberOrString::String("E004".to_string())))
+                .unwrap();
+            assert_eq!(diagnostic.range.start.character, start);
+            assert_eq!(diagnostic.range.end.character, start + 3);
+
+            let range = Range::new(
+                lsp_types::Position::new(0, start),
+                lsp_types::Position::new(0, start),
+            );
+            let actions = handler.code_actions(&uri, content, range, &mut linter);
+            let CodeActionOrCommand::CodeAction(action) = &actions[0] else {
+                panic!("expected a code action");
+            };
+            let edits = &action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+            assert_eq!(edits[0].range.start.character, start);
+        }
+    }
+
     #[test]
     fn test_valid_query_diagnostics() {
         let mut linter = BrandwatchLinter::new();
@@ -0,0 +1,203 @@
+use lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind, Position as LspPosition};
+
+use crate::utils::{LineIndex, PositionEncoding};
+use bwq_linter::{
+    BrandwatchLinter,
+    ast::FieldType,
+    docs::{Operator, OperatorDoc, field_doc, operator_doc},
+    error::Span,
+    lexer::{Lexer, Token, TokenType},
+    registry::rule,
+};
+
+/// documentation for the operator or field under the cursor, followed by explanations of any
+/// diagnostics there
+pub fn hover(
+    content: &str,
+    position: LspPosition,
+    linter: &mut BrandwatchLinter,
+    encoding: PositionEncoding,
+) -> Option<Hover> {
+    let index = LineIndex::new(content, encoding);
+    let offset = index.offset(position);
+    let (tokens, _) = Lexer::new(content).tokenize_with_errors();
+
+    let mut sections = Vec::new();
+    let mut range = None;
+
+    if let Some((doc, span)) = token_doc(&tokens, offset) {
+        sections.push(doc.to_markdown());
+        range = Some(index.range(&span));
+    }
+
+    let analysis = linter.analyze_and_skip_empty(content);
+    let diagnostics = analysis
+        .errors
+        .iter()
+        .map(|error| (error.code(), error.to_string(), error.span()))
+        .chain(
+            analysis
+                .warnings
+                .iter()
+                .map(|warning| (warning.code(), warning.to_string(), warning.span())),
+        );
+
+    for (code, message, span) in diagnostics {
+        if !contains(span, offset) {
+            continue;
+        }
+
+        sections.push(match rule(code) {
+            Some(rule) => format!(
+                "**{code}** ({}): {message}\n\n{}",
+                rule.name, rule.explanation
+            ),
+            None => format!("**{code}**: {message}"),
+        });
+        range.get_or_insert_with(|| index.range(span));
+    }
+
+    if sections.is_empty() {
+        return None;
+    }
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: sections.join("\n\n---\n\n"),
+        }),
+        range,
+    })
+}
+
+/// the documentation for the token at `offset`, and the span it applies to
+fn token_doc(tokens: &[Token], offset: usize) -> Option<(OperatorDoc, Span)> {
+    let index = tokens.iter().position(|token| {
+        !matches!(token.token_type, TokenType::Whitespace | TokenType::Eof)
+            && contains(&token.span, offset)
+    })?;
+    let token = &tokens[index];
+
+    let operator = match &token.token_type {
+        TokenType::Word(_) | TokenType::Field(_) => {
+            let colon = tokens.get(index + 1)?;
+            let field = field_name(token, colon)?;
+            let span = Span::new(token.span.start.clone(), colon.span.end.clone());
+            return Some((field_doc(&field), span));
+        }
+        TokenType::Colon => {
+            let field = field_name(tokens.get(index.checked_sub(1)?)?, token)?;
+            let span = Span::new(tokens[index - 1].span.start.clone(), token.span.end.clone());
+            return Some((field_doc(&field), span));
+        }
+        TokenType::And => Operator::And,
+        TokenType::Or => Operator::Or,
+        TokenType::Not => Operator::Not,
+        TokenType::LeftParen | TokenType::RightParen => Operator::Group,
+        TokenType::Tilde => Operator::Proximity,
+        TokenType::Near(_) => Operator::Near,
+        TokenType::NearForward(_) => Operator::NearForward,
+        TokenType::Asterisk => Operator::Wildcard,
+        TokenType::Question => Operator::Replacement,
+        TokenType::LeftBrace | TokenType::RightBrace => Operator::CaseSensitive,
+        TokenType::CommentStart | TokenType::CommentText(_) | TokenType::CommentEnd => {
+            Operator::Comment
+        }
+        _ => return None,
+    };
+
+    Some((operator_doc(operator), token.span.clone()))
+}
+
+/// the field named by `name` when it is directly followed by `colon`
+fn field_name(name: &Token, colon: &Token) -> Option<FieldType> {
+    let (TokenType::Word(word) | TokenType::Field(word)) = &name.token_type else {
+        return None;
+    };
+
+    if colon.token_type != TokenType::Colon || colon.span.start != name.span.end {
+        return None;
+    }
+
+    FieldType::parse(word)
+}
+
+fn contains(span: &Span, offset: usize) -> bool {
+    span.start.offset <= offset && offset < span.end.offset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hover_text(content: &str, character: u32) -> Option<String> {
+        let hover = hover(
+            content,
+            LspPosition::new(0, character),
+            &mut BrandwatchLinter::new(),
+            PositionEncoding::Utf16,
+        )?;
+        let HoverContents::Markup(markup) = hover.contents else {
+            panic!("expected markdown");
+        };
+        Some(markup.value)
+    }
+
+    #[test]
+    fn test_field_hover() {
+        let content = "apple AND authorVerifiedType:blue";
+
+        for character in [10, 28] {
+            let text = hover_text(content, character).unwrap();
+            assert!(text.starts_with("**`authorVerifiedType:`**"));
+            assert!(text.contains("`blue`, `business`, `government`"));
+        }
+
+        // words that aren't fields have no documentation
+        assert_eq!(hover_text(content, 2), None);
+    }
+
+    #[test]
+    fn test_operator_hover() {
+        let content = "(logitech NEAR/2f keyboard) AND <<<note>>>";
+
+        assert!(
+            hover_text(content, 12)
+                .unwrap()
+                .starts_with("**`NEAR/xf`**")
+        );
+        assert!(hover_text(content, 28).unwrap().starts_with("**`AND`**"));
+        assert!(
+            hover_text(content, 36)
+                .unwrap()
+                .starts_with("**`<<< >>>`**")
+        );
+    }
+
+    #[test]
+    fn test_hover_after_emoji() {
+        // each 🍎 is two UTF-16 code units, so `title:` starts at 13 rather than 11
+        let content = "🍎 OR 🍎 AND title:apple";
+        let text = hover_text(content, 13).unwrap();
+        assert!(text.starts_with("**`title:`**"));
+
+        let hover = hover(
+            content,
+            LspPosition::new(0, 13),
+            &mut BrandwatchLinter::new(),
+            PositionEncoding::Utf16,
+        )
+        .unwrap();
+        let range = hover.range.unwrap();
+        assert_eq!(range.start.character, 13);
+        assert_eq!(range.end.character, 19);
+    }
+
+    #[test]
+    fn test_diagnostic_hover() {
+        let text = hover_text("apple AND juice OR cherry", 20).unwrap();
+
+        assert!(text.contains("**E015**"));
+        assert!(text.contains("AND and OR can't be mixed"));
+    }
+}
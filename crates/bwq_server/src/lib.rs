@@ -1,4 +1,9 @@
+mod completion;
 mod diagnostics_handler;
+mod hover;
+mod lint_worker;
+mod outline;
+mod semantic_tokens;
 mod server;
 mod utils;
 use crate::server::Server;
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
+use lsp_server::{Message, Notification};
+use lsp_types::{
+    Diagnostic, PublishDiagnosticsParams, Uri,
+    notification::{Notification as NotificationTrait, PublishDiagnostics},
+};
+
+use crate::diagnostics_handler::DiagnosticsHandler;
+use crate::server::linter_for;
+use crate::utils::PositionEncoding;
+
+/// how long a document has to go without changes before it is linted
+const DEBOUNCE: Duration = Duration::from_millis(150);
+
+enum Task {
+    Lint {
+        uri: Uri,
+        content: String,
+        version: i32,
+    },
+    Close {
+        uri: Uri,
+    },
+}
+
+/// lints documents on a background thread and publishes their diagnostics
+///
+/// edits arriving within [`DEBOUNCE`] of each other are linted once, and results for a version
+/// that has since been replaced or closed are dropped rather than published.
+pub struct LintWorker {
+    tasks: Option<Sender<Task>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl LintWorker {
+    /// start the worker, which sends its notifications to `client` with ranges counted in
+    /// `encoding`
+    pub fn spawn(client: Sender<Message>, encoding: PositionEncoding) -> Self {
+        let (tasks, receiver) = crossbeam_channel::unbounded();
+        let handle = thread::spawn(move || Worker::new(receiver, client, encoding).run());
+
+        Self {
+            tasks: Some(tasks),
+            handle: Some(handle),
+        }
+    }
+
+    /// lint `version` of a document once it stops changing
+    pub fn lint(&self, uri: Uri, content: String, version: i32) {
+        self.send(Task::Lint {
+            uri,
+            content,
+            version,
+        });
+    }
+
+    /// forget a document, clearing its diagnostics
+    pub fn close(&self, uri: Uri) {
+        self.send(Task::Close { uri });
+    }
+
+    fn send(&self, task: Task) {
+        if let Some(tasks) = &self.tasks
+            && tasks.send(task).is_err()
+        {
+            eprintln!("Lint worker stopped unexpectedly");
+        }
+    }
+}
+
+impl Drop for LintWorker {
+    fn drop(&mut self) {
+        // disconnecting the channel stops the worker
+        self.tasks.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+struct Worker {
+    tasks: Receiver<Task>,
+    client: Sender<Message>,
+    diagnostics_handler: DiagnosticsHandler,
+    /// documents waiting to be linted
+    pending: HashMap<Uri, (String, i32)>,
+    /// the latest version of each open document
+    versions: HashMap<Uri, i32>,
+}
+
+impl Worker {
+    fn new(tasks: Receiver<Task>, client: Sender<Message>, encoding: PositionEncoding) -> Self {
+        Self {
+            tasks,
+            client,
+            diagnostics_handler: DiagnosticsHandler::with_encoding(encoding),
+            pending: HashMap::new(),
+            versions: HashMap::new(),
+        }
+    }
+
+    fn run(mut self) {
+        loop {
+            let task = if self.pending.is_empty() {
+                match self.tasks.recv() {
+                    Ok(task) => task,
+                    Err(_) => return,
+                }
+            } else {
+                match self.tasks.recv_timeout(DEBOUNCE) {
+                    Ok(task) => task,
+                    Err(RecvTimeoutError::Timeout) => {
+                        self.lint_pending();
+                        continue;
+                    }
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            };
+
+            self.receive(task);
+        }
+    }
+
+    fn receive(&mut self, task: Task) {
+        match task {
+            Task::Lint {
+                uri,
+                content,
+                version,
+            } => {
+                self.versions.insert(uri.clone(), version);
+                self.pending.insert(uri, (content, version));
+            }
+            Task::Close { uri } => {
+                self.versions.remove(&uri);
+                self.pending.remove(&uri);
+                self.publish(uri, Vec::new(), None);
+            }
+        }
+    }
+
+    fn lint_pending(&mut self) {
+        let pending: Vec<_> = self.pending.drain().collect();
+
+        for (uri, (content, version)) in pending {
+            let mut linter = linter_for(&uri);
+            let diagnostics = match self
+                .diagnostics_handler
+                .analyze_content(&content, &mut linter)
+            {
+                Ok(diagnostics) => diagnostics,
+                Err(e) => {
+                    eprintln!("Failed to lint {uri:?}: {e}");
+                    continue;
+                }
+            };
+
+            // pick up anything that arrived while linting, so stale results aren't published
+            while let Ok(task) = self.tasks.try_recv() {
+                self.receive(task);
+            }
+            if self.versions.get(&uri) == Some(&version) && !self.pending.contains_key(&uri) {
+                self.publish(uri, diagnostics, Some(version));
+            }
+        }
+    }
+
+    fn publish(&self, uri: Uri, diagnostics: Vec<Diagnostic>, version: Option<i32>) {
+        let params = PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version,
+        };
+        let notification = Notification::new(
+            <PublishDiagnostics as NotificationTrait>::METHOD.to_string(),
+            params,
+        );
+
+        if self
+            .client
+            .send(Message::Notification(notification))
+            .is_err()
+        {
+            eprintln!("Failed to publish diagnostics: the client has disconnected");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uri() -> Uri {
+        "untitled:query.bwq".parse().unwrap()
+    }
+
+    fn published(client: &Receiver<Message>) -> PublishDiagnosticsParams {
+        let Message::Notification(notification) =
+            client.recv_timeout(Duration::from_secs(5)).unwrap()
+        else {
+            panic!("expected a notification");
+        };
+        serde_json::from_value(notification.params).unwrap()
+    }
+
+    #[test]
+    fn test_debounced_lint() {
+        let (client, notifications) = crossbeam_channel::unbounded();
+        let worker = LintWorker::spawn(client, PositionEncoding::Utf16);
+
+        worker.lint(uri(), "apple AND juice OR".to_string(), 1);
+        worker.lint(uri(), "apple AND juice OR cherry".to_string(), 2);
+
+        let params = published(&notifications);
+        assert_eq!(params.version, Some(2));
+        assert_eq!(params.diagnostics.len(), 1);
+        assert!(notifications.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_close_cancels_pending_lint() {
+        let (client, notifications) = crossbeam_channel::unbounded();
+        let worker = LintWorker::spawn(client, PositionEncoding::Utf16);
+
+        worker.lint(uri(), "apple AND juice OR cherry".to_string(), 1);
+        worker.close(uri());
+
+        let params = published(&notifications);
+        assert_eq!(params.version, None);
+        assert!(params.diagnostics.is_empty());
+
+        drop(worker);
+        assert!(notifications.try_recv().is_err());
+    }
+}
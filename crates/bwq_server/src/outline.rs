@@ -0,0 +1,286 @@
+use lsp_types::{DocumentSymbol, FoldingRange, FoldingRangeKind, SymbolKind};
+
+use crate::utils::{LineIndex, PositionEncoding};
+use bwq_linter::{
+    ast::{BooleanOperator, Comment, Expression, Query},
+    error::Span,
+    lexer::Lexer,
+    parser::Parser,
+};
+
+/// the longest symbol name taken from the query text itself
+const MAX_NAME_CHARS: usize = 40;
+
+/// an outline of the operands of the top-level AND/OR, with the groups nested inside them
+///
+/// each symbol is named after the comment just before it, e.g. a `<<<brand terms>>>` header,
+/// or after the start of its own text when there isn't one
+pub fn document_symbols(content: &str, encoding: PositionEncoding) -> Vec<DocumentSymbol> {
+    let query = parse(content);
+    let chars: Vec<char> = content.chars().collect();
+    let outline = Outline {
+        chars: &chars,
+        comments: &query.comments,
+        index: LineIndex::new(content, encoding),
+    };
+
+    outline.symbols(&query.expression, 0, true)
+}
+
+/// a folding range for each group and comment that spans more than one line
+pub fn folding_ranges(content: &str) -> Vec<FoldingRange> {
+    let query = parse(content);
+
+    let mut ranges = Vec::new();
+    collect_groups(&query.expression, &mut ranges);
+    ranges.extend(
+        query
+            .comments
+            .iter()
+            .filter_map(|comment| folding_range(&comment.span, Some(FoldingRangeKind::Comment))),
+    );
+
+    ranges.sort_by_key(|range| (range.start_line, range.end_line));
+    ranges
+}
+
+fn parse(content: &str) -> Query {
+    let (tokens, _) = Lexer::new(content).tokenize_with_errors();
+    Parser::new(tokens).parse().query
+}
+
+struct Outline<'a> {
+    chars: &'a [char],
+    comments: &'a [Comment],
+    index: LineIndex,
+}
+
+impl Outline<'_> {
+    /// symbols for the operands of `expr`; only groups are listed below the top level, so
+    /// the outline follows the structure of the query rather than every term in it
+    fn symbols(&self, expr: &Expression, after: usize, top_level: bool) -> Vec<DocumentSymbol> {
+        let (operator, operands) = operands(expr);
+
+        let mut symbols = Vec::new();
+        let mut previous_end = after;
+        for operand in operands {
+            let span = operand.span();
+            let comment = self.comment_between(previous_end, span.start.offset);
+            previous_end = span.end.offset;
+
+            let children = match operand {
+                Expression::Group { expression, span } => {
+                    self.symbols(expression, span.start.offset, false)
+                }
+                _ if top_level => Vec::new(),
+                _ => continue,
+            };
+
+            symbols.push(self.symbol(operand, comment, operator, children));
+        }
+
+        symbols
+    }
+
+    #[allow(deprecated)]
+    fn symbol(
+        &self,
+        operand: &Expression,
+        comment: Option<&Comment>,
+        operator: Option<&BooleanOperator>,
+        children: Vec<DocumentSymbol>,
+    ) -> DocumentSymbol {
+        let span = operand.span();
+        let name = comment
+            .map(|comment| comment.text.trim().to_string())
+            .unwrap_or_else(|| self.excerpt(span));
+        let start = comment.map_or(&span.start, |comment| &comment.span.start);
+
+        DocumentSymbol {
+            name,
+            detail: operator.map(|operator| operator.as_str().to_string()),
+            kind: match operand {
+                Expression::Group { .. } => SymbolKind::NAMESPACE,
+                Expression::Field { .. } | Expression::Range { .. } => SymbolKind::FIELD,
+                Expression::BooleanOp { .. } | Expression::Proximity { .. } => SymbolKind::OPERATOR,
+                Expression::Term { .. } | Expression::Error { .. } => SymbolKind::STRING,
+            },
+            tags: None,
+            deprecated: None,
+            range: self
+                .index
+                .range(&Span::new(start.clone(), span.end.clone())),
+            selection_range: self.index.range(span),
+            children: (!children.is_empty()).then_some(children),
+        }
+    }
+
+    /// the last comment between the chars `start..end`, other than a `<<<bwq: ...>>>` directive
+    fn comment_between(&self, start: usize, end: usize) -> Option<&Comment> {
+        self.comments.iter().rev().find(|comment| {
+            comment.span.start.offset >= start
+                && comment.span.end.offset <= end
+                && !comment.text.trim().is_empty()
+                && !comment.text.trim().starts_with("bwq:")
+        })
+    }
+
+    /// the text of `span` on one line, shortened to [`MAX_NAME_CHARS`]
+    fn excerpt(&self, span: &Span) -> String {
+        let end = span.end.offset.min(self.chars.len());
+        let text: String = self.chars[span.start.offset.min(end)..end]
+            .iter()
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if text.chars().count() > MAX_NAME_CHARS {
+            let short: String = text.chars().take(MAX_NAME_CHARS - 1).collect();
+            format!("{}…", short.trim_end())
+        } else if text.is_empty() {
+            "(empty)".to_string()
+        } else {
+            text
+        }
+    }
+}
+
+/// the operands of a chain of ANDs or ORs, e.g. `a`, `b` and `c` in `a OR b OR c`, with the
+/// operator joining them. anything else is a chain of one
+fn operands(expr: &Expression) -> (Option<&BooleanOperator>, Vec<&Expression>) {
+    let Expression::BooleanOp {
+        operator: operator @ (BooleanOperator::And | BooleanOperator::Or),
+        ..
+    } = expr
+    else {
+        return (None, vec![expr]);
+    };
+
+    let mut operands = Vec::new();
+    let mut stack = vec![expr];
+    while let Some(expr) = stack.pop() {
+        match expr {
+            Expression::BooleanOp {
+                operator: inner,
+                left,
+                right: Some(right),
+                ..
+            } if inner == operator => {
+                stack.push(right);
+                stack.push(left);
+            }
+            _ => operands.push(expr),
+        }
+    }
+
+    (Some(operator), operands)
+}
+
+fn collect_groups(expr: &Expression, ranges: &mut Vec<FoldingRange>) {
+    match expr {
+        Expression::BooleanOp { left, right, .. } => {
+            collect_groups(left, ranges);
+            if let Some(right) = right {
+                collect_groups(right, ranges);
+            }
+        }
+        Expression::Group { expression, span } => {
+            ranges.extend(folding_range(span, None));
+            collect_groups(expression, ranges);
+        }
+        Expression::Proximity { terms, .. } => {
+            for term in terms {
+                collect_groups(term, ranges);
+            }
+        }
+        Expression::Field { value, .. } => collect_groups(value, ranges),
+        Expression::Range { .. } | Expression::Term { .. } | Expression::Error { .. } => {}
+    }
+}
+
+/// folding ranges are whole lines, so positions don't depend on the encoding
+fn folding_range(span: &Span, kind: Option<FoldingRangeKind>) -> Option<FoldingRange> {
+    (span.end.line > span.start.line).then(|| FoldingRange {
+        start_line: span.start.line as u32 - 1,
+        end_line: span.end.line as u32 - 1,
+        kind,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUERY: &str = "<<<brand terms>>>
+(apple OR
+  (juice AND cherry)
+  OR pear)
+OR
+<<<bwq: ignore[W001]>>>
+title:orchard
+OR (
+  banana OR
+  <<<tropical>>>
+  (mango OR
+   papaya)
+)";
+
+    fn names(symbols: &[DocumentSymbol]) -> Vec<&str> {
+        symbols.iter().map(|symbol| symbol.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_document_symbols() {
+        let symbols = document_symbols(QUERY, PositionEncoding::Utf16);
+
+        assert_eq!(
+            names(&symbols),
+            vec![
+                "brand terms",
+                "title:orchard",
+                "( banana OR <<<tropical>>> (mango OR pa…"
+            ]
+        );
+        assert_eq!(symbols[0].kind, SymbolKind::NAMESPACE);
+        assert_eq!(symbols[0].detail.as_deref(), Some("OR"));
+        assert_eq!(symbols[1].kind, SymbolKind::FIELD);
+
+        // the comment is part of the symbol, but the group is what gets selected
+        assert_eq!(symbols[0].range.start.line, 0);
+        assert_eq!(symbols[0].selection_range.start.line, 1);
+        assert_eq!(symbols[0].range.end.line, 3);
+
+        let children = symbols[0].children.as_ref().unwrap();
+        assert_eq!(names(children), vec!["(juice AND cherry)"]);
+        let children = symbols[2].children.as_ref().unwrap();
+        assert_eq!(names(children), vec!["tropical"]);
+        assert_eq!(children[0].range.start.line, 9);
+    }
+
+    #[test]
+    fn test_single_expression() {
+        let symbols = document_symbols("apple AND juice", PositionEncoding::Utf16);
+        assert_eq!(names(&symbols), vec!["apple", "juice"]);
+        assert_eq!(symbols[0].detail.as_deref(), Some("AND"));
+
+        let symbols = document_symbols("apple", PositionEncoding::Utf16);
+        assert_eq!(names(&symbols), vec!["apple"]);
+        assert_eq!(symbols[0].detail, None);
+    }
+
+    #[test]
+    fn test_folding_ranges() {
+        let ranges: Vec<_> = folding_ranges(QUERY)
+            .into_iter()
+            .map(|range| (range.start_line, range.end_line, range.kind))
+            .collect();
+
+        assert_eq!(ranges, vec![(1, 3, None), (7, 12, None), (10, 11, None)]);
+
+        let ranges = folding_ranges("apple <<<a long\nnote>>> OR juice");
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0].kind, Some(FoldingRangeKind::Comment));
+    }
+}
@@ -0,0 +1,317 @@
+use lsp_types::{Range, SemanticToken, SemanticTokenType, SemanticTokensLegend};
+
+use crate::utils::{LineIndex, PositionEncoding};
+use bwq_linter::{
+    ast::{Expression, Term},
+    lexer::{Lexer, Token, TokenType},
+    parser::Parser,
+};
+
+/// the token types in legend order; the standard types come first so clients without the
+/// extension's custom types still colour operators, fields and comments
+pub static TOKEN_TYPES: [SemanticTokenType; 11] = [
+    SemanticTokenType::OPERATOR,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::COMMENT,
+    SemanticTokenType::new("fieldValue"),
+    SemanticTokenType::new("range"),
+    SemanticTokenType::new("wildcard"),
+    SemanticTokenType::new("replacement"),
+    SemanticTokenType::new("caseSensitive"),
+    SemanticTokenType::new("hashtag"),
+    SemanticTokenType::new("mention"),
+    SemanticTokenType::new("emoji"),
+];
+
+/// the kind of a highlighted token, as its index into [`TOKEN_TYPES`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Operator,
+    Field,
+    Comment,
+    FieldValue,
+    Range,
+    Wildcard,
+    Replacement,
+    CaseSensitive,
+    Hashtag,
+    Mention,
+    Emoji,
+}
+
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TOKEN_TYPES.to_vec(),
+        token_modifiers: Vec::new(),
+    }
+}
+
+/// the semantic tokens of a document, limited to the lines of `range` when given
+///
+/// operators and comments come from the lexer; everything else comes from the parsed query, so
+/// a word containing a colon is only a field when the parser recognised the field name
+pub fn semantic_tokens(
+    content: &str,
+    range: Option<Range>,
+    encoding: PositionEncoding,
+) -> Vec<SemanticToken> {
+    let (tokens, _) = Lexer::new(content).tokenize_with_errors();
+    let query = Parser::new(tokens.clone()).parse().query;
+
+    // (start offset, end offset, kind)
+    let mut spans = Vec::new();
+    for token in &tokens {
+        let kind = match token.token_type {
+            TokenType::And
+            | TokenType::Or
+            | TokenType::Not
+            | TokenType::Near(_)
+            | TokenType::NearForward(_)
+            | TokenType::Tilde => Kind::Operator,
+            TokenType::CommentStart | TokenType::CommentText(_) | TokenType::CommentEnd => {
+                Kind::Comment
+            }
+            _ => continue,
+        };
+        spans.push((token.span.start.offset, token.span.end.offset, kind));
+    }
+    collect_expression(&query.expression, false, &tokens, &mut spans);
+
+    spans.sort_by_key(|(start, _, _)| *start);
+    let mut last_end = 0;
+    spans.retain(|(start, end, _)| {
+        let keep = *start >= last_end && start < end;
+        if keep {
+            last_end = *end;
+        }
+        keep
+    });
+
+    encode(content, &spans, range, encoding)
+}
+
+fn collect_expression(
+    expr: &Expression,
+    in_field: bool,
+    tokens: &[Token],
+    spans: &mut Vec<(usize, usize, Kind)>,
+) {
+    match expr {
+        Expression::BooleanOp { left, right, .. } => {
+            collect_expression(left, in_field, tokens, spans);
+            if let Some(right) = right {
+                collect_expression(right, in_field, tokens, spans);
+            }
+        }
+        Expression::Group { expression, .. } => {
+            collect_expression(expression, in_field, tokens, spans);
+        }
+        Expression::Proximity { terms, .. } => {
+            for term in terms {
+                collect_expression(term, in_field, tokens, spans);
+            }
+        }
+        Expression::Field { value, span, .. } => {
+            // the field name runs up to and including the first colon
+            if let Some(colon) = tokens.iter().find(|token| {
+                token.token_type == TokenType::Colon && token.span.start.offset >= span.start.offset
+            }) {
+                spans.push((span.start.offset, colon.span.end.offset, Kind::Field));
+            }
+            collect_expression(value, true, tokens, spans);
+        }
+        Expression::Range { span, .. } => {
+            spans.push((span.start.offset, span.end.offset, Kind::Range));
+        }
+        Expression::Term { term, span } => {
+            let kind = match term {
+                Term::Wildcard { .. } => Kind::Wildcard,
+                Term::Replacement { .. } => Kind::Replacement,
+                Term::CaseSensitive { .. } => Kind::CaseSensitive,
+                Term::Hashtag { .. } => Kind::Hashtag,
+                Term::Mention { .. } => Kind::Mention,
+                Term::Emoji { .. } => Kind::Emoji,
+                // emojis are lexed as words
+                Term::Word { value } if is_emoji(value) => Kind::Emoji,
+                Term::Word { .. } | Term::Phrase { .. } if in_field => Kind::FieldValue,
+                Term::Word { .. } | Term::Phrase { .. } => return,
+            };
+            spans.push((span.start.offset, span.end.offset, kind));
+        }
+        Expression::Error { .. } => {}
+    }
+}
+
+fn is_emoji(word: &str) -> bool {
+    !word.is_ascii() && !word.chars().any(char::is_alphanumeric)
+}
+
+/// delta-encode `spans`, splitting any that cross a line break since clients needn't support
+/// multiline tokens
+fn encode(
+    content: &str,
+    spans: &[(usize, usize, Kind)],
+    range: Option<Range>,
+    encoding: PositionEncoding,
+) -> Vec<SemanticToken> {
+    let chars: Vec<char> = content.chars().collect();
+    let index = LineIndex::new(content, encoding);
+
+    let mut data = Vec::new();
+    let (mut previous_line, mut previous_start) = (0, 0);
+
+    for &(start, end, kind) in spans {
+        let end = end.min(chars.len());
+        let mut piece_start = start;
+
+        while piece_start < end {
+            let piece_end = chars[piece_start..end]
+                .iter()
+                .position(|&ch| ch == '\n')
+                .map_or(end, |newline| piece_start + newline);
+            let start_position = index.position(piece_start);
+            let (line, start_column) = (start_position.line, start_position.character);
+            let length = index.position(piece_end).character - start_column;
+            let in_range =
+                range.is_none_or(|range| (range.start.line..=range.end.line).contains(&line));
+
+            if length > 0 && in_range {
+                let delta_line = line - previous_line;
+                data.push(SemanticToken {
+                    delta_line,
+                    delta_start: if delta_line == 0 {
+                        start_column - previous_start
+                    } else {
+                        start_column
+                    },
+                    length,
+                    token_type: kind as u32,
+                    token_modifiers_bitset: 0,
+                });
+                previous_line = line;
+                previous_start = start_column;
+            }
+
+            piece_start = piece_end + 1;
+        }
+    }
+
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_types::Position as LspPosition;
+
+    /// the tokens as absolute `(line, column, text, type)`
+    fn highlights(content: &str, range: Option<Range>) -> Vec<(u32, u32, String, &'static str)> {
+        let lines: Vec<Vec<char>> = content.lines().map(|l| l.chars().collect()).collect();
+        let (mut line, mut column) = (0, 0);
+
+        semantic_tokens(content, range, PositionEncoding::Utf32)
+            .into_iter()
+            .map(|token| {
+                if token.delta_line > 0 {
+                    column = 0;
+                }
+                line += token.delta_line;
+                column += token.delta_start;
+                let text = lines[line as usize][column as usize..(column + token.length) as usize]
+                    .iter()
+                    .collect();
+                (
+                    line,
+                    column,
+                    text,
+                    TOKEN_TYPES[token.token_type as usize].as_str(),
+                )
+            })
+            .collect()
+    }
+
+    fn texts(content: &str) -> Vec<(String, &'static str)> {
+        highlights(content, None)
+            .into_iter()
+            .map(|(_, _, text, kind)| (text, kind))
+            .collect()
+    }
+
+    fn owned(expected: &[(&str, &'static str)]) -> Vec<(String, &'static str)> {
+        expected
+            .iter()
+            .map(|(text, kind)| (text.to_string(), *kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_fields() {
+        assert_eq!(
+            texts("title:apple AND notafield:value AND rating:[1 TO 3]"),
+            owned(&[
+                ("title:", "property"),
+                ("apple", "fieldValue"),
+                ("AND", "operator"),
+                ("AND", "operator"),
+                ("rating:", "property"),
+                ("[1 TO 3]", "range"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_terms() {
+        assert_eq!(
+            texts("app* OR colo?r OR {BBC} OR #apple OR @apple OR 🍎 NEAR/3 juice"),
+            owned(&[
+                ("app*", "wildcard"),
+                ("OR", "operator"),
+                ("colo?r", "replacement"),
+                ("OR", "operator"),
+                ("{BBC}", "caseSensitive"),
+                ("OR", "operator"),
+                ("#apple", "hashtag"),
+                ("OR", "operator"),
+                ("@apple", "mention"),
+                ("OR", "operator"),
+                ("🍎", "emoji"),
+                ("NEAR/3", "operator"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_multiline_comment() {
+        assert_eq!(
+            highlights("apple <<<first\nsecond>>>\nOR juice", None),
+            vec![
+                (0, 6, "<<<".to_string(), "comment"),
+                (0, 9, "first".to_string(), "comment"),
+                (1, 0, "second".to_string(), "comment"),
+                (1, 6, ">>>".to_string(), "comment"),
+                (2, 0, "OR".to_string(), "operator"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_utf16_columns() {
+        let tokens = semantic_tokens("🍎 OR title:🍎🍎", None, PositionEncoding::Utf16);
+        let columns: Vec<_> = tokens
+            .iter()
+            .map(|token| (token.delta_start, token.length))
+            .collect();
+
+        assert_eq!(columns, vec![(0, 2), (3, 2), (3, 6), (6, 4)]);
+    }
+
+    #[test]
+    fn test_range() {
+        let range = Range::new(LspPosition::new(1, 0), LspPosition::new(1, 10));
+
+        assert_eq!(
+            highlights("apple AND\njuice OR cherry\nAND pear", Some(range)),
+            vec![(1, 6, "OR".to_string(), "operator")]
+        );
+    }
+}
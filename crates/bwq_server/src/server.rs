@@ -3,24 +3,47 @@ use std::collections::HashMap;
 use anyhow::Result;
 use lsp_server::{Connection, Message, Notification, Request, Response};
 use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOptions, CodeActionOrCommand, CodeActionParams,
+    CodeActionProviderCapability, CompletionOptions, CompletionParams, CompletionResponse,
     DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
-    InitializeParams, InitializeResult, PublishDiagnosticsParams, ServerCapabilities,
-    TextDocumentSyncCapability, TextDocumentSyncKind, Uri,
+    DocumentFormattingParams, DocumentSymbolParams, DocumentSymbolResponse, FoldingRange,
+    FoldingRangeParams, FoldingRangeProviderCapability, Hover, HoverParams,
+    HoverProviderCapability, InitializeParams, InitializeResult, OneOf, Range, SemanticTokens,
+    SemanticTokensFullOptions, SemanticTokensOptions, SemanticTokensParams,
+    SemanticTokensRangeParams, SemanticTokensRangeResult, SemanticTokensResult,
+    SemanticTokensServerCapabilities, ServerCapabilities, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextEdit, Uri,
     notification::{
         DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
-        Notification as NotificationTrait, PublishDiagnostics,
+        Notification as NotificationTrait,
+    },
+    request::{
+        CodeActionRequest, Completion, DocumentSymbolRequest, FoldingRangeRequest, Formatting,
+        HoverRequest, Initialize, Request as RequestTrait, SemanticTokensFullRequest,
+        SemanticTokensRangeRequest, Shutdown,
     },
-    request::{Initialize, Request as RequestTrait, Shutdown},
 };
 
+use crate::completion::completions;
 use crate::diagnostics_handler::DiagnosticsHandler;
-use bwq_linter::BrandwatchLinter;
+use crate::hover::hover;
+use crate::lint_worker::LintWorker;
+use crate::outline::{document_symbols, folding_ranges};
+use crate::semantic_tokens::{legend, semantic_tokens};
+use crate::utils::{LineIndex, PositionEncoding, apply_change, uri_to_path};
+use bwq_linter::{
+    BrandwatchLinter,
+    config::ConfigResolver,
+    formatter::{FormatOptions, format_source},
+};
 
 pub struct Server {
     connection: Connection,
-    linter: BrandwatchLinter,
     documents: HashMap<Uri, DocumentState>,
     diagnostics_handler: DiagnosticsHandler,
+    lint_worker: LintWorker,
+    /// the unit the client counts characters in
+    encoding: PositionEncoding,
 }
 
 #[derive(Debug, Clone)]
@@ -30,12 +53,15 @@ struct DocumentState {
 }
 
 impl Server {
-    pub fn new(connection: Connection) -> Self {
+    pub fn new(connection: Connection, encoding: PositionEncoding) -> Self {
+        let lint_worker = LintWorker::spawn(connection.sender.clone(), encoding);
+
         Self {
             connection,
-            linter: BrandwatchLinter::new(),
             documents: HashMap::new(),
-            diagnostics_handler: DiagnosticsHandler::new(),
+            diagnostics_handler: DiagnosticsHandler::with_encoding(encoding),
+            lint_worker,
+            encoding,
         }
     }
 
@@ -43,13 +69,43 @@ impl Server {
         let (connection, io_threads) = Connection::stdio();
 
         let (initialize_id, initialize_params) = connection.initialize_start()?;
-        let _initialize_params: InitializeParams = serde_json::from_value(initialize_params)?;
+        let initialize_params: InitializeParams = serde_json::from_value(initialize_params)?;
+        let encoding = PositionEncoding::negotiate(&initialize_params.capabilities);
 
         let initialize_result = InitializeResult {
             capabilities: ServerCapabilities {
+                position_encoding: Some(encoding.kind()),
                 text_document_sync: Some(TextDocumentSyncCapability::Kind(
-                    TextDocumentSyncKind::FULL,
+                    TextDocumentSyncKind::INCREMENTAL,
+                )),
+                document_formatting_provider: Some(OneOf::Left(true)),
+                code_action_provider: Some(CodeActionProviderCapability::Options(
+                    CodeActionOptions {
+                        code_action_kinds: Some(vec![
+                            CodeActionKind::QUICKFIX,
+                            CodeActionKind::REFACTOR_EXTRACT,
+                            CodeActionKind::REFACTOR_REWRITE,
+                        ]),
+                        ..Default::default()
+                    },
                 )),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                document_symbol_provider: Some(OneOf::Left(true)),
+                folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+                completion_provider: Some(CompletionOptions {
+                    trigger_characters: Some(vec![":".to_string()]),
+                    ..Default::default()
+                }),
+                semantic_tokens_provider: Some(
+                    SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        SemanticTokensOptions {
+                            legend: legend(),
+                            range: Some(true),
+                            full: Some(SemanticTokensFullOptions::Bool(true)),
+                            ..Default::default()
+                        },
+                    ),
+                ),
                 ..Default::default()
             },
             server_info: Some(lsp_types::ServerInfo {
@@ -60,9 +116,11 @@ impl Server {
 
         connection.initialize_finish(initialize_id, serde_json::to_value(initialize_result)?)?;
 
-        let mut server = Server::new(connection);
+        let mut server = Server::new(connection, encoding);
         server.main_loop()?;
 
+        // the io threads finish once every sender to the client, including the worker's, is gone
+        drop(server);
         io_threads.join()?;
         Ok(())
     }
@@ -93,6 +151,54 @@ impl Server {
         match req.method.as_str() {
             <Initialize as RequestTrait>::METHOD => {}
             <Shutdown as RequestTrait>::METHOD => {}
+            <Formatting as RequestTrait>::METHOD => {
+                let params: DocumentFormattingParams = serde_json::from_value(req.params)?;
+                let edits = self.handle_formatting(params);
+                let response = Response::new_ok(req.id, edits);
+                self.connection.sender.send(Message::Response(response))?;
+            }
+            <HoverRequest as RequestTrait>::METHOD => {
+                let params: HoverParams = serde_json::from_value(req.params)?;
+                let hover = self.handle_hover(params);
+                let response = Response::new_ok(req.id, hover);
+                self.connection.sender.send(Message::Response(response))?;
+            }
+            <Completion as RequestTrait>::METHOD => {
+                let params: CompletionParams = serde_json::from_value(req.params)?;
+                let items = self.handle_completion(params);
+                let response = Response::new_ok(req.id, items);
+                self.connection.sender.send(Message::Response(response))?;
+            }
+            <CodeActionRequest as RequestTrait>::METHOD => {
+                let params: CodeActionParams = serde_json::from_value(req.params)?;
+                let actions = self.handle_code_action(params);
+                let response = Response::new_ok(req.id, actions);
+                self.connection.sender.send(Message::Response(response))?;
+            }
+            <SemanticTokensFullRequest as RequestTrait>::METHOD => {
+                let params: SemanticTokensParams = serde_json::from_value(req.params)?;
+                let tokens = self.handle_semantic_tokens(params);
+                let response = Response::new_ok(req.id, tokens);
+                self.connection.sender.send(Message::Response(response))?;
+            }
+            <SemanticTokensRangeRequest as RequestTrait>::METHOD => {
+                let params: SemanticTokensRangeParams = serde_json::from_value(req.params)?;
+                let tokens = self.handle_semantic_tokens_range(params);
+                let response = Response::new_ok(req.id, tokens);
+                self.connection.sender.send(Message::Response(response))?;
+            }
+            <DocumentSymbolRequest as RequestTrait>::METHOD => {
+                let params: DocumentSymbolParams = serde_json::from_value(req.params)?;
+                let symbols = self.handle_document_symbols(params);
+                let response = Response::new_ok(req.id, symbols);
+                self.connection.sender.send(Message::Response(response))?;
+            }
+            <FoldingRangeRequest as RequestTrait>::METHOD => {
+                let params: FoldingRangeParams = serde_json::from_value(req.params)?;
+                let ranges = self.handle_folding_ranges(params);
+                let response = Response::new_ok(req.id, ranges);
+                self.connection.sender.send(Message::Response(response))?;
+            }
             _ => {
                 let response = Response::new_err(
                     req.id,
@@ -139,12 +245,14 @@ impl Server {
         };
 
         self.documents.insert(doc.uri.clone(), document_state);
-        self.publish_diagnostics(&doc.uri, &doc.text)?;
+        self.lint_worker
+            .lint(doc.uri.clone(), doc.text, doc.version);
 
         eprintln!("Opened document: {:?}", doc.uri);
         Ok(())
     }
 
+    /// apply the edits in order, then queue the new version for linting
     fn handle_did_change(&mut self, params: DidChangeTextDocumentParams) -> Result<()> {
         let uri = params.text_document.uri;
 
@@ -153,12 +261,13 @@ impl Server {
         }
 
         if let Some(document) = self.documents.get_mut(&uri) {
-            if let Some(change) = params.content_changes.into_iter().next() {
-                document.content = change.text.clone();
-                document.version = params.text_document.version;
-
-                self.publish_diagnostics(&uri, &change.text)?;
+            for change in params.content_changes {
+                apply_change(&mut document.content, change, self.encoding);
             }
+            document.version = params.text_document.version;
+
+            self.lint_worker
+                .lint(uri, document.content.clone(), document.version);
         }
 
         Ok(())
@@ -167,46 +276,148 @@ impl Server {
     fn handle_did_close(&mut self, params: DidCloseTextDocumentParams) -> Result<()> {
         let uri = params.text_document.uri;
         self.documents.remove(&uri);
+        self.lint_worker.close(uri.clone());
+
+        eprintln!("Closed document: {uri:?}");
+        Ok(())
+    }
+
+    /// replace the whole document with its formatted text. `None` when the document is unknown,
+    /// already formatted, or has syntax errors (which are reported as diagnostics instead)
+    fn handle_formatting(&self, params: DocumentFormattingParams) -> Option<Vec<TextEdit>> {
+        let document = self.documents.get(&params.text_document.uri)?;
 
-        let diagnostics = PublishDiagnosticsParams {
-            uri: uri.clone(),
-            diagnostics: vec![],
-            version: None,
+        let options = FormatOptions {
+            indent_width: params.options.tab_size as usize,
+            ..FormatOptions::default()
         };
+        let formatted = format_source(&document.content, &options).ok()?;
 
-        let notification = Notification::new(
-            <PublishDiagnostics as NotificationTrait>::METHOD.to_string(),
-            serde_json::to_value(diagnostics)?,
-        );
+        if formatted == document.content {
+            return None;
+        }
 
-        self.connection
-            .sender
-            .send(Message::Notification(notification))?;
+        Some(vec![TextEdit {
+            range: Range {
+                start: lsp_types::Position::new(0, 0),
+                end: LineIndex::new(&document.content, self.encoding).end(),
+            },
+            new_text: formatted,
+        }])
+    }
 
-        eprintln!("Closed document: {uri:?}");
-        Ok(())
+    fn handle_hover(&self, params: HoverParams) -> Option<Hover> {
+        let position = params.text_document_position_params;
+        let uri = &position.text_document.uri;
+        let document = self.documents.get(uri)?;
+
+        hover(
+            &document.content,
+            position.position,
+            &mut linter_for(uri),
+            self.encoding,
+        )
+    }
+
+    fn handle_completion(&self, params: CompletionParams) -> Option<CompletionResponse> {
+        let position = params.text_document_position;
+        let document = self.documents.get(&position.text_document.uri)?;
+
+        Some(CompletionResponse::Array(completions(
+            &document.content,
+            position.position,
+            self.encoding,
+        )))
     }
 
-    fn publish_diagnostics(&mut self, uri: &Uri, content: &str) -> Result<()> {
-        let diagnostics = self
-            .diagnostics_handler
-            .analyze_content(content, &mut self.linter)?;
+    fn handle_semantic_tokens(&self, params: SemanticTokensParams) -> Option<SemanticTokensResult> {
+        let document = self.documents.get(&params.text_document.uri)?;
+
+        Some(SemanticTokensResult::Tokens(SemanticTokens {
+            result_id: None,
+            data: semantic_tokens(&document.content, None, self.encoding),
+        }))
+    }
+
+    fn handle_semantic_tokens_range(
+        &self,
+        params: SemanticTokensRangeParams,
+    ) -> Option<SemanticTokensRangeResult> {
+        let document = self.documents.get(&params.text_document.uri)?;
+
+        Some(SemanticTokensRangeResult::Tokens(SemanticTokens {
+            result_id: None,
+            data: semantic_tokens(&document.content, Some(params.range), self.encoding),
+        }))
+    }
 
-        let publish_diagnostics = PublishDiagnosticsParams {
-            uri: uri.clone(),
-            diagnostics,
-            version: None,
+    fn handle_document_symbols(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Option<DocumentSymbolResponse> {
+        let document = self.documents.get(&params.text_document.uri)?;
+
+        Some(DocumentSymbolResponse::Nested(document_symbols(
+            &document.content,
+            self.encoding,
+        )))
+    }
+
+    fn handle_folding_ranges(&self, params: FoldingRangeParams) -> Option<Vec<FoldingRange>> {
+        let document = self.documents.get(&params.text_document.uri)?;
+
+        Some(folding_ranges(&document.content))
+    }
+
+    /// quick fixes for the diagnostics in the requested range, found by linting the document
+    /// again so the edits always match its current content
+    fn handle_code_action(&self, params: CodeActionParams) -> Vec<CodeActionOrCommand> {
+        let uri = &params.text_document.uri;
+        let Some(document) = self.documents.get(uri) else {
+            return Vec::new();
         };
 
-        let notification = Notification::new(
-            <PublishDiagnostics as NotificationTrait>::METHOD.to_string(),
-            serde_json::to_value(publish_diagnostics)?,
+        let mut linter = linter_for(uri);
+        let mut actions = self.diagnostics_handler.code_actions(
+            uri,
+            &document.content,
+            params.range,
+            &mut linter,
         );
 
-        self.connection
-            .sender
-            .send(Message::Notification(notification))?;
-        Ok(())
+        // a client asking for particular kinds also accepts their sub-kinds
+        if let Some(only) = &params.context.only {
+            actions.retain(|action| {
+                let CodeActionOrCommand::CodeAction(CodeAction {
+                    kind: Some(kind), ..
+                }) = action
+                else {
+                    return false;
+                };
+                only.iter().any(|wanted| {
+                    kind.as_str() == wanted.as_str()
+                        || kind.as_str().starts_with(&format!("{}.", wanted.as_str()))
+                })
+            });
+        }
+
+        actions
+    }
+}
+
+/// a linter set up from the config that applies to the document. the config is looked up again
+/// every time so edits to it are picked up without restarting the server
+pub(crate) fn linter_for(uri: &Uri) -> BrandwatchLinter {
+    let Some(path) = uri_to_path(uri) else {
+        return BrandwatchLinter::new();
+    };
+
+    match ConfigResolver::new().settings_for(&path) {
+        Ok(settings) => BrandwatchLinter::with_settings(settings),
+        Err(e) => {
+            eprintln!("Ignoring invalid configuration: {e}");
+            BrandwatchLinter::new()
+        }
     }
 }
 
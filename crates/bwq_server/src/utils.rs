@@ -1,40 +1,299 @@
-use lsp_types::{Position as LspPosition, Range as LspRange};
+use std::path::PathBuf;
 
-use bwq_linter::error::{Position, Span};
+use lsp_types::{
+    ClientCapabilities, Position as LspPosition, PositionEncodingKind, Range as LspRange,
+    TextDocumentContentChangeEvent, Uri,
+};
 
-pub fn position_to_lsp(pos: &Position) -> LspPosition {
-    LspPosition {
-        line: pos.line.saturating_sub(1) as u32,
-        character: pos.column.saturating_sub(1) as u32,
+use bwq_linter::error::Span;
+
+/// the unit LSP positions count characters in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PositionEncoding {
+    Utf8,
+    /// the default, and the only encoding clients are required to support
+    #[default]
+    Utf16,
+    Utf32,
+}
+
+impl PositionEncoding {
+    /// the first encoding in the client's order of preference that the server supports
+    pub fn negotiate(capabilities: &ClientCapabilities) -> Self {
+        capabilities
+            .general
+            .as_ref()
+            .and_then(|general| general.position_encodings.as_ref())
+            .and_then(|encodings| encodings.iter().find_map(Self::from_kind))
+            .unwrap_or_default()
+    }
+
+    fn from_kind(kind: &PositionEncodingKind) -> Option<Self> {
+        match kind.as_str() {
+            "utf-8" => Some(Self::Utf8),
+            "utf-16" => Some(Self::Utf16),
+            "utf-32" => Some(Self::Utf32),
+            _ => None,
+        }
+    }
+
+    pub fn kind(&self) -> PositionEncodingKind {
+        match self {
+            Self::Utf8 => PositionEncodingKind::UTF8,
+            Self::Utf16 => PositionEncodingKind::UTF16,
+            Self::Utf32 => PositionEncodingKind::UTF32,
+        }
     }
+
+    /// the number of units `ch` takes up
+    fn len(&self, ch: char) -> u32 {
+        match self {
+            Self::Utf8 => ch.len_utf8() as u32,
+            Self::Utf16 => ch.len_utf16() as u32,
+            Self::Utf32 => 1,
+        }
+    }
+}
+
+/// converts between the char offsets in linter spans and LSP positions for one document
+pub struct LineIndex {
+    chars: Vec<char>,
+    /// the char offset each line starts at
+    line_starts: Vec<usize>,
+    encoding: PositionEncoding,
 }
 
-pub fn span_to_range(span: &Span) -> LspRange {
-    LspRange {
-        start: position_to_lsp(&span.start),
-        end: position_to_lsp(&span.end),
+impl LineIndex {
+    pub fn new(content: &str, encoding: PositionEncoding) -> Self {
+        let chars: Vec<char> = content.chars().collect();
+        let line_starts = std::iter::once(0)
+            .chain(
+                chars
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, ch)| **ch == '\n')
+                    .map(|(index, _)| index + 1),
+            )
+            .collect();
+
+        Self {
+            chars,
+            line_starts,
+            encoding,
+        }
+    }
+
+    /// the LSP position of a char offset
+    pub fn position(&self, offset: usize) -> LspPosition {
+        let offset = offset.min(self.chars.len());
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let character = self.chars[self.line_starts[line]..offset]
+            .iter()
+            .map(|&ch| self.encoding.len(ch))
+            .sum();
+
+        LspPosition::new(line as u32, character)
+    }
+
+    pub fn range(&self, span: &Span) -> LspRange {
+        LspRange::new(
+            self.position(span.start.offset),
+            self.position(span.end.offset),
+        )
+    }
+
+    /// the char offset of an LSP position, clamped to the end of its line
+    pub fn offset(&self, position: LspPosition) -> usize {
+        let Some(&start) = self.line_starts.get(position.line as usize) else {
+            return self.chars.len();
+        };
+        let end = self
+            .line_starts
+            .get(position.line as usize + 1)
+            .map_or(self.chars.len(), |next| next - 1);
+
+        let mut units = 0;
+        for offset in start..end {
+            if units >= position.character {
+                return offset;
+            }
+            units += self.encoding.len(self.chars[offset]);
+        }
+
+        end
+    }
+
+    /// position just past the last character of the document
+    pub fn end(&self) -> LspPosition {
+        self.position(self.chars.len())
     }
 }
 
+/// apply a `textDocument/didChange` edit, which replaces the whole text when it has no range
+pub fn apply_change(
+    content: &mut String,
+    change: TextDocumentContentChangeEvent,
+    encoding: PositionEncoding,
+) {
+    let Some(range) = change.range else {
+        *content = change.text;
+        return;
+    };
+
+    let index = LineIndex::new(content, encoding);
+    let start = index.offset(range.start);
+    let end = index.offset(range.end).max(start);
+    let byte_offset = |offset: usize| {
+        content
+            .char_indices()
+            .nth(offset)
+            .map_or(content.len(), |(index, _)| index)
+    };
+
+    let (start, end) = (byte_offset(start), byte_offset(end));
+    content.replace_range(start..end, &change.text);
+}
+
+/// the file system path of a `file:` uri
+pub fn uri_to_path(uri: &Uri) -> Option<PathBuf> {
+    if uri.scheme().is_none_or(|scheme| scheme.as_str() != "file") {
+        return None;
+    }
+
+    let path = uri.path().as_estr().decode().into_string_lossy();
+    Some(PathBuf::from(path.as_ref()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bwq_linter::error::Position;
+    use lsp_types::GeneralClientCapabilities;
 
     #[test]
-    fn test_position_conversion() {
-        let pos = Position::new(1, 1, 0);
-        let lsp_pos = position_to_lsp(&pos);
-        assert_eq!(lsp_pos.line, 0);
-        assert_eq!(lsp_pos.character, 0)
+    fn test_span_conversion() {
+        let index = LineIndex::new("apple OR juice", PositionEncoding::Utf16);
+        let span = Span::new(Position::new(1, 1, 0), Position::new(1, 5, 4));
+        let range = index.range(&span);
+        assert_eq!(range.start, LspPosition::new(0, 0));
+        assert_eq!(range.end, LspPosition::new(0, 4));
     }
 
     #[test]
-    fn test_span_conversion() {
-        let span = Span::new(Position::new(1, 1, 0), Position::new(1, 5, 4));
-        let range = span_to_range(&span);
-        assert_eq!(range.start.line, 0);
-        assert_eq!(range.start.character, 0);
-        assert_eq!(range.end.line, 0);
-        assert_eq!(range.end.character, 4);
+    fn test_emoji_positions() {
+        // 🍎 is one char, two UTF-16 code units and four UTF-8 bytes
+        let content = "🍎🍎 OR juice\n👍 AND é";
+        let juice = 6;
+        let and = 14;
+
+        for (encoding, juice_character, and_character) in [
+            (PositionEncoding::Utf8, 12, 5),
+            (PositionEncoding::Utf16, 8, 3),
+            (PositionEncoding::Utf32, 6, 2),
+        ] {
+            let index = LineIndex::new(content, encoding);
+            assert_eq!(index.position(juice), LspPosition::new(0, juice_character));
+            assert_eq!(index.position(and), LspPosition::new(1, and_character));
+            assert_eq!(index.offset(LspPosition::new(0, juice_character)), juice);
+            assert_eq!(index.offset(LspPosition::new(1, and_character)), and);
+        }
+    }
+
+    #[test]
+    fn test_offset() {
+        let index = LineIndex::new("apple\nOR juice", PositionEncoding::Utf16);
+        assert_eq!(index.offset(LspPosition::new(0, 3)), 3);
+        assert_eq!(index.offset(LspPosition::new(1, 0)), 6);
+        assert_eq!(index.offset(LspPosition::new(0, 40)), 5);
+        assert_eq!(index.offset(LspPosition::new(7, 0)), 14);
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let capabilities = |encodings: Option<Vec<PositionEncodingKind>>| ClientCapabilities {
+            general: Some(GeneralClientCapabilities {
+                position_encodings: encodings,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            PositionEncoding::negotiate(&ClientCapabilities::default()),
+            PositionEncoding::Utf16
+        );
+        assert_eq!(
+            PositionEncoding::negotiate(&capabilities(Some(vec![
+                PositionEncodingKind::new("utf-7"),
+                PositionEncodingKind::UTF8,
+                PositionEncodingKind::UTF16,
+            ]))),
+            PositionEncoding::Utf8
+        );
+        assert_eq!(
+            PositionEncoding::negotiate(&capabilities(None)),
+            PositionEncoding::Utf16
+        );
+    }
+
+    #[test]
+    fn test_uri_to_path() {
+        let uri: Uri = "file:///home/user/my%20queries/brand.bwq".parse().unwrap();
+        assert_eq!(
+            uri_to_path(&uri),
+            Some(PathBuf::from("/home/user/my queries/brand.bwq"))
+        );
+
+        let uri: Uri = "untitled:Untitled-1".parse().unwrap();
+        assert_eq!(uri_to_path(&uri), None);
+    }
+
+    #[test]
+    fn test_apply_change() {
+        let change = |range: Option<LspRange>, text: &str| TextDocumentContentChangeEvent {
+            range,
+            range_length: None,
+            text: text.to_string(),
+        };
+        let range = |start: (u32, u32), end: (u32, u32)| {
+            Some(LspRange::new(
+                LspPosition::new(start.0, start.1),
+                LspPosition::new(end.0, end.1),
+            ))
+        };
+
+        let mut content = "🍎 OR apple\njuice".to_string();
+        apply_change(
+            &mut content,
+            change(range((0, 3), (0, 5)), "AND"),
+            PositionEncoding::Utf16,
+        );
+        assert_eq!(content, "🍎 AND apple\njuice");
+
+        apply_change(
+            &mut content,
+            change(range((0, 12), (1, 0)), " OR "),
+            PositionEncoding::Utf16,
+        );
+        assert_eq!(content, "🍎 AND apple OR juice");
+
+        apply_change(
+            &mut content,
+            change(range((0, 2), (0, 5)), "OR"),
+            PositionEncoding::Utf32,
+        );
+        assert_eq!(content, "🍎 OR apple OR juice");
+
+        apply_change(&mut content, change(None, "cherry"), PositionEncoding::Utf8);
+        assert_eq!(content, "cherry");
+    }
+
+    #[test]
+    fn test_document_end() {
+        let end = |content: &str| LineIndex::new(content, PositionEncoding::Utf16).end();
+        assert_eq!(end(""), LspPosition::new(0, 0));
+        assert_eq!(end("apple OR juice"), LspPosition::new(0, 14));
+        assert_eq!(end("apple\nOR juice\n"), LspPosition::new(2, 0));
+        assert_eq!(end("apple 🍎"), LspPosition::new(0, 8));
     }
 }